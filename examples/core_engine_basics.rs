@@ -1,36 +1,31 @@
+use std::time::Duration;
+
 use wolf_engine::prelude::*;
 
-pub struct GameData {
+pub struct MyGame {
     pub number: i32,
 }
 
-pub fn main() {
-    let (mut event_loop, mut context) = wolf_engine::init(GameData { number: 0 });
-
-    while let Some(event) = event_loop.next_event() {
-        process_event(event, &mut context);
+impl Game for MyGame {
+    fn update(&mut self, context: &mut Context<()>, _delta: Duration) {
+        if self.number == 3 {
+            context.quit();
+        } else {
+            self.number += 1;
+        }
     }
-}
 
-pub fn process_event(event: Event, context: &mut Context<GameData>) {
-    match event {
-        Event::EventsCleared => {
-            update(context);
-            display(context);
-        }
-        Event::Quit => println!("Quit event received.  Goodbye!"),
-        _ => (),
+    fn render(&mut self, _context: &mut Context<()>, _alpha: f32) {
+        println!("{}", self.number);
     }
-}
 
-pub fn update(context: &mut Context<GameData>) {
-    if context.data.number == 3 {
-        context.quit();
-    } else {
-        context.data.number += 1;
+    fn on_event(&mut self, event: Event, _context: &mut Context<()>) {
+        if event == Event::Quit {
+            println!("Quit event received.  Goodbye!");
+        }
     }
 }
 
-pub fn display(context: &mut Context<GameData>) {
-    println!("{}", context.data.number);
+pub fn main() -> Result<(), RunError> {
+    wolf_engine::framework::run(MyGame { number: 0 })
 }
@@ -35,9 +35,11 @@
 //!
 //! ## Crate Features
 //!
+//! - `config`: Enable loading engine configuration from TOML files, and the command-line.
 //! - `framework`: Enable the high-level framework (enabled by default.)
 //! - `logging`: Enable built-in logging implementation.
 //! - `serde`: Enable [Serde](https://crates.io.crates/serde) support for some types.
+//! - `testing`: Enable the headless test harness.
 //! - `window`: Enable Wolf Engine's high-level window API.
 //!
 //! ## Usage Examples
@@ -54,9 +56,15 @@ pub mod framework {
     pub use wolf_engine_framework::*;
 }
 
+#[cfg(feature = "config")]
+pub use wolf_engine_core::{cli, config};
+
 #[cfg(feature = "logging")]
 pub use wolf_engine_core::logging;
 
+#[cfg(feature = "testing")]
+pub use wolf_engine_core::testing;
+
 #[cfg(feature = "window")]
 pub mod window {
     //! Provides a high-level, back-end agnostic window API.
@@ -0,0 +1,333 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crate::events::*;
+use crate::{Context, ControlFlow, Engine};
+
+/// A unique identifier for a child engine spawned by a [`Context`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct ChildEngineId(u64);
+
+impl ChildEngineId {
+    /// Returns the raw id number.
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A handle used to communicate with a child engine.
+///
+/// Handles are cheap to clone.  Events sent through the handle are received by the child
+/// engine's Event-Loop.
+#[derive(Clone)]
+pub struct ChildEngineHandle {
+    id: ChildEngineId,
+    event_sender: Arc<dyn EventSender<Event>>,
+}
+
+impl ChildEngineHandle {
+    /// Returns the child engine's id.
+    pub fn id(&self) -> ChildEngineId {
+        self.id
+    }
+
+    /// Tells the child engine to quit.
+    pub fn quit(&self) {
+        self.event_sender.send_event(Event::Quit).ok();
+    }
+}
+
+impl HasEventSender<Event> for ChildEngineHandle {
+    fn event_sender(&self) -> Arc<dyn EventSender<Event>> {
+        self.event_sender.clone()
+    }
+}
+
+/// Provides a child engine with a link back to its parent.
+///
+/// This is inserted as a [resource](Context::resource()) into the child engine's [`Context`], so
+/// the child can send events to the parent.
+#[derive(Clone)]
+pub struct ParentEngine {
+    child_id: ChildEngineId,
+    event_sender: Arc<dyn EventSender<Event>>,
+}
+
+impl ParentEngine {
+    /// Returns the id the parent uses for this child engine.
+    pub fn child_id(&self) -> ChildEngineId {
+        self.child_id
+    }
+}
+
+impl HasEventSender<Event> for ParentEngine {
+    fn event_sender(&self) -> Arc<dyn EventSender<Event>> {
+        self.event_sender.clone()
+    }
+}
+
+struct ChildEngine {
+    handle: ChildEngineHandle,
+    thread: JoinHandle<()>,
+}
+
+/// Stores the child engines owned by the [`Context`].
+pub(crate) struct ChildEngines {
+    children: Vec<ChildEngine>,
+    next_id: u64,
+}
+
+impl ChildEngines {
+    pub fn new() -> Self {
+        Self {
+            children: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<D> Context<D> {
+    /// Spawns a child engine, which runs its own Event-Loop on a new thread.
+    ///
+    /// The child engine is created on the new thread by the `init` function, so its context
+    /// data does not need to be [`Send`].  It is then run using
+    /// [`EventLoop::run()`](crate::EventLoop::run()) with the provided event handler.  A
+    /// [`ParentEngine`] resource is inserted into the child's Context, so it can send events back
+    /// to the parent.
+    ///
+    /// The parent receives [`ChildEngineEvent`]s as the child starts, and exits.  When the parent
+    /// [shuts down](Context::shutdown()), its children are quit, and joined, one at a time, in
+    /// the order they were spawned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wolf_engine_core as wolf_engine;
+    /// use wolf_engine::prelude::*;
+    ///
+    /// let (mut event_loop, mut context) = wolf_engine::init(());
+    ///
+    /// let child = context.spawn_child_engine(
+    ///     || wolf_engine::init(0),
+    ///     |event, context, control_flow| {
+    ///         if event == Event::EventsCleared {
+    ///             context.data += 1;
+    ///             if context.data == 3 {
+    ///                 *control_flow = ControlFlow::Exit(3);
+    ///             }
+    ///         }
+    ///     },
+    /// );
+    ///
+    /// while let Some(event) = event_loop.next_event() {
+    ///     if let Event::ChildEngine(ChildEngineEvent::Exited(id, exit_code)) = event {
+    ///         assert_eq!(id, child.id());
+    ///         assert_eq!(exit_code, 3);
+    ///         context.quit();
+    ///     }
+    /// }
+    /// context.shutdown();
+    /// ```
+    pub fn spawn_child_engine<CD, I, F>(
+        &mut self,
+        init: I,
+        mut event_handler: F,
+    ) -> ChildEngineHandle
+    where
+        CD: 'static,
+        I: FnOnce() -> Engine<CD> + Send + 'static,
+        F: FnMut(Event, &mut Context<CD>, &mut ControlFlow) + Send + 'static,
+    {
+        let id = ChildEngineId(self.child_engines.next_id);
+        self.child_engines.next_id += 1;
+        let parent = ParentEngine {
+            child_id: id,
+            event_sender: self.event_sender(),
+        };
+        let (sender_sender, sender_receiver) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name(format!("wolf_engine_child_{}", id.0))
+            .spawn(move || {
+                let parent_sender = parent.event_sender();
+                let result = panic::catch_unwind(AssertUnwindSafe(move || {
+                    let (event_loop, mut context) = init();
+                    sender_sender.send(context.event_sender()).ok();
+                    context.insert_resource(parent.clone());
+                    parent
+                        .event_sender
+                        .send_event(Event::ChildEngine(ChildEngineEvent::Started(id)))
+                        .ok();
+                    event_loop.run(context, move |event, context, control_flow| {
+                        event_handler(event, context, control_flow)
+                    })
+                }));
+                let event = match result {
+                    Ok(exit_code) => ChildEngineEvent::Exited(id, exit_code),
+                    Err(_) => ChildEngineEvent::Panicked(id),
+                };
+                parent_sender.send_event(Event::ChildEngine(event)).ok();
+            })
+            .expect("Failed to spawn a child engine thread");
+        let event_sender = sender_receiver
+            .recv()
+            .unwrap_or_else(|_| Arc::new(DisconnectedEventSender));
+        let handle = ChildEngineHandle { id, event_sender };
+        self.child_engines.children.push(ChildEngine {
+            handle: handle.clone(),
+            thread,
+        });
+        handle
+    }
+
+    /// Returns handles to the child engines which have not been shut down.
+    pub fn child_engines(&self) -> Vec<ChildEngineHandle> {
+        self.child_engines
+            .children
+            .iter()
+            .map(|child| child.handle.clone())
+            .collect()
+    }
+
+    /// Quits, and joins, every child engine, one at a time, in the order they were spawned.
+    pub(crate) fn shut_down_child_engines(&mut self) {
+        for child in self.child_engines.children.drain(..) {
+            child.handle.quit();
+            child.thread.join().ok();
+        }
+    }
+}
+
+/// Used when a child engine panics before its event sender is created.
+struct DisconnectedEventSender;
+
+impl EventSender<Event> for DisconnectedEventSender {
+    fn send_event(&self, _event: Event) -> Result<(), String> {
+        Err("The child engine is not running".to_string())
+    }
+}
+
+#[cfg(test)]
+mod child_engine_tests {
+    use std::sync::{Arc, Mutex};
+
+    use ntest::timeout;
+
+    use crate::prelude::*;
+
+    fn collect_child_events(
+        event_loop: &mut EventLoop,
+        context: &mut Context<()>,
+        expected: usize,
+    ) -> Vec<ChildEngineEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = event_loop.next_event() {
+            if let Event::ChildEngine(child_event) = event {
+                events.push(child_event);
+                if events.len() == expected {
+                    context.quit();
+                }
+            }
+        }
+        events
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_report_the_child_exit_code() {
+        let (mut event_loop, mut context) = crate::init(());
+        let child = context.spawn_child_engine(
+            || crate::init(()),
+            |event, _context, control_flow| {
+                if event == Event::EventsCleared {
+                    *control_flow = ControlFlow::Exit(7);
+                }
+            },
+        );
+
+        let events = collect_child_events(&mut event_loop, &mut context, 2);
+
+        assert_eq!(
+            events,
+            vec![
+                ChildEngineEvent::Started(child.id()),
+                ChildEngineEvent::Exited(child.id(), 7)
+            ]
+        );
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_send_events_between_parent_and_child() {
+        let (mut event_loop, mut context) = crate::init(());
+        let child = context.spawn_child_engine(
+            || crate::init(()),
+            |event, context, _control_flow| {
+                if let Event::FramePhase(FramePhase::Custom("Ping")) = event {
+                    let parent = context.resource::<ParentEngine>().unwrap();
+                    parent
+                        .event_sender()
+                        .send_event(Event::FramePhase(FramePhase::Custom("Pong")))
+                        .unwrap();
+                }
+            },
+        );
+
+        child
+            .event_sender()
+            .send_event(Event::FramePhase(FramePhase::Custom("Ping")))
+            .unwrap();
+        while let Some(event) = event_loop.next_event() {
+            if event == Event::FramePhase(FramePhase::Custom("Pong")) {
+                context.quit();
+            }
+        }
+        context.shutdown();
+
+        assert!(context.child_engines().is_empty());
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_shut_down_children_in_order_when_the_parent_quits() {
+        let shutdown_order = Arc::new(Mutex::new(Vec::new()));
+        let (event_loop, mut context) = crate::init(());
+        for name in ["first", "second", "third"] {
+            let shutdown_order = shutdown_order.clone();
+            context.spawn_child_engine(
+                || crate::init(()),
+                move |event, _context, _control_flow| {
+                    if event == Event::Quit {
+                        shutdown_order.lock().unwrap().push(name);
+                    }
+                },
+            );
+        }
+
+        event_loop.run(context, |event, context, _control_flow| {
+            if event == Event::EventsCleared {
+                context.quit();
+            }
+        });
+
+        assert_eq!(
+            *shutdown_order.lock().unwrap(),
+            vec!["first", "second", "third"]
+        );
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_report_panicking_children() {
+        let (mut event_loop, mut context) = crate::init(());
+        let child = context.spawn_child_engine(
+            || crate::init(()),
+            |_event, _context, _control_flow| panic!("Child engine panicked"),
+        );
+
+        let events = collect_child_events(&mut event_loop, &mut context, 2);
+
+        assert_eq!(events[1], ChildEngineEvent::Panicked(child.id()));
+    }
+}
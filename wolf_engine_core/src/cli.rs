@@ -0,0 +1,587 @@
+//! Provides command-line argument parsing, integrated with the engine [config](crate::config).
+//!
+//! A [`CommandLine`] knows about a set of standard flags, which map onto the engine config:
+//!
+//! | Flag                  | Config Key                          |
+//! |-----------------------|-------------------------------------|
+//! | `--windowed`          | Unsets `window.fullscreen_mode`     |
+//! | `--fullscreen`        | `window.fullscreen_mode`            |
+//! | `--size WxH`          | `window.width`, and `window.height` |
+//! | `--log-level LEVEL`   | `engine.log_level`                  |
+//! | `--target-fps FPS`    | `engine.target_fps`                 |
+//! | `--fixed-rate RATE`   | `engine.fixed_rate`                 |
+//! | `--time-scale SCALE`  | `engine.time_scale`                 |
+//! | `--config PATH`       | Loads the config file at `PATH`     |
+//! | `--set KEY=VALUE`     | Sets any config key                 |
+//! | `--help`              | Requests the help text              |
+//!
+//! Games can register their own flags using [`CommandLine::with_flag()`].  Values can be passed
+//! as `--flag value`, or `--flag=value`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use wolf_engine_core as wolf_engine;
+//! use wolf_engine::cli::*;
+//! use wolf_engine::config::ConfigLoader;
+//!
+//! let command_line = CommandLine::new("my_game")
+//!     .with_flag(Flag::switch("god-mode", "Makes the player invincible"));
+//!
+//! // Normally, you would use `command_line.parse()` to parse the real arguments.
+//! let arguments = command_line
+//!     .parse_from(["--size", "800x600", "--fixed-rate", "120", "--god-mode"])
+//!     .unwrap();
+//!
+//! let config = arguments.apply(ConfigLoader::new()).load().unwrap();
+//!
+//! assert_eq!(config.engine().unwrap().fixed_rate, Some(120));
+//! assert_eq!(config.value("window.width").unwrap().as_integer(), Some(800));
+//! assert!(arguments.is_set("god-mode"));
+//! ```
+//!
+//! When `--help` is passed, [`CliError::HelpRequested`] is returned with the help text.
+//!
+//! ```
+//! # use wolf_engine_core as wolf_engine;
+//! # use wolf_engine::cli::*;
+//! #
+//! let result = CommandLine::new("my_game").parse_from(["--help"]);
+//!
+//! if let Err(CliError::HelpRequested { help }) = result {
+//!     assert!(help.contains("--windowed"));
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::config::ConfigLoader;
+
+/// Indicates a problem parsing command-line arguments.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CliError {
+    /// `--help` was passed.  Contains the help text, which should be shown to the user.
+    HelpRequested {
+        /// The generated help text.
+        help: String,
+    },
+    /// An unknown flag was passed.
+    UnknownFlag(String),
+    /// An argument, which is not a flag, was passed.
+    UnexpectedArgument(String),
+    /// A flag which requires a value was passed without one.
+    MissingValue {
+        /// The name of the flag.
+        flag: String,
+    },
+    /// A switch, which does not take a value, was passed with one.
+    UnexpectedValue {
+        /// The name of the flag.
+        flag: String,
+    },
+    /// A flag was passed an invalid value.
+    InvalidValue {
+        /// The name of the flag.
+        flag: String,
+        /// The invalid value.
+        value: String,
+        /// Describes what was expected.
+        message: String,
+    },
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HelpRequested { help } => write!(f, "{}", help),
+            Self::UnknownFlag(flag) => write!(f, "Unknown flag: `{}`", flag),
+            Self::UnexpectedArgument(argument) => {
+                write!(f, "Unexpected argument: `{}`", argument)
+            }
+            Self::MissingValue { flag } => write!(f, "`--{}` requires a value", flag),
+            Self::UnexpectedValue { flag } => write!(f, "`--{}` does not take a value", flag),
+            Self::InvalidValue {
+                flag,
+                value,
+                message,
+            } => write!(f, "Invalid value `{}` for `--{}`: {}", value, flag, message),
+        }
+    }
+}
+
+impl Error for CliError {}
+
+/// Describes a command-line flag.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Flag {
+    name: String,
+    value_name: Option<String>,
+    help: String,
+    config_key: Option<String>,
+}
+
+impl Flag {
+    /// Creates a flag which does not take a value, such as `--verbose`.
+    pub fn switch(name: &str, help: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value_name: None,
+            help: help.to_string(),
+            config_key: None,
+        }
+    }
+
+    /// Creates a flag which takes a value, such as `--level NAME`.
+    pub fn option(name: &str, value_name: &str, help: &str) -> Self {
+        Self {
+            value_name: Some(value_name.to_string()),
+            ..Self::switch(name, help)
+        }
+    }
+
+    /// Sets the config key the flag's value is stored under, such as `gameplay.level`.
+    ///
+    /// Switches store `true` when they are set.
+    pub fn with_config_key(mut self, config_key: &str) -> Self {
+        self.config_key = Some(config_key.to_string());
+        self
+    }
+
+    /// Returns the flag's name, without the leading `--`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn takes_value(&self) -> bool {
+        self.value_name.is_some()
+    }
+
+    fn usage(&self) -> String {
+        match &self.value_name {
+            Some(value_name) => format!("--{} <{}>", self.name, value_name),
+            None => format!("--{}", self.name),
+        }
+    }
+}
+
+/// Parses command-line arguments.
+///
+/// See the [module documentation](self) for details.
+pub struct CommandLine {
+    program: String,
+    about: Option<String>,
+    flags: Vec<Flag>,
+}
+
+impl CommandLine {
+    /// Creates a new parser, with the standard flags, for the named program.
+    pub fn new(program: &str) -> Self {
+        Self {
+            program: program.to_string(),
+            about: None,
+            flags: standard_flags(),
+        }
+    }
+
+    /// Sets a description of the program, which is shown in the help text.
+    pub fn with_about(mut self, about: &str) -> Self {
+        self.about = Some(about.to_string());
+        self
+    }
+
+    /// Adds a game-specific flag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a flag with the same name already exists.
+    pub fn with_flag(mut self, flag: Flag) -> Self {
+        assert!(
+            self.find_flag(flag.name()).is_none(),
+            "A flag named `--{}` already exists",
+            flag.name()
+        );
+        self.flags.push(flag);
+        self
+    }
+
+    /// Returns the generated help text.
+    pub fn help(&self) -> String {
+        let mut help = String::new();
+        if let Some(about) = &self.about {
+            help.push_str(about);
+            help.push_str("\n\n");
+        }
+        help.push_str(&format!("Usage: {} [OPTIONS]\n\nOptions:\n", self.program));
+        let usages = self.flags.iter().map(Flag::usage).collect::<Vec<_>>();
+        let width = usages.iter().map(String::len).max().unwrap_or(0);
+        for (usage, flag) in usages.iter().zip(self.flags.iter()) {
+            help.push_str(&format!(
+                "  {:width$}  {}\n",
+                usage,
+                flag.help,
+                width = width
+            ));
+        }
+        help
+    }
+
+    /// Parses the program's arguments.
+    pub fn parse(&self) -> Result<Arguments, CliError> {
+        self.parse_from(std::env::args().skip(1))
+    }
+
+    /// Parses the provided arguments.
+    ///
+    /// The arguments should not include the program name.
+    pub fn parse_from<I, S>(&self, arguments: I) -> Result<Arguments, CliError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut parsed = Arguments::default();
+        let mut arguments = arguments.into_iter().map(Into::into);
+        while let Some(argument) = arguments.next() {
+            let flag_text = argument
+                .strip_prefix("--")
+                .ok_or_else(|| CliError::UnexpectedArgument(argument.clone()))?;
+            let (name, inline_value) = match flag_text.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag_text, None),
+            };
+            let flag = self
+                .find_flag(name)
+                .ok_or_else(|| CliError::UnknownFlag(argument.clone()))?;
+            let value = match (flag.takes_value(), inline_value) {
+                (true, Some(value)) => Some(value),
+                (true, None) => Some(arguments.next().ok_or_else(|| CliError::MissingValue {
+                    flag: name.to_string(),
+                })?),
+                (false, Some(_)) => {
+                    return Err(CliError::UnexpectedValue {
+                        flag: name.to_string(),
+                    })
+                }
+                (false, None) => None,
+            };
+            if name == "help" {
+                return Err(CliError::HelpRequested { help: self.help() });
+            }
+            parsed.apply_flag(flag, value)?;
+        }
+        Ok(parsed)
+    }
+
+    fn find_flag(&self, name: &str) -> Option<&Flag> {
+        self.flags.iter().find(|flag| flag.name == name)
+    }
+}
+
+fn standard_flags() -> Vec<Flag> {
+    vec![
+        Flag::switch("windowed", "Runs in a window"),
+        Flag::switch("fullscreen", "Runs in fullscreen"),
+        Flag::option(
+            "size",
+            "WIDTHxHEIGHT",
+            "Sets the window size, such as 800x600",
+        ),
+        Flag::option(
+            "log-level",
+            "LEVEL",
+            "Sets the log level: off, error, warn, info, debug, or trace",
+        ),
+        Flag::option("target-fps", "FPS", "Limits the frame rate"),
+        Flag::option(
+            "fixed-rate",
+            "RATE",
+            "Sets the number of fixed updates per second",
+        ),
+        Flag::option("time-scale", "SCALE", "Sets the speed of game time"),
+        Flag::option("config", "PATH", "Loads the config file at PATH"),
+        Flag::option("set", "KEY=VALUE", "Overrides any config value"),
+        Flag::switch("help", "Shows this help text"),
+    ]
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum ConfigOverride {
+    Set(String, String),
+    Unset(String),
+}
+
+/// Provides the results of parsing command-line arguments.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Arguments {
+    config_path: Option<PathBuf>,
+    log_level: Option<log::LevelFilter>,
+    overrides: Vec<ConfigOverride>,
+    flags: HashMap<String, Option<String>>,
+}
+
+impl Arguments {
+    /// Applies the parsed arguments to the [`ConfigLoader`], as overrides.
+    ///
+    /// If `--config` was passed, the loader is set to load that file.
+    pub fn apply(&self, mut loader: ConfigLoader) -> ConfigLoader {
+        if let Some(config_path) = &self.config_path {
+            loader = loader.with_path(config_path);
+        }
+        for config_override in self.overrides.iter() {
+            loader = match config_override {
+                ConfigOverride::Set(key, value) => loader.with_override(key, value),
+                ConfigOverride::Unset(key) => loader.with_unset(key),
+            };
+        }
+        loader
+    }
+
+    /// Returns the path passed to `--config`, if there was one.
+    pub fn config_path(&self) -> Option<&PathBuf> {
+        self.config_path.as_ref()
+    }
+
+    /// Returns the log level passed to `--log-level`, if there was one.
+    pub fn log_level(&self) -> Option<log::LevelFilter> {
+        self.log_level
+    }
+
+    /// Returns `true` if the flag with the provided name was passed.
+    pub fn is_set(&self, flag: &str) -> bool {
+        self.flags.contains_key(flag)
+    }
+
+    /// Returns the value passed to the flag with the provided name, if there was one.
+    ///
+    /// If the flag was passed more than once, the last value is returned.
+    pub fn value(&self, flag: &str) -> Option<&str> {
+        self.flags.get(flag).and_then(|value| value.as_deref())
+    }
+
+    /// Parses the value passed to the flag with the provided name.
+    pub fn parse_value<T: FromStr>(&self, flag: &str) -> Option<Result<T, T::Err>> {
+        self.value(flag).map(str::parse)
+    }
+
+    fn apply_flag(&mut self, flag: &Flag, value: Option<String>) -> Result<(), CliError> {
+        let invalid_value = |value: &str, message: &str| CliError::InvalidValue {
+            flag: flag.name.clone(),
+            value: value.to_string(),
+            message: message.to_string(),
+        };
+        let raw_value = value.clone().unwrap_or_default();
+        match flag.name() {
+            "windowed" => self.unset("window.fullscreen_mode"),
+            "fullscreen" => self.set("window.fullscreen_mode", "\"Fullscreen\""),
+            "size" => {
+                let (width, height) = parse_size(&raw_value)
+                    .ok_or_else(|| invalid_value(&raw_value, "Expected WIDTHxHEIGHT"))?;
+                self.set("window.width", &width.to_string());
+                self.set("window.height", &height.to_string());
+            }
+            "log-level" => {
+                let log_level = log::LevelFilter::from_str(&raw_value)
+                    .map_err(|_| invalid_value(&raw_value, "Expected a log level"))?;
+                self.log_level = Some(log_level);
+                self.set("engine.log_level", &format!("\"{}\"", log_level));
+            }
+            "target-fps" | "fixed-rate" => {
+                let rate = raw_value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|rate| *rate > 0)
+                    .ok_or_else(|| invalid_value(&raw_value, "Expected a positive number"))?;
+                self.set(
+                    &format!("engine.{}", flag.name.replace('-', "_")),
+                    &rate.to_string(),
+                );
+            }
+            "time-scale" => {
+                let time_scale = raw_value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|time_scale| time_scale.is_finite() && *time_scale >= 0.0)
+                    .ok_or_else(|| invalid_value(&raw_value, "Expected a number of at least 0"))?;
+                self.set("engine.time_scale", &format!("{:?}", time_scale));
+            }
+            "config" => self.config_path = Some(PathBuf::from(&raw_value)),
+            "set" => {
+                let (key, value) = raw_value
+                    .split_once('=')
+                    .filter(|(key, _)| !key.is_empty())
+                    .ok_or_else(|| invalid_value(&raw_value, "Expected KEY=VALUE"))?;
+                self.set(key, value);
+            }
+            _ => {
+                if let Some(config_key) = &flag.config_key {
+                    let config_value = value.clone().unwrap_or_else(|| "true".to_string());
+                    self.set(config_key, &config_value);
+                }
+            }
+        }
+        self.flags.insert(flag.name.clone(), value);
+        Ok(())
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        self.overrides
+            .push(ConfigOverride::Set(key.to_string(), value.to_string()));
+    }
+
+    fn unset(&mut self, key: &str) {
+        self.overrides.push(ConfigOverride::Unset(key.to_string()));
+    }
+}
+
+fn parse_size(size: &str) -> Option<(u32, u32)> {
+    let (width, height) = size.split_once(['x', 'X'])?;
+    let width = width.parse::<u32>().ok().filter(|width| *width > 0)?;
+    let height = height.parse::<u32>().ok().filter(|height| *height > 0)?;
+    Some((width, height))
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn load(arguments: &Arguments) -> crate::config::Config {
+        arguments
+            .apply(
+                ConfigLoader::new().with_defaults(
+                    "[window]\ntitle = \"Test\"\nfullscreen_mode = \"Borderless\"\n",
+                ),
+            )
+            .load()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_map_standard_flags_onto_the_config() {
+        let arguments = CommandLine::new("test")
+            .parse_from([
+                "--windowed",
+                "--size=800x600",
+                "--log-level",
+                "DEBUG",
+                "--target-fps",
+                "30",
+                "--fixed-rate",
+                "120",
+                "--time-scale",
+                "0.5",
+            ])
+            .unwrap();
+        let config = load(&arguments);
+        let engine = config.engine().unwrap();
+
+        assert_eq!(config.value("window.fullscreen_mode"), None);
+        assert_eq!(
+            config.value("window.width"),
+            Some(&toml::Value::Integer(800))
+        );
+        assert_eq!(
+            config.value("window.height"),
+            Some(&toml::Value::Integer(600))
+        );
+        assert_eq!(arguments.log_level(), Some(log::LevelFilter::Debug));
+        assert_eq!(engine.log_level, log::LevelFilter::Debug);
+        assert_eq!(engine.target_fps, Some(30));
+        assert_eq!(engine.fixed_rate, Some(120));
+        assert_eq!(engine.time_scale, Some(0.5));
+    }
+
+    #[test]
+    fn should_apply_config_paths_and_generic_overrides() {
+        let arguments = CommandLine::new("test")
+            .parse_from(["--config", "game.toml", "--set", "gameplay.lives=5"])
+            .unwrap();
+
+        assert_eq!(arguments.config_path(), Some(&PathBuf::from("game.toml")));
+        let config = arguments.apply(ConfigLoader::new()).load();
+        assert!(matches!(
+            config.err(),
+            Some(crate::config::ConfigError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn should_support_game_specific_flags() {
+        let command_line = CommandLine::new("test")
+            .with_flag(Flag::switch("god-mode", "Makes the player invincible"))
+            .with_flag(
+                Flag::option("level", "NAME", "Starts on the named level")
+                    .with_config_key("gameplay.level"),
+            )
+            .with_flag(Flag::option("seed", "NUMBER", "Sets the random seed"));
+
+        let arguments = command_line
+            .parse_from(["--god-mode", "--level", "intro", "--seed=42"])
+            .unwrap();
+        let config = load(&arguments);
+
+        assert!(arguments.is_set("god-mode"));
+        assert!(!arguments.is_set("debug"));
+        assert_eq!(arguments.value("level"), Some("intro"));
+        assert_eq!(arguments.parse_value::<u64>("seed"), Some(Ok(42)));
+        assert_eq!(
+            config.value("gameplay.level"),
+            Some(&toml::Value::String("intro".to_string()))
+        );
+    }
+
+    #[test]
+    fn should_generate_help_text() {
+        let command_line = CommandLine::new("test")
+            .with_about("A test game")
+            .with_flag(Flag::switch("god-mode", "Makes the player invincible"));
+
+        let error = command_line
+            .parse_from(["--size", "800x600", "--help"])
+            .err();
+
+        let help = command_line.help();
+        assert_eq!(error, Some(CliError::HelpRequested { help: help.clone() }));
+        assert!(help.starts_with("A test game\n\nUsage: test [OPTIONS]"));
+        assert!(help.contains("--size <WIDTHxHEIGHT>"));
+        assert!(help.contains("--god-mode"));
+        assert!(help.contains("Makes the player invincible"));
+    }
+
+    #[test_case(&["--unknown"], CliError::UnknownFlag("--unknown".to_string()) ; "unknown flag")]
+    #[test_case(&["windowed"], CliError::UnexpectedArgument("windowed".to_string()) ; "missing dashes")]
+    #[test_case(&["--size"], CliError::MissingValue { flag: "size".to_string() } ; "missing value")]
+    #[test_case(&["--windowed=yes"], CliError::UnexpectedValue { flag: "windowed".to_string() } ; "unexpected value")]
+    fn should_reject_invalid_arguments(arguments: &[&str], expected: CliError) {
+        let result = CommandLine::new("test").parse_from(arguments.iter().copied());
+
+        assert_eq!(result.err(), Some(expected));
+    }
+
+    #[test_case("--size", "800" ; "size without height")]
+    #[test_case("--size", "0x600" ; "zero width")]
+    #[test_case("--log-level", "loud" ; "unknown log level")]
+    #[test_case("--fixed-rate", "0" ; "zero fixed rate")]
+    #[test_case("--target-fps", "fast" ; "non-numeric fps")]
+    #[test_case("--time-scale", "-1" ; "negative time scale")]
+    #[test_case("--set", "no_equals_sign" ; "set without value")]
+    fn should_reject_invalid_values(flag: &str, value: &str) {
+        let result = CommandLine::new("test").parse_from([flag, value]);
+
+        assert!(
+            matches!(result, Err(CliError::InvalidValue { .. })),
+            "Unexpected result: {:?}",
+            result
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn should_panic_on_duplicate_flags() {
+        CommandLine::new("test").with_flag(Flag::switch("windowed", "Duplicate"));
+    }
+}
@@ -0,0 +1,314 @@
+use std::time::{Duration, Instant};
+
+/// The most fixed steps which can be accumulated at once.  Prevents a "spiral of death" when the
+/// game can't keep up with the fixed rate.
+const MAX_FIXED_STEPS: u32 = 10;
+
+/// Keeps track of game time.
+///
+/// The clock is owned by the [`Context`](crate::Context), and is ticked once per frame by
+/// [`Context::start_frame()`](crate::Context::start_frame()).  Game time can be paused, or
+/// scaled, without affecting real time.  Things like [timers](crate::Context::after()) are driven
+/// by game time.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// # use std::time::Duration;
+/// #
+/// let (_event_loop, mut context) = wolf_engine::init(());
+///
+/// // Run the game in slow-motion.
+/// context.clock_mut().set_time_scale(0.5);
+/// context.clock_mut().tick_by(Duration::from_millis(100));
+/// assert_eq!(context.clock().delta(), Duration::from_millis(50));
+///
+/// // Pause the game.
+/// context.clock_mut().pause();
+/// context.clock_mut().tick_by(Duration::from_millis(100));
+/// assert_eq!(context.clock().delta(), Duration::ZERO);
+/// ```
+///
+/// ## Fixed Updates
+///
+/// When a fixed rate is set, the clock accumulates game time, which can be consumed in
+/// fixed-sized steps.  This follows the approach described in
+/// [Fix Your Timestep!](https://www.gafferongames.com/post/fix_your_timestep/).
+///
+/// ```
+/// # use wolf_engine_core::Clock;
+/// # use std::time::Duration;
+/// #
+/// let mut clock = Clock::new().with_fixed_rate(100);
+/// clock.tick_by(Duration::from_millis(25));
+///
+/// let mut fixed_updates = 0;
+/// while clock.consume_fixed_step() {
+///     fixed_updates += 1;
+/// }
+///
+/// assert_eq!(fixed_updates, 2);
+/// assert_eq!(clock.fixed_alpha(), 0.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Clock {
+    last_tick: Option<Instant>,
+    delta: Duration,
+    real_delta: Duration,
+    elapsed: Duration,
+    time_scale: f64,
+    is_paused: bool,
+    frame: u64,
+    fixed_timestep: Option<Duration>,
+    accumulator: Duration,
+}
+
+impl Clock {
+    /// Creates a new clock.
+    pub fn new() -> Self {
+        Self {
+            last_tick: None,
+            delta: Duration::ZERO,
+            real_delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            time_scale: 1.0,
+            is_paused: false,
+            frame: 0,
+            fixed_timestep: None,
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// Sets the number of fixed steps per second of game time.
+    ///
+    /// A rate of `0` disables fixed steps.
+    pub fn with_fixed_rate(mut self, fixed_rate: u32) -> Self {
+        self.set_fixed_rate(fixed_rate);
+        self
+    }
+
+    /// Sets the number of fixed steps per second of game time.
+    ///
+    /// A rate of `0` disables fixed steps.
+    pub fn set_fixed_rate(&mut self, fixed_rate: u32) {
+        self.fixed_timestep = match fixed_rate {
+            0 => None,
+            fixed_rate => Some(Duration::from_secs(1) / fixed_rate),
+        };
+        self.accumulator = Duration::ZERO;
+    }
+
+    /// Returns the length of a fixed step, if fixed steps are enabled.
+    pub fn fixed_timestep(&self) -> Option<Duration> {
+        self.fixed_timestep
+    }
+
+    /// Consumes one fixed step's worth of accumulated game time, if enough is available.
+    ///
+    /// Returns `true` if a fixed step should be run.  This is intended to be called in a loop,
+    /// once per frame, until it returns `false`.  Always returns `false` if fixed steps are not
+    /// enabled.
+    pub fn consume_fixed_step(&mut self) -> bool {
+        match self.fixed_timestep {
+            Some(fixed_timestep) if self.accumulator >= fixed_timestep => {
+                self.accumulator -= fixed_timestep;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns how far, from `0.0` to `1.0`, the accumulated game time is into the next fixed
+    /// step.
+    ///
+    /// This is useful for interpolating between fixed steps while rendering.  Returns `0.0` if
+    /// fixed steps are not enabled.
+    pub fn fixed_alpha(&self) -> f32 {
+        match self.fixed_timestep {
+            Some(fixed_timestep) => {
+                (self.accumulator.as_secs_f64() / fixed_timestep.as_secs_f64()) as f32
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Advances the clock by the real time passed since the last tick.
+    ///
+    /// The first tick always has a delta of zero.
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        let real_delta = match self.last_tick {
+            Some(last_tick) => now.saturating_duration_since(last_tick),
+            None => Duration::ZERO,
+        };
+        self.tick_by(real_delta);
+        self.last_tick = Some(now);
+    }
+
+    /// Advances the clock by the provided amount of real time.
+    ///
+    /// This is useful for driving the clock manually, such as in tests.
+    pub fn tick_by(&mut self, real_delta: Duration) {
+        self.real_delta = real_delta;
+        self.delta = if self.is_paused {
+            Duration::ZERO
+        } else {
+            real_delta.mul_f64(self.time_scale)
+        };
+        self.elapsed += self.delta;
+        self.frame += 1;
+        if let Some(fixed_timestep) = self.fixed_timestep {
+            self.accumulator =
+                (self.accumulator + self.delta).min(fixed_timestep * MAX_FIXED_STEPS);
+        }
+    }
+
+    /// Returns the amount of game time which passed during the last tick.
+    ///
+    /// This is zero while the clock is paused.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Returns the amount of real time which passed during the last tick.
+    pub fn real_delta(&self) -> Duration {
+        self.real_delta
+    }
+
+    /// Returns the total amount of game time which has passed.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the number of times the clock has been ticked.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns the rate at which game time passes, relative to real time.
+    pub fn time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    /// Sets the rate at which game time passes, relative to real time.
+    ///
+    /// Negative, and non-finite, values are treated as `0.0`.
+    pub fn set_time_scale(&mut self, time_scale: f64) {
+        self.time_scale = if time_scale.is_finite() {
+            time_scale.max(0.0)
+        } else {
+            0.0
+        };
+    }
+
+    /// Pauses game time.
+    pub fn pause(&mut self) {
+        self.is_paused = true;
+    }
+
+    /// Resumes game time.
+    pub fn resume(&mut self) {
+        self.is_paused = false;
+    }
+
+    /// Returns `true` if game time is paused.
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod clock_tests {
+    use super::*;
+
+    #[test]
+    fn should_accumulate_elapsed_time() {
+        let mut clock = Clock::new();
+
+        clock.tick_by(Duration::from_millis(10));
+        clock.tick_by(Duration::from_millis(20));
+
+        assert_eq!(clock.delta(), Duration::from_millis(20));
+        assert_eq!(clock.elapsed(), Duration::from_millis(30));
+        assert_eq!(clock.frame(), 2);
+    }
+
+    #[test]
+    fn should_scale_game_time() {
+        let mut clock = Clock::new();
+        clock.set_time_scale(2.0);
+
+        clock.tick_by(Duration::from_millis(10));
+
+        assert_eq!(clock.delta(), Duration::from_millis(20));
+        assert_eq!(clock.real_delta(), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn should_not_advance_game_time_while_paused() {
+        let mut clock = Clock::new();
+
+        clock.pause();
+        clock.tick_by(Duration::from_millis(10));
+        assert!(clock.is_paused());
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+
+        clock.resume();
+        clock.tick_by(Duration::from_millis(10));
+        assert_eq!(clock.elapsed(), Duration::from_millis(10));
+    }
+
+    #[test]
+    fn should_consume_fixed_steps() {
+        let mut clock = Clock::new().with_fixed_rate(10);
+
+        clock.tick_by(Duration::from_millis(250));
+
+        assert!(clock.consume_fixed_step());
+        assert!(clock.consume_fixed_step());
+        assert!(!clock.consume_fixed_step());
+        assert_eq!(clock.fixed_alpha(), 0.5);
+    }
+
+    #[test]
+    fn should_limit_accumulated_fixed_steps() {
+        let mut clock = Clock::new().with_fixed_rate(10);
+
+        clock.tick_by(Duration::from_secs(100));
+
+        let mut fixed_steps = 0;
+        while clock.consume_fixed_step() {
+            fixed_steps += 1;
+        }
+        assert_eq!(fixed_steps, MAX_FIXED_STEPS);
+    }
+
+    #[test]
+    fn should_not_consume_fixed_steps_when_disabled() {
+        let mut clock = Clock::new().with_fixed_rate(0);
+
+        clock.tick_by(Duration::from_secs(1));
+
+        assert_eq!(clock.fixed_timestep(), None);
+        assert!(!clock.consume_fixed_step());
+        assert_eq!(clock.fixed_alpha(), 0.0);
+    }
+
+    #[test]
+    fn should_clamp_invalid_time_scales() {
+        let mut clock = Clock::new();
+
+        clock.set_time_scale(-1.0);
+        assert_eq!(clock.time_scale(), 0.0);
+
+        clock.set_time_scale(f64::NAN);
+        assert_eq!(clock.time_scale(), 0.0);
+    }
+}
@@ -0,0 +1,732 @@
+//! Provides engine configuration loaded from TOML files, environment variables, and overrides.
+//!
+//! Configuration is loaded by a [`ConfigLoader`], which layers the following sources, with each
+//! layer overriding the ones before it:
+//!
+//! 1. Defaults, provided as TOML.
+//! 2. A config file, either at a specific path, or the first file found in a search list.
+//! 3. Environment variables, which start with a prefix.
+//! 4. Overrides, such as those parsed from the command-line.
+//!
+//! The resulting [`Config`] is made up of sections, which are deserialized into typed structs
+//! using [`Config::section()`].  The `[engine]` section is used by the engine itself, and is
+//! provided as [`EngineSettings`].  Other sections are free for the game to use, including the
+//! `[window]` section, which is meant for the window crate's `WindowSettings`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use wolf_engine_core as wolf_engine;
+//! use wolf_engine::config::*;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Gameplay {
+//!     difficulty: String,
+//! }
+//!
+//! let config = ConfigLoader::new()
+//!     .with_defaults(
+//!         r#"
+//!         [engine]
+//!         target_fps = 60
+//!
+//!         [gameplay]
+//!         difficulty = "normal"
+//!         "#,
+//!     )
+//!     .with_override("gameplay.difficulty", "hard")
+//!     .load()
+//!     .unwrap();
+//!
+//! assert_eq!(config.engine().unwrap().target_fps, Some(60));
+//! assert_eq!(config.section::<Gameplay>("gameplay").unwrap().difficulty, "hard");
+//! ```
+//!
+//! ## Environment Variables
+//!
+//! Environment variables are named using the prefix, followed by the path to the key, with
+//! each part separated by a double underscore (`__`).  For example, with a prefix of
+//! `MY_GAME`, `MY_GAME__ENGINE__TARGET_FPS=30` sets `target_fps` in the `[engine]` section.
+//! Keys are converted to lowercase.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use serde::de::{DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, Visitor};
+use serde::Deserialize;
+use toml::{Table, Value};
+
+/// The name of the section used by the engine.
+pub const ENGINE_SECTION: &str = "engine";
+
+/// The separator used between the parts of environment variable names.
+pub const ENV_SEPARATOR: &str = "__";
+
+/// Indicates a problem loading, or reading, a [`Config`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ConfigError {
+    /// A config file could not be read.
+    Io {
+        /// The path of the file.
+        path: PathBuf,
+        /// The error message.
+        message: String,
+    },
+    /// None of the files in the search list exist.
+    NotFound {
+        /// The paths which were searched.
+        searched: Vec<PathBuf>,
+    },
+    /// A config file, or the defaults, contain invalid TOML, or a value of the wrong type.
+    Parse {
+        /// The path of the file, or `None` for the defaults.
+        path: Option<PathBuf>,
+        /// The line number of the error, counting from `1`.
+        line: usize,
+        /// The column number of the error, counting from `1`.
+        column: usize,
+        /// The error message.
+        message: String,
+    },
+    /// An override, or environment variable, could not be applied.
+    InvalidOverride {
+        /// The key path of the override.
+        key: String,
+        /// The error message.
+        message: String,
+    },
+    /// The requested section does not exist.
+    MissingSection {
+        /// The name of the section.
+        section: String,
+    },
+    /// A section could not be deserialized into the requested type.
+    InvalidSection {
+        /// The name of the section.
+        section: String,
+        /// The error message.
+        message: String,
+    },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, message } => {
+                write!(f, "Failed to read {}: {}", path.display(), message)
+            }
+            Self::NotFound { searched } => {
+                let searched = searched
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "No config file was found, searched: {}", searched)
+            }
+            Self::Parse {
+                path,
+                line,
+                column,
+                message,
+            } => {
+                let source = path
+                    .as_ref()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_else(|| "defaults".to_string());
+                write!(f, "{}:{}:{}: {}", source, line, column, message)
+            }
+            Self::InvalidOverride { key, message } => {
+                write!(f, "Invalid override for `{}`: {}", key, message)
+            }
+            Self::MissingSection { section } => {
+                write!(f, "The config has no [{}] section", section)
+            }
+            Self::InvalidSection { section, message } => {
+                write!(f, "Invalid [{}] section: {}", section, message)
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+/// Provides the settings stored in the `[engine]` section of a [`Config`].
+///
+/// Every setting is optional, so the section can be left out entirely.  Settings can be applied
+/// to an [`EngineBuilder`](crate::EngineBuilder) using
+/// [`EngineBuilder::with_config()`](crate::EngineBuilder::with_config()).
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineSettings {
+    /// The most verbose level of log messages to show.
+    pub log_level: log::LevelFilter,
+    /// A `RUST_LOG`-style filter string, such as `my_game::ai=trace`, applied on top of the
+    /// log level.
+    pub log_filter: Option<String>,
+    /// The maximum number of frames per second.
+    pub target_fps: Option<u32>,
+    /// The number of fixed updates per second.
+    pub fixed_rate: Option<u32>,
+    /// The rate at which game time passes, relative to real time.  The clock uses `1.0` when
+    /// this is not set.
+    pub time_scale: Option<f64>,
+    /// The number of threads used by the [`JobPool`](crate::JobPool).
+    pub job_threads: Option<usize>,
+}
+
+impl Default for EngineSettings {
+    fn default() -> Self {
+        Self {
+            log_level: log::LevelFilter::Info,
+            log_filter: None,
+            target_fps: None,
+            fixed_rate: None,
+            time_scale: None,
+            job_threads: None,
+        }
+    }
+}
+
+struct ConfigSource {
+    path: Option<PathBuf>,
+    text: String,
+}
+
+/// Provides the engine configuration, loaded by a [`ConfigLoader`].
+pub struct Config {
+    table: Table,
+    sources: Vec<ConfigSource>,
+}
+
+impl Config {
+    /// Returns the section with the provided name, deserialized into `T`.
+    ///
+    /// If the section is invalid, and the invalid value came from a file, the error includes
+    /// the line number of the value.
+    pub fn section<T: DeserializeOwned>(&self, section: &str) -> Result<T, ConfigError> {
+        let value = self
+            .table
+            .get(section)
+            .ok_or_else(|| ConfigError::MissingSection {
+                section: section.to_string(),
+            })?;
+        value.clone().try_into().map_err(|error: toml::de::Error| {
+            self.locate_section_error::<T>(section)
+                .unwrap_or_else(|| ConfigError::InvalidSection {
+                    section: section.to_string(),
+                    message: error.message().to_string(),
+                })
+        })
+    }
+
+    /// Returns the section with the provided name, or the default value, if the section does
+    /// not exist.
+    pub fn section_or_default<T: DeserializeOwned + Default>(
+        &self,
+        section: &str,
+    ) -> Result<T, ConfigError> {
+        match self.section(section) {
+            Err(ConfigError::MissingSection { .. }) => Ok(T::default()),
+            result => result,
+        }
+    }
+
+    /// Returns the [`EngineSettings`] from the `[engine]` section.
+    pub fn engine(&self) -> Result<EngineSettings, ConfigError> {
+        self.section_or_default(ENGINE_SECTION)
+    }
+
+    /// Returns `true` if the config has a section with the provided name.
+    pub fn has_section(&self, section: &str) -> bool {
+        self.table.contains_key(section)
+    }
+
+    /// Returns the raw value at the provided dotted key path, such as `engine.target_fps`.
+    pub fn value(&self, key: &str) -> Option<&Value> {
+        let mut parts = key.split('.');
+        let mut value = self.table.get(parts.next()?)?;
+        for part in parts {
+            value = value.as_table()?.get(part)?;
+        }
+        Some(value)
+    }
+
+    /// Returns the paths of the files the config was loaded from.
+    pub fn paths(&self) -> Vec<&Path> {
+        self.sources
+            .iter()
+            .filter_map(|source| source.path.as_deref())
+            .collect()
+    }
+
+    /// Finds the line number of a section error by re-reading the section from the sources,
+    /// last to first, since later sources override earlier ones.
+    fn locate_section_error<T: DeserializeOwned>(&self, section: &str) -> Option<ConfigError> {
+        self.sources.iter().rev().find_map(|source| {
+            let seed = SectionSeed::<T> {
+                section,
+                phantom: PhantomData,
+            };
+            match seed.deserialize(toml::Deserializer::new(&source.text)) {
+                Err(error) if error.span().is_some() => {
+                    Some(parse_error(source.path.clone(), &source.text, &error))
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Loads a [`Config`] from layered sources.
+///
+/// See the [module documentation](self) for details.
+#[derive(Default)]
+pub struct ConfigLoader {
+    defaults: Option<String>,
+    path: Option<PathBuf>,
+    search_paths: Vec<PathBuf>,
+    env_prefix: Option<String>,
+    overrides: Vec<(String, Option<String>)>,
+}
+
+impl ConfigLoader {
+    /// Creates a new loader, with no sources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the default config, as a TOML string.
+    pub fn with_defaults(mut self, defaults: &str) -> Self {
+        self.defaults = Some(defaults.to_string());
+        self
+    }
+
+    /// Loads the config file at the provided path.  The file must exist.
+    pub fn with_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Loads the first config file which exists from the provided list of paths.
+    ///
+    /// If none of the files exist, loading fails with [`ConfigError::NotFound`].  This is ignored
+    /// if a specific path is set using [`ConfigLoader::with_path()`].
+    pub fn with_search_paths<I, P>(mut self, search_paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.search_paths = search_paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Reads overrides from environment variables starting with the provided prefix.
+    pub fn with_env_prefix(mut self, prefix: &str) -> Self {
+        self.env_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Overrides the value at the provided dotted key path, such as `engine.target_fps`.
+    ///
+    /// The value is parsed as a TOML value, such as `60`, `true`, or `[1, 2]`.  If it isn't
+    /// valid TOML, it is used as a string.
+    pub fn with_override(mut self, key: &str, value: &str) -> Self {
+        self.overrides
+            .push((key.to_string(), Some(value.to_string())));
+        self
+    }
+
+    /// Removes the value at the provided dotted key path, so the section's default is used.
+    ///
+    /// This is useful for optional values, since TOML has no way to represent "no value."
+    pub fn with_unset(mut self, key: &str) -> Self {
+        self.overrides.push((key.to_string(), None));
+        self
+    }
+
+    /// Loads the config from all of the sources.
+    pub fn load(self) -> Result<Config, ConfigError> {
+        let mut config = Config {
+            table: Table::new(),
+            sources: Vec::new(),
+        };
+        let path = self.find_path()?;
+        if let Some(defaults) = self.defaults {
+            config.load_source(None, defaults)?;
+        }
+        if let Some(path) = path {
+            let text = std::fs::read_to_string(&path).map_err(|error| ConfigError::Io {
+                path: path.clone(),
+                message: error.to_string(),
+            })?;
+            config.load_source(Some(path), text)?;
+        }
+        if let Some(prefix) = self.env_prefix {
+            let mut env_overrides = env_overrides(&prefix, std::env::vars());
+            env_overrides.sort();
+            for (key, value) in env_overrides {
+                set_value(&mut config.table, &key, Some(parse_value(&value)))?;
+            }
+        }
+        for (key, value) in self.overrides {
+            set_value(&mut config.table, &key, value.as_deref().map(parse_value))?;
+        }
+        Ok(config)
+    }
+
+    fn find_path(&self) -> Result<Option<PathBuf>, ConfigError> {
+        if let Some(path) = &self.path {
+            return Ok(Some(path.clone()));
+        }
+        if self.search_paths.is_empty() {
+            return Ok(None);
+        }
+        self.search_paths
+            .iter()
+            .find(|path| path.is_file())
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| ConfigError::NotFound {
+                searched: self.search_paths.clone(),
+            })
+    }
+}
+
+impl Config {
+    fn load_source(&mut self, path: Option<PathBuf>, text: String) -> Result<(), ConfigError> {
+        let table = text
+            .parse::<Table>()
+            .map_err(|error| parse_error(path.clone(), &text, &error))?;
+        if table.contains_key(ENGINE_SECTION) {
+            let seed = SectionSeed::<EngineSettings> {
+                section: ENGINE_SECTION,
+                phantom: PhantomData,
+            };
+            seed.deserialize(toml::Deserializer::new(&text))
+                .map_err(|error| parse_error(path.clone(), &text, &error))?;
+        }
+        merge_tables(&mut self.table, table);
+        self.sources.push(ConfigSource { path, text });
+        Ok(())
+    }
+}
+
+fn merge_tables(base: &mut Table, layer: Table) {
+    for (key, value) in layer {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(layer_table)) => {
+                merge_tables(base_table, layer_table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Sets the value at the key path, or removes it, if the value is `None`.
+fn set_value(table: &mut Table, key: &str, value: Option<Value>) -> Result<(), ConfigError> {
+    let invalid_override = |message: &str| ConfigError::InvalidOverride {
+        key: key.to_string(),
+        message: message.to_string(),
+    };
+    let parts = key.split('.').collect::<Vec<_>>();
+    if parts.iter().any(|part| part.is_empty()) {
+        return Err(invalid_override("Key paths must not have empty parts"));
+    }
+    let (last, parents) = parts.split_last().expect("Split always returns a part");
+    let mut table = table;
+    for part in parents {
+        table = table
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| invalid_override(&format!("`{}` is not a table", part)))?;
+    }
+    match value {
+        Some(value) => table.insert(last.to_string(), value),
+        None => table.remove(*last),
+    };
+    Ok(())
+}
+
+fn parse_value(value: &str) -> Value {
+    format!("value = {}", value)
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+fn env_overrides<I>(prefix: &str, vars: I) -> Vec<(String, String)>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let prefix = format!("{}{}", prefix, ENV_SEPARATOR);
+    vars.into_iter()
+        .filter_map(|(name, value)| {
+            let key = name
+                .strip_prefix(&prefix)?
+                .split(ENV_SEPARATOR)
+                .map(str::to_lowercase)
+                .collect::<Vec<_>>()
+                .join(".");
+            Some((key, value))
+        })
+        .collect()
+}
+
+fn parse_error(path: Option<PathBuf>, text: &str, error: &toml::de::Error) -> ConfigError {
+    let offset = error.span().map(|span| span.start).unwrap_or(0);
+    let (line, column) = line_and_column(text, offset);
+    ConfigError::Parse {
+        path,
+        line,
+        column,
+        message: error.message().to_string(),
+    }
+}
+
+fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    (line, column)
+}
+
+/// Deserializes a single section of a document, ignoring the rest, so errors keep their spans.
+struct SectionSeed<'a, T> {
+    section: &'a str,
+    phantom: PhantomData<T>,
+}
+
+impl<'de, 'a, T: Deserialize<'de>> DeserializeSeed<'de> for SectionSeed<'a, T> {
+    type Value = Option<T>;
+
+    fn deserialize<D: serde::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, T: Deserialize<'de>> Visitor<'de> for SectionSeed<'a, T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "a table")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut section = None;
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.section {
+                section = Some(map.next_value::<T>()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(section)
+    }
+}
+
+#[cfg(test)]
+mod config_tests {
+    use std::io::Write;
+
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Gameplay {
+        difficulty: String,
+        lives: u32,
+    }
+
+    fn write_config_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "wolf_engine_config_test_{}_{}.toml",
+            std::process::id(),
+            name
+        ));
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn should_layer_sources_in_order() {
+        let path = write_config_file(
+            "layers",
+            "[engine]\ntarget_fps = 30\nfixed_rate = 50\n\n[gameplay]\nlives = 5\n",
+        );
+        std::env::set_var("WOLF_LAYER_TEST__ENGINE__FIXED_RATE", "100");
+
+        let config = ConfigLoader::new()
+            .with_defaults(
+                "[engine]\ntarget_fps = 60\n\n[gameplay]\ndifficulty = \"easy\"\nlives = 3\n",
+            )
+            .with_path(&path)
+            .with_env_prefix("WOLF_LAYER_TEST")
+            .with_override("gameplay.difficulty", "hard")
+            .load()
+            .unwrap();
+        let engine = config.engine().unwrap();
+
+        assert_eq!(engine.target_fps, Some(30));
+        assert_eq!(engine.fixed_rate, Some(100));
+        assert_eq!(
+            config.section::<Gameplay>("gameplay").unwrap(),
+            Gameplay {
+                difficulty: "hard".to_string(),
+                lives: 5
+            }
+        );
+        assert_eq!(config.paths(), vec![path.as_path()]);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn should_use_the_first_file_found_in_the_search_list() {
+        let path = write_config_file("search", "[engine]\ntarget_fps = 144\n");
+        let missing_path = std::env::temp_dir().join("wolf_engine_missing_config.toml");
+
+        let config = ConfigLoader::new()
+            .with_search_paths([missing_path.clone(), path.clone()])
+            .load()
+            .unwrap();
+        let error = ConfigLoader::new()
+            .with_search_paths([missing_path.clone()])
+            .load()
+            .err();
+
+        assert_eq!(config.engine().unwrap().target_fps, Some(144));
+        assert_eq!(
+            error,
+            Some(ConfigError::NotFound {
+                searched: vec![missing_path]
+            })
+        );
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn should_report_syntax_errors_with_line_numbers() {
+        let path = write_config_file("syntax", "[engine]\ntarget_fps = 60\nlog_level = \n");
+
+        let error = ConfigLoader::new().with_path(&path).load().err().unwrap();
+
+        assert!(
+            matches!(&error, ConfigError::Parse { path: Some(error_path), line: 3, .. } if *error_path == path),
+            "Unexpected error: {:?}",
+            error
+        );
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn should_report_engine_setting_errors_with_line_numbers() {
+        let error = ConfigLoader::new()
+            .with_defaults("[engine]\n\ntarget_fps = \"fast\"\n")
+            .load()
+            .err()
+            .unwrap();
+
+        assert!(
+            matches!(
+                error,
+                ConfigError::Parse {
+                    path: None,
+                    line: 3,
+                    ..
+                }
+            ),
+            "Unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn should_report_game_section_errors_with_line_numbers() {
+        let config = ConfigLoader::new()
+            .with_defaults("[gameplay]\ndifficulty = \"easy\"\nlives = -1\n")
+            .load()
+            .unwrap();
+
+        let error = config.section::<Gameplay>("gameplay").err().unwrap();
+
+        assert!(
+            matches!(error, ConfigError::Parse { line: 3, .. }),
+            "Unexpected error: {:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn should_report_missing_sections() {
+        let config = ConfigLoader::new().load().unwrap();
+
+        assert_eq!(
+            config.section::<Gameplay>("gameplay").err(),
+            Some(ConfigError::MissingSection {
+                section: "gameplay".to_string()
+            })
+        );
+        assert_eq!(config.engine().unwrap(), EngineSettings::default());
+    }
+
+    #[test]
+    fn should_parse_override_values() {
+        let config = ConfigLoader::new()
+            .with_override("engine.log_level", "debug")
+            .with_override("engine.time_scale", "0.5")
+            .with_override("debug.enabled", "true")
+            .load()
+            .unwrap();
+
+        assert_eq!(config.engine().unwrap().log_level, log::LevelFilter::Debug);
+        assert_eq!(config.engine().unwrap().time_scale, Some(0.5));
+        assert_eq!(config.value("debug.enabled"), Some(&Value::Boolean(true)));
+    }
+
+    #[test]
+    fn should_unset_values() {
+        let config = ConfigLoader::new()
+            .with_defaults("[engine]\ntarget_fps = 60\nfixed_rate = 30\n")
+            .with_unset("engine.target_fps")
+            .load()
+            .unwrap();
+
+        assert_eq!(config.engine().unwrap().target_fps, None);
+        assert_eq!(config.engine().unwrap().fixed_rate, Some(30));
+    }
+
+    #[test]
+    fn should_reject_overrides_through_non_tables() {
+        let error = ConfigLoader::new()
+            .with_defaults("title = \"My Game\"")
+            .with_override("title.text", "Other Game")
+            .load()
+            .err();
+
+        assert!(matches!(error, Some(ConfigError::InvalidOverride { .. })));
+    }
+
+    #[test]
+    fn should_convert_environment_variable_names_to_keys() {
+        let vars = vec![
+            ("GAME__ENGINE__TARGET_FPS".to_string(), "30".to_string()),
+            ("GAMEPLAY__LIVES".to_string(), "3".to_string()),
+        ];
+
+        assert_eq!(
+            env_overrides("GAME", vars),
+            vec![("engine.target_fps".to_string(), "30".to_string())]
+        );
+    }
+}
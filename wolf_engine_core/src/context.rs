@@ -1,6 +1,16 @@
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::Arc;
 
+use crate::child_engines::ChildEngines;
 use crate::events::*;
+use crate::subsystems::Subsystems;
+use crate::timers::Timers;
+use crate::{
+    Clock, JobContext, JobHandle, JobPool, MainThreadDispatcher, MainThreadTask, ResourceError,
+    Resources,
+};
+
+pub(crate) type ShutdownHook<D> = Box<dyn FnOnce(&mut Context<D>) + Send>;
 
 /// Provides a container for Wolf Engine's user-facing data.
 ///
@@ -51,24 +61,275 @@ use crate::events::*;
 /// // Initialize the engine with your custom data.
 /// let (mut event_loop, mut context) = wolf_engine::init(CustomContextData::new());
 /// ```
+///
+/// ## Resources
+///
+/// Alongside the Context data, the Context also provides a type-keyed store of
+/// [`Resources`].  Resources allow independent subsystems, and plugins, to store their own state
+/// without it needing to be part of the Context data.
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// #
+/// struct AudioSettings {
+///     volume: f32,
+/// }
+///
+/// let (mut event_loop, mut context) = wolf_engine::init(());
+/// context.insert_resource(AudioSettings { volume: 1.0 });
+///
+/// context.resource_mut::<AudioSettings>().unwrap().volume = 0.5;
+///
+/// assert_eq!(context.resource::<AudioSettings>().unwrap().volume, 0.5);
+/// ```
+///
+/// ## Background Jobs
+///
+/// The Context owns a [`JobPool`] for running work, such as loading files, off of the main
+/// thread.  See [`Context::spawn_job()`] for more details.
+///
+/// ## Running Code on the Main Thread
+///
+/// Other threads can send closures to be run on the main thread using a
+/// [`MainThreadDispatcher`], which is created by [`Context::main_thread_dispatcher()`].
+///
+/// ## Subsystems
+///
+/// Parts of the engine which need to hook into its lifecycle can implement the
+/// [`Subsystem`](crate::Subsystem) trait, and be registered with [`Context::add_subsystem()`].
+///
+/// ## Child Engines
+///
+/// Separate engines, such as a server simulation, can be run on their own threads using
+/// [`Context::spawn_child_engine()`].
+///
+/// ## Snapshots
+///
+/// With the `serde` feature enabled, the Context data, and opted-in resources, can be saved to,
+/// and restored from, a [`Snapshot`](crate::Snapshot) using [`Context::snapshot()`], and
+/// [`Context::restore_snapshot()`].
+///
+/// ## Game Time, and Timers
+///
+/// The Context owns the game's [`Clock`], which can be paused, or scaled.  Timers can be
+/// created using [`Context::after()`], [`Context::every()`], and [`Context::after_frames()`].
 pub struct Context<D> {
     /// The user-facing engine data.  Normally things like subsystems.
     pub data: D,
+    resources: Resources,
     event_sender: Arc<dyn EventSender<Event>>,
+    job_pool: JobPool,
+    main_thread_task_sender: Sender<MainThreadTask<D>>,
+    main_thread_task_receiver: Receiver<MainThreadTask<D>>,
+    clock: Clock,
+    pub(crate) timers: Timers<D>,
+    pub(crate) shutdown_hooks: Vec<ShutdownHook<D>>,
+    pub(crate) subsystems: Subsystems<D>,
+    pub(crate) child_engines: ChildEngines,
+    #[cfg(feature = "serde")]
+    pub(crate) snapshot_resources: Vec<crate::snapshots::SnapshotResource>,
 }
 
 impl<D> Context<D> {
     /// Create a new `Context` from the provided [`EventQueue`] and data.
     pub(crate) fn new(event_queue: &dyn EventQueue<Event>, data: D) -> Self {
+        let event_sender = event_queue.event_sender();
+        let (main_thread_task_sender, main_thread_task_receiver) = mpsc::channel();
         Self {
             data,
-            event_sender: event_queue.event_sender(),
+            resources: Resources::new(),
+            job_pool: JobPool::new(event_sender.clone()),
+            event_sender,
+            main_thread_task_sender,
+            main_thread_task_receiver,
+            clock: Clock::new(),
+            timers: Timers::new(),
+            shutdown_hooks: Vec::new(),
+            subsystems: Subsystems::new(),
+            child_engines: ChildEngines::new(),
+            #[cfg(feature = "serde")]
+            snapshot_resources: Vec::new(),
+        }
+    }
+
+    pub(crate) fn set_job_pool(&mut self, job_pool: JobPool) {
+        self.job_pool.shutdown();
+        self.job_pool = job_pool;
+    }
+
+    /// Runs the engine's per-frame work.
+    ///
+    /// The [`Clock`] is ticked first, then any new [subsystems](Context::add_subsystem()) are
+    /// started, closures sent through a [`MainThreadDispatcher`] are run, timers are updated,
+    /// and finally, [`Subsystem::on_frame()`](crate::Subsystem::on_frame()) is called.
+    ///
+    /// This is called automatically by [`EventLoop::run()`](crate::EventLoop::run()) at the start
+    /// of every frame, right before the first
+    /// [frame phase](crate::EventLoop::frame_phases()) is handled.  Custom main-loops should do
+    /// the same, by calling this whenever
+    /// [`EventLoop::has_started_frame()`](crate::EventLoop::has_started_frame()) returns `true`.
+    pub fn start_frame(&mut self) {
+        self.clock.tick();
+        if let Err(error) = self.start_subsystems() {
+            log::error!("Failed to start subsystems: {}", error);
+            self.quit();
+        }
+        self.run_main_thread_tasks();
+        self.update_timers();
+        self.update_subsystems();
+    }
+
+    /// Inserts a resource, returning the previous resource of the same type, if there was one.
+    pub fn insert_resource<T: Send + 'static>(&mut self, resource: T) -> Option<T> {
+        self.resources.insert(resource)
+    }
+
+    /// Returns a reference to the resource of type `T`.
+    pub fn resource<T: 'static>(&self) -> Result<&T, ResourceError> {
+        self.resources.get()
+    }
+
+    /// Returns a mutable reference to the resource of type `T`.
+    pub fn resource_mut<T: 'static>(&mut self) -> Result<&mut T, ResourceError> {
+        self.resources.get_mut()
+    }
+
+    /// Removes, and returns, the resource of type `T`.
+    pub fn remove_resource<T: 'static>(&mut self) -> Result<T, ResourceError> {
+        self.resources.remove()
+    }
+
+    /// Returns `true` if there is a resource of type `T`.
+    pub fn has_resource<T: 'static>(&self) -> bool {
+        self.resources.contains::<T>()
+    }
+
+    /// Returns the Context's [`Resources`].
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    /// Returns a mutable reference to the Context's [`Resources`].
+    pub fn resources_mut(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+
+    /// Returns the game [`Clock`].
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Returns a mutable reference to the game [`Clock`].
+    pub fn clock_mut(&mut self) -> &mut Clock {
+        &mut self.clock
+    }
+
+    fn run_main_thread_tasks(&mut self) {
+        while let Ok(task) = self.main_thread_task_receiver.try_recv() {
+            task(self);
         }
     }
 
+    /// Runs the queued main-thread tasks, then closes the queue.
+    ///
+    /// Tasks sent after this are refused, and tasks which slip in before the queue is closed are
+    /// dropped, so anything waiting on their results gets an error instead of blocking forever.
+    fn close_main_thread_tasks(&mut self) {
+        self.run_main_thread_tasks();
+        let (closed_sender, _) = mpsc::channel();
+        let (_, closed_receiver) = mpsc::channel();
+        self.main_thread_task_sender = closed_sender;
+        drop(std::mem::replace(
+            &mut self.main_thread_task_receiver,
+            closed_receiver,
+        ));
+    }
+
+    /// Creates a new [`MainThreadDispatcher`], which can be used to run closures on the main
+    /// thread.
+    pub fn main_thread_dispatcher(&self) -> MainThreadDispatcher<D> {
+        MainThreadDispatcher::new(
+            self.main_thread_task_sender.clone(),
+            self.event_sender.clone(),
+        )
+    }
+
+    /// Tells the engine to quit.
     pub fn quit(&self) {
         self.event_sender.send_event(Event::Quit).ok();
     }
+
+    /// Adds a hook, which will be called once when the engine shuts down.
+    ///
+    /// Hooks are called in the order they were added, before the [`JobPool`] is shut down.
+    pub fn add_shutdown_hook<F>(&mut self, hook: F)
+    where
+        F: FnOnce(&mut Context<D>) + Send + 'static,
+    {
+        self.shutdown_hooks.push(Box::new(hook));
+    }
+
+    /// Shuts down the engine's subsystems, such as the [`JobPool`].
+    ///
+    /// Shutdown hooks, added by [`Context::add_shutdown_hook()`], are called first.  Then the
+    /// closures already sent through a [`MainThreadDispatcher`] are run, and the dispatcher stops
+    /// accepting new ones, so threads waiting on the main thread can't block shutdown.  Then
+    /// [child engines](Context::spawn_child_engine()) are quit, and joined, in order, followed by
+    /// [`Subsystem::on_shutdown()`](crate::Subsystem::on_shutdown()), in reverse order.
+    ///
+    /// This is called automatically by [`EventLoop::run()`](crate::EventLoop::run()) once the
+    /// engine has quit.  Custom main-loops should call it after the Event-Loop stops.
+    pub fn shutdown(&mut self) {
+        for hook in std::mem::take(&mut self.shutdown_hooks) {
+            hook(self);
+        }
+        self.close_main_thread_tasks();
+        self.shut_down_child_engines();
+        self.shut_down_subsystems();
+        self.job_pool.shutdown();
+    }
+
+    /// Returns the engine's [`JobPool`].
+    pub fn job_pool(&self) -> &JobPool {
+        &self.job_pool
+    }
+
+    /// Spawns a background job on the engine's [`JobPool`].
+    ///
+    /// When the job finishes, an [`Event::Job`] event tagged with the job's id is sent through
+    /// the engine's event queue.  The result can then be taken from the returned [`JobHandle`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wolf_engine_core as wolf_engine;
+    /// # use wolf_engine::prelude::*;
+    /// #
+    /// let (mut event_loop, mut context) = wolf_engine::init(());
+    ///
+    /// let job = context.spawn_job(|job| {
+    ///     // Do some heavy lifting, checking for cancellation along the way.
+    ///     if job.is_cancelled() {
+    ///         return None;
+    ///     }
+    ///     Some("Level data")
+    /// });
+    ///
+    /// while let Some(event) = event_loop.next_event() {
+    ///     if let Event::Job(JobEvent::Finished(id)) = event {
+    ///         assert_eq!(id, job.id());
+    ///         assert_eq!(job.take_result(), Some(Some("Level data")));
+    ///         context.quit();
+    ///     }
+    /// }
+    /// ```
+    pub fn spawn_job<T, F>(&self, job: F) -> JobHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&JobContext) -> T + Send + 'static,
+    {
+        self.job_pool.spawn(job)
+    }
 }
 
 impl<D> HasEventSender<Event> for Context<D> {
@@ -76,3 +337,23 @@ impl<D> HasEventSender<Event> for Context<D> {
         self.event_sender.clone()
     }
 }
+
+#[cfg(test)]
+mod context_resource_tests {
+    #[derive(Debug, PartialEq)]
+    struct Volume(u32);
+
+    #[test]
+    fn should_store_resources_alongside_data() {
+        let (_event_loop, mut context) = crate::init(123);
+
+        context.insert_resource(Volume(10));
+        context.resource_mut::<Volume>().unwrap().0 += 1;
+
+        assert_eq!(context.data, 123);
+        assert!(context.has_resource::<Volume>());
+        assert_eq!(context.resource::<Volume>(), Ok(&Volume(11)));
+        assert_eq!(context.remove_resource::<Volume>(), Ok(Volume(11)));
+        assert!(context.resource::<Volume>().is_err());
+    }
+}
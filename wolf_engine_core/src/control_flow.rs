@@ -0,0 +1,51 @@
+use std::time::Instant;
+
+/// Controls how the [`EventLoop`](crate::EventLoop) behaves while running with
+/// [`EventLoop::run()`](crate::EventLoop::run()).
+///
+/// The control flow is passed to the event handler on every event, and can be changed at any
+/// time.  The new value takes effect the next time the Event-Loop looks for an event.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// # use wolf_engine::prelude::*;
+/// #
+/// let (event_loop, context) = wolf_engine::init(());
+///
+/// let exit_code = event_loop.run(context, |event, _context, control_flow| {
+///     if event == Event::EventsCleared {
+///         // Stop the engine, and return an exit code.
+///         *control_flow = ControlFlow::Exit(0);
+///     }
+/// });
+///
+/// assert_eq!(exit_code, 0);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ControlFlow {
+    /// Continually emit events, even when there are none in the queue.
+    ///
+    /// This is the default behavior, and is the best choice for games which are constantly
+    /// updating, and rendering.
+    #[default]
+    Poll,
+
+    /// Block, after each frame, until a new event arrives.
+    ///
+    /// This is useful for applications, such as tools, which only need to update in response to
+    /// events.
+    Wait,
+
+    /// Block, after each frame, until a new event arrives, or the provided [`Instant`] is
+    /// reached, whichever happens first.
+    WaitUntil(Instant),
+
+    /// Shut down the engine, and return the provided exit code from
+    /// [`EventLoop::run()`](crate::EventLoop::run()).
+    ///
+    /// The [`Event::Quit`](crate::events::Event::Quit) event is still emitted, so the game has
+    /// a chance to clean up before the Event-Loop stops.
+    Exit(i32),
+}
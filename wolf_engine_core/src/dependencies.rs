@@ -0,0 +1,161 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Indicates why a list could not be ordered by [`sort_by_dependencies()`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DependencyError {
+    /// An item depends on a name which is not in the list.
+    Missing {
+        /// The name of the item.
+        name: &'static str,
+        /// The name of the missing dependency.
+        dependency: &'static str,
+    },
+    /// The dependencies of some items form a cycle, so they can't be ordered.
+    Cycle {
+        /// The names of the items which could not be ordered, in their original order.
+        names: Vec<&'static str>,
+    },
+}
+
+impl Display for DependencyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing { name, dependency } => write!(
+                f,
+                "`{}` depends on `{}`, which does not exist",
+                name, dependency
+            ),
+            Self::Cycle { names } => write!(
+                f,
+                "The dependencies of these items form a cycle: {}",
+                names.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for DependencyError {}
+
+/// Sorts the items so each one comes after its dependencies, keeping the original order wherever
+/// possible.
+///
+/// Each item is identified by the name returned by `name`, and depends on the items named by
+/// `dependencies`.  This is the ordering used for subsystems, plugins, and systems.
+///
+/// If a dependency is missing, the items are left untouched.  If the dependencies form a cycle,
+/// the items which could be ordered are moved to the front, followed by the rest, in their
+/// original order.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core::*;
+/// #
+/// let mut items = vec![("renderer", vec!["window"]), ("window", vec![]), ("audio", vec![])];
+///
+/// sort_by_dependencies(&mut items, |item| item.0, |item| item.1.clone()).unwrap();
+///
+/// let names: Vec<&str> = items.iter().map(|item| item.0).collect();
+/// assert_eq!(names, vec!["window", "renderer", "audio"]);
+/// ```
+pub fn sort_by_dependencies<T>(
+    items: &mut Vec<T>,
+    name: impl Fn(&T) -> &'static str,
+    dependencies: impl Fn(&T) -> Vec<&'static str>,
+) -> Result<(), DependencyError> {
+    let names: Vec<&'static str> = items.iter().map(&name).collect();
+    for item in items.iter() {
+        if let Some(dependency) = dependencies(item)
+            .into_iter()
+            .find(|dependency| !names.contains(dependency))
+        {
+            return Err(DependencyError::Missing {
+                name: name(item),
+                dependency,
+            });
+        }
+    }
+    let mut unsorted = std::mem::take(items);
+    let mut sorted_names = Vec::with_capacity(unsorted.len());
+    while !unsorted.is_empty() {
+        let next = unsorted.iter().position(|item| {
+            dependencies(item)
+                .iter()
+                .all(|dependency| sorted_names.contains(dependency))
+        });
+        match next {
+            Some(index) => {
+                let item = unsorted.remove(index);
+                sorted_names.push(name(&item));
+                items.push(item);
+            }
+            None => {
+                let names = unsorted.iter().map(&name).collect();
+                items.append(&mut unsorted);
+                return Err(DependencyError::Cycle { names });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod sort_by_dependencies_tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    type Item = (&'static str, Vec<&'static str>);
+
+    fn sort(items: &mut Vec<Item>) -> Result<(), DependencyError> {
+        sort_by_dependencies(items, |item| item.0, |item| item.1.clone())
+    }
+
+    fn names(items: &[Item]) -> Vec<&'static str> {
+        items.iter().map(|item| item.0).collect()
+    }
+
+    #[test_case(vec![("a", vec![]), ("b", vec![]), ("c", vec![])], vec!["a", "b", "c"] ; "no dependencies")]
+    #[test_case(vec![("a", vec!["c"]), ("b", vec![]), ("c", vec!["b"])], vec!["b", "c", "a"] ; "chain")]
+    #[test_case(vec![("a", vec!["c"]), ("b", vec![]), ("c", vec![])], vec!["b", "c", "a"] ; "original order")]
+    fn should_sort_items_after_their_dependencies(
+        mut items: Vec<Item>,
+        expected_names: Vec<&'static str>,
+    ) {
+        sort(&mut items).unwrap();
+
+        assert_eq!(names(&items), expected_names);
+    }
+
+    #[test]
+    fn should_leave_the_items_untouched_when_a_dependency_is_missing() {
+        let mut items = vec![("b", vec![]), ("a", vec!["c"])];
+
+        let result = sort(&mut items);
+
+        assert_eq!(
+            result,
+            Err(DependencyError::Missing {
+                name: "a",
+                dependency: "c"
+            })
+        );
+        assert_eq!(names(&items), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn should_keep_every_item_when_there_is_a_cycle() {
+        let mut items = vec![("a", vec!["b"]), ("b", vec!["a"]), ("c", vec![])];
+
+        let result = sort(&mut items);
+
+        assert_eq!(
+            result,
+            Err(DependencyError::Cycle {
+                names: vec!["a", "b"]
+            })
+        );
+        assert_eq!(names(&items), vec!["c", "a", "b"]);
+    }
+}
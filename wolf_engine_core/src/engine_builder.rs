@@ -0,0 +1,576 @@
+use std::any::{type_name, TypeId};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use crate::context::ShutdownHook;
+use crate::events::*;
+use crate::subsystems::Subsystems;
+use crate::{
+    Clock, Context, ControlFlow, Engine, EventLoop, FrameWatchdog, JobPool, Resources, Subsystem,
+    SubsystemError,
+};
+
+type ResourceInserter = Box<dyn FnOnce(&mut Resources)>;
+
+/// Indicates a problem with the configuration provided to an [`EngineBuilder`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum EngineBuilderError {
+    /// The fixed update rate was set to `0`.
+    ZeroFixedRate,
+    /// The target FPS was set to `0`.
+    ZeroTargetFps,
+    /// The job pool was configured to use `0` threads.
+    ZeroJobThreads,
+    /// The time scale was negative, or not a finite number.
+    InvalidTimeScale(f64),
+    /// A subsystem could not be registered, or its dependencies could not be ordered.
+    Subsystem(SubsystemError),
+    /// The engine settings could not be read from the provided config.
+    #[cfg(feature = "config")]
+    Config(crate::config::ConfigError),
+    /// More than one resource of the same type was added.
+    DuplicateResource {
+        /// The name of the duplicated type.
+        type_name: &'static str,
+    },
+}
+
+impl Display for EngineBuilderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZeroFixedRate => write!(f, "The fixed update rate must be greater than 0"),
+            Self::ZeroTargetFps => write!(f, "The target FPS must be greater than 0"),
+            Self::ZeroJobThreads => write!(f, "The job pool must have at least 1 thread"),
+            Self::InvalidTimeScale(time_scale) => write!(
+                f,
+                "The time scale must be a finite number, of at least 0, but it was {}",
+                time_scale
+            ),
+            Self::Subsystem(error) => write!(f, "{}", error),
+            #[cfg(feature = "config")]
+            Self::Config(error) => write!(f, "{}", error),
+            Self::DuplicateResource { type_name } => write!(
+                f,
+                "A resource of type `{}` was added more than once",
+                type_name
+            ),
+        }
+    }
+}
+
+impl Error for EngineBuilderError {}
+
+#[cfg(feature = "config")]
+impl From<crate::config::ConfigError> for EngineBuilderError {
+    fn from(error: crate::config::ConfigError) -> Self {
+        Self::Config(error)
+    }
+}
+
+impl From<SubsystemError> for EngineBuilderError {
+    fn from(error: SubsystemError) -> Self {
+        Self::Subsystem(error)
+    }
+}
+
+/// Provides a way to configure, and build, the [`Engine`].
+///
+/// [`init()`](crate::init()) is a shortcut for `EngineBuilder::new(data).build()` with the
+/// default options.  The builder should be used when the engine needs to be configured.  The
+/// configuration is checked by [`EngineBuilder::build()`], which returns an
+/// [`EngineBuilderError`] if there is a problem.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// use wolf_engine::prelude::*;
+///
+/// struct Score(u32);
+///
+/// let (event_loop, context) = EngineBuilder::new(())
+///     .with_fixed_rate(60)
+///     .with_job_threads(2)
+///     .with_resource(Score(0))
+///     .with_shutdown_hook(|context| println!("Final score: {}", context.resource::<Score>().unwrap().0))
+///     .build()
+///     .expect("Failed to build the engine");
+///
+/// assert_eq!(context.resource::<Score>().unwrap().0, 0);
+/// ```
+///
+/// Invalid configurations are reported when building.
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// # use wolf_engine::prelude::*;
+/// #
+/// let error = EngineBuilder::new(()).with_target_fps(0).build().err();
+///
+/// assert_eq!(error, Some(EngineBuilderError::ZeroTargetFps));
+/// ```
+pub struct EngineBuilder<D> {
+    data: D,
+    event_queue: Option<Box<dyn EventQueue<Event> + Send>>,
+    control_flow: ControlFlow,
+    frame_phases: Option<Vec<FramePhase>>,
+    frame_watchdog: Option<FrameWatchdog>,
+    clock: Clock,
+    time_scale: Option<f64>,
+    fixed_rate: Option<u32>,
+    target_fps: Option<u32>,
+    job_threads: Option<usize>,
+    resource_types: Vec<(TypeId, &'static str)>,
+    resource_inserters: Vec<ResourceInserter>,
+    shutdown_hooks: Vec<ShutdownHook<D>>,
+    subsystems: Subsystems<D>,
+    subsystem_error: Option<SubsystemError>,
+    #[cfg(feature = "config")]
+    config_error: Option<crate::config::ConfigError>,
+}
+
+impl<D> EngineBuilder<D> {
+    /// Creates a new builder, with the default options, and the provided data.
+    pub fn new(data: D) -> Self {
+        Self {
+            data,
+            event_queue: None,
+            control_flow: ControlFlow::default(),
+            frame_phases: None,
+            frame_watchdog: None,
+            clock: Clock::new(),
+            time_scale: None,
+            fixed_rate: None,
+            target_fps: None,
+            job_threads: None,
+            resource_types: Vec::new(),
+            resource_inserters: Vec::new(),
+            shutdown_hooks: Vec::new(),
+            subsystems: Subsystems::new(),
+            subsystem_error: None,
+            #[cfg(feature = "config")]
+            config_error: None,
+        }
+    }
+
+    /// Uses the provided [`EventQueue`] instead of the default
+    /// [`MpscEventQueue`](crate::events::MpscEventQueue).
+    pub fn with_event_queue<Q: EventQueue<Event> + Send + 'static>(
+        mut self,
+        event_queue: Q,
+    ) -> Self {
+        self.event_queue = Some(Box::new(event_queue));
+        self
+    }
+
+    /// Sets the [`ControlFlow`] [`EventLoop::run()`] starts with.
+    pub fn with_control_flow(mut self, control_flow: ControlFlow) -> Self {
+        self.control_flow = control_flow;
+        self
+    }
+
+    /// Sets the Event-Loop's [frame phases](EventLoop::set_frame_phases()).
+    pub fn with_frame_phases<I: IntoIterator<Item = FramePhase>>(
+        mut self,
+        frame_phases: I,
+    ) -> Self {
+        self.frame_phases = Some(frame_phases.into_iter().collect());
+        self
+    }
+
+    /// Sets the Event-Loop's [`FrameWatchdog`].
+    pub fn with_frame_watchdog(mut self, frame_watchdog: FrameWatchdog) -> Self {
+        self.frame_watchdog = Some(frame_watchdog);
+        self
+    }
+
+    /// Uses the provided [`Clock`] instead of a new one.
+    ///
+    /// The time scale, and fixed rate options are applied on top of the provided clock.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets the [time scale](Clock::set_time_scale()) of the clock.
+    pub fn with_time_scale(mut self, time_scale: f64) -> Self {
+        self.time_scale = Some(time_scale);
+        self
+    }
+
+    /// Sets the number of [fixed steps](Clock::consume_fixed_step()) per second.
+    pub fn with_fixed_rate(mut self, fixed_rate: u32) -> Self {
+        self.fixed_rate = Some(fixed_rate);
+        self
+    }
+
+    /// Limits the number of frames per second [`EventLoop::run()`] will run at.
+    pub fn with_target_fps(mut self, target_fps: u32) -> Self {
+        self.target_fps = Some(target_fps);
+        self
+    }
+
+    /// Sets the number of threads used by the [`JobPool`].
+    pub fn with_job_threads(mut self, job_threads: usize) -> Self {
+        self.job_threads = Some(job_threads);
+        self
+    }
+
+    /// Adds a resource, which will be inserted into the [`Context`].
+    ///
+    /// Only one resource of each type may be added.
+    pub fn with_resource<T: Send + 'static>(mut self, resource: T) -> Self {
+        self.resource_types
+            .push((TypeId::of::<T>(), type_name::<T>()));
+        self.resource_inserters.push(Box::new(move |resources| {
+            resources.insert(resource);
+        }));
+        self
+    }
+
+    /// Adds a hook, which will be called when the engine shuts down.
+    ///
+    /// See [`Context::add_shutdown_hook()`] for details.
+    pub fn with_shutdown_hook<F>(mut self, hook: F) -> Self
+    where
+        F: FnOnce(&mut Context<D>) + Send + 'static,
+    {
+        self.shutdown_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Registers a [`Subsystem`] on the [`Context`].
+    ///
+    /// Duplicate subsystems, missing dependencies, and dependency cycles are reported by
+    /// [`EngineBuilder::build()`].
+    pub fn with_subsystem<S: Subsystem<D> + 'static>(mut self, subsystem: S) -> Self {
+        if let Err(error) = self.subsystems.add(Box::new(subsystem)) {
+            self.subsystem_error.get_or_insert(error);
+        }
+        self
+    }
+
+    /// Applies the [`EngineSettings`](crate::config::EngineSettings) from the config, then adds
+    /// the [`Config`](crate::config::Config) as a resource.
+    ///
+    /// Settings which the config leaves unset keep the values already set on the builder.
+    /// Invalid engine settings are reported by [`EngineBuilder::build()`].  The log level is not
+    /// applied, because the builder does not set up logging.
+    #[cfg(feature = "config")]
+    pub fn with_config(mut self, config: crate::config::Config) -> Self {
+        match config.engine() {
+            Ok(settings) => {
+                self.time_scale = settings.time_scale.or(self.time_scale);
+                self.target_fps = settings.target_fps.or(self.target_fps);
+                self.fixed_rate = settings.fixed_rate.or(self.fixed_rate);
+                self.job_threads = settings.job_threads.or(self.job_threads);
+            }
+            Err(error) => {
+                self.config_error.get_or_insert(error);
+            }
+        }
+        self.with_resource(config)
+    }
+
+    /// Checks the configuration, and builds the [`Engine`].
+    pub fn build(mut self) -> Result<Engine<D>, EngineBuilderError> {
+        self.validate()?;
+        #[cfg(feature = "config")]
+        if let Some(error) = self.config_error {
+            return Err(error.into());
+        }
+        if let Some(error) = self.subsystem_error {
+            return Err(error.into());
+        }
+        self.subsystems.validate()?;
+
+        let mut event_loop = match self.event_queue {
+            Some(event_queue) => EventLoop::with_event_queue(event_queue),
+            None => EventLoop::new(),
+        };
+        event_loop.set_control_flow(self.control_flow);
+        event_loop.set_target_fps(self.target_fps);
+        if let Some(frame_phases) = self.frame_phases {
+            event_loop.set_frame_phases(frame_phases);
+        }
+        if let Some(frame_watchdog) = self.frame_watchdog {
+            event_loop.set_frame_watchdog(frame_watchdog);
+        }
+
+        let mut context = Context::new(&event_loop, self.data);
+        let mut clock = self.clock;
+        if let Some(time_scale) = self.time_scale {
+            clock.set_time_scale(time_scale);
+        }
+        if let Some(fixed_rate) = self.fixed_rate {
+            clock.set_fixed_rate(fixed_rate);
+        }
+        *context.clock_mut() = clock;
+        if let Some(job_threads) = self.job_threads {
+            let event_sender: Arc<dyn EventSender<Event>> = context.event_sender();
+            context.set_job_pool(JobPool::with_thread_count(event_sender, job_threads));
+        }
+        for insert_resource in self.resource_inserters {
+            insert_resource(context.resources_mut());
+        }
+        context.shutdown_hooks = self.shutdown_hooks;
+        context.subsystems = self.subsystems;
+
+        Ok((event_loop, context))
+    }
+
+    fn validate(&self) -> Result<(), EngineBuilderError> {
+        if self.fixed_rate == Some(0) {
+            return Err(EngineBuilderError::ZeroFixedRate);
+        }
+        if self.target_fps == Some(0) {
+            return Err(EngineBuilderError::ZeroTargetFps);
+        }
+        if self.job_threads == Some(0) {
+            return Err(EngineBuilderError::ZeroJobThreads);
+        }
+        if let Some(time_scale) = self.time_scale {
+            if !time_scale.is_finite() || time_scale < 0.0 {
+                return Err(EngineBuilderError::InvalidTimeScale(time_scale));
+            }
+        }
+        for (index, (type_id, type_name)) in self.resource_types.iter().enumerate() {
+            if self.resource_types[..index]
+                .iter()
+                .any(|(other_type_id, _)| other_type_id == type_id)
+            {
+                return Err(EngineBuilderError::DuplicateResource { type_name });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod engine_builder_tests {
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::time::Duration;
+
+    use ntest::timeout;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Score(u32);
+
+    #[test]
+    fn should_build_with_default_options() {
+        let (event_loop, context) = EngineBuilder::new(0).build().unwrap();
+
+        assert_eq!(event_loop.control_flow(), ControlFlow::Poll);
+        assert_eq!(event_loop.target_fps(), None);
+        assert_eq!(context.clock().time_scale(), 1.0);
+        assert_eq!(context.clock().fixed_timestep(), None);
+        assert!(context.resources().is_empty());
+    }
+
+    #[test]
+    fn should_apply_options() {
+        let (event_loop, context) = EngineBuilder::new(())
+            .with_event_queue(MpscEventQueue::new())
+            .with_control_flow(ControlFlow::Wait)
+            .with_frame_phases([FramePhase::Update])
+            .with_frame_watchdog(FrameWatchdog::new(Duration::from_millis(16)))
+            .with_time_scale(0.5)
+            .with_fixed_rate(50)
+            .with_target_fps(30)
+            .with_job_threads(3)
+            .with_resource(Score(7))
+            .build()
+            .unwrap();
+
+        assert_eq!(event_loop.control_flow(), ControlFlow::Wait);
+        assert_eq!(event_loop.frame_phases(), &[FramePhase::Update]);
+        assert!(event_loop.frame_watchdog().is_some());
+        assert_eq!(event_loop.target_fps(), Some(30));
+        assert_eq!(context.clock().time_scale(), 0.5);
+        assert_eq!(
+            context.clock().fixed_timestep(),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(context.job_pool().thread_count(), 3);
+        assert_eq!(context.resource::<Score>(), Ok(&Score(7)));
+    }
+
+    #[test]
+    fn should_reject_invalid_options() {
+        let build_error = |builder: EngineBuilder<()>| builder.build().err();
+
+        assert_eq!(
+            build_error(EngineBuilder::new(()).with_fixed_rate(0)),
+            Some(EngineBuilderError::ZeroFixedRate)
+        );
+        assert_eq!(
+            build_error(EngineBuilder::new(()).with_target_fps(0)),
+            Some(EngineBuilderError::ZeroTargetFps)
+        );
+        assert_eq!(
+            build_error(EngineBuilder::new(()).with_job_threads(0)),
+            Some(EngineBuilderError::ZeroJobThreads)
+        );
+        assert_eq!(
+            build_error(EngineBuilder::new(()).with_time_scale(-1.0)),
+            Some(EngineBuilderError::InvalidTimeScale(-1.0))
+        );
+    }
+
+    #[test]
+    fn should_reject_duplicate_resources() {
+        let error = EngineBuilder::new(())
+            .with_resource(Score(1))
+            .with_resource(String::from("Not a duplicate"))
+            .with_resource(Score(2))
+            .build()
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error,
+            EngineBuilderError::DuplicateResource {
+                type_name: type_name::<Score>()
+            }
+        );
+        assert!(error.to_string().contains("Score"));
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn should_apply_engine_settings_from_the_config() {
+        let config = crate::config::ConfigLoader::new()
+            .with_defaults("[engine]\ntarget_fps = 30\nfixed_rate = 20\ntime_scale = 2.0\n")
+            .load()
+            .unwrap();
+
+        let (event_loop, context) = EngineBuilder::new(()).with_config(config).build().unwrap();
+
+        assert_eq!(event_loop.target_fps(), Some(30));
+        assert_eq!(
+            context.clock().fixed_timestep(),
+            Some(Duration::from_millis(50))
+        );
+        assert_eq!(context.clock().time_scale(), 2.0);
+        assert!(context.has_resource::<crate::config::Config>());
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn should_keep_options_the_config_does_not_set() {
+        let config = crate::config::ConfigLoader::new()
+            .with_defaults("[engine]\ntarget_fps = 30\n")
+            .load()
+            .unwrap();
+
+        let (event_loop, context) = EngineBuilder::new(())
+            .with_time_scale(0.5)
+            .with_fixed_rate(20)
+            .with_config(config)
+            .build()
+            .unwrap();
+
+        assert_eq!(event_loop.target_fps(), Some(30));
+        assert_eq!(context.clock().time_scale(), 0.5);
+        assert_eq!(
+            context.clock().fixed_timestep(),
+            Some(Duration::from_millis(50))
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn should_report_invalid_engine_settings_from_the_config() {
+        let config = crate::config::ConfigLoader::new()
+            .with_override("engine.target_fps", "-1")
+            .load()
+            .unwrap();
+
+        let error = EngineBuilder::new(()).with_config(config).build().err();
+
+        assert!(matches!(error, Some(EngineBuilderError::Config(_))));
+    }
+
+    struct TestSubsystem(&'static str, Vec<&'static str>);
+
+    impl Subsystem<()> for TestSubsystem {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn dependencies(&self) -> Vec<&'static str> {
+            self.1.clone()
+        }
+    }
+
+    #[test]
+    fn should_register_subsystems_in_dependency_order() {
+        let (_event_loop, context) = EngineBuilder::new(())
+            .with_subsystem(TestSubsystem("renderer", vec!["window"]))
+            .with_subsystem(TestSubsystem("window", vec![]))
+            .build()
+            .unwrap();
+
+        assert_eq!(context.subsystem_names(), vec!["window", "renderer"]);
+    }
+
+    #[test]
+    fn should_reject_invalid_subsystems() {
+        let duplicate_error = EngineBuilder::new(())
+            .with_subsystem(TestSubsystem("window", vec![]))
+            .with_subsystem(TestSubsystem("window", vec![]))
+            .build()
+            .err();
+        let missing_error = EngineBuilder::new(())
+            .with_subsystem(TestSubsystem("renderer", vec!["window"]))
+            .build()
+            .err();
+
+        assert_eq!(
+            duplicate_error,
+            Some(EngineBuilderError::Subsystem(SubsystemError::Duplicate {
+                name: "window"
+            }))
+        );
+        assert_eq!(
+            missing_error,
+            Some(EngineBuilderError::Subsystem(
+                SubsystemError::MissingDependency {
+                    name: "renderer",
+                    dependency: "window"
+                }
+            ))
+        );
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_call_shutdown_hooks_in_order_after_quitting() {
+        let calls = Arc::new(AtomicI32::new(0));
+        let first_calls = calls.clone();
+        let second_calls = calls.clone();
+        let (event_loop, context) = EngineBuilder::new(())
+            .with_shutdown_hook(move |_context| {
+                assert_eq!(first_calls.load(Ordering::SeqCst), 0);
+                first_calls.store(1, Ordering::SeqCst);
+            })
+            .with_shutdown_hook(move |context| {
+                assert_eq!(second_calls.load(Ordering::SeqCst), 1);
+                assert!(!context.job_pool().is_shut_down());
+                second_calls.store(2, Ordering::SeqCst);
+            })
+            .build()
+            .unwrap();
+
+        event_loop.run(context, |event, context, _control_flow| {
+            if event == Event::EventsCleared {
+                context.quit();
+            }
+        });
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,6 +1,8 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::events::*;
+use crate::{Context, ControlFlow, FrameWatchdog};
 
 /// Provides a way to retrieve events from the [`Context`](crate::Context).
 ///
@@ -16,6 +18,9 @@ use crate::events::*;
 /// as the engine is running.  When [`Event::Quit`] is received, the Event-Loop will trigger a
 /// shutdown of the engine.  Only after a shutdown, will the Event-Loop stop emitting events.
 ///
+/// The events emitted at the end of each frame can be replaced with a custom sequence of
+/// [frame phases](FramePhase) using [`EventLoop::set_frame_phases()`].
+///
 /// # Examples
 ///
 /// ## Creating an `EventLoop`
@@ -46,17 +51,225 @@ use crate::events::*;
 /// #   break;
 /// }
 /// ```
+///
+/// ## Running the Event-Loop With a Closure
+///
+/// Instead of writing the main-loop by hand, [`EventLoop::run()`] can drive the engine for you.
+/// The [`ControlFlow`] can be changed from inside the closure to control how the Event-Loop
+/// waits for new events, or to shut down the engine with an exit code.
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// # use wolf_engine::prelude::*;
+/// #
+/// let (event_loop, context) = wolf_engine::init(());
+///
+/// let exit_code = event_loop.run(context, |event, context, control_flow| {
+///     match event {
+///         Event::EventsCleared => *control_flow = ControlFlow::Exit(0),
+///         Event::Quit => println!("Goodbye!"),
+///         _ => (),
+///     }
+/// });
+/// #
+/// # assert_eq!(exit_code, 0);
+/// ```
+///
+/// ## Using Frame Phases
+///
+/// Rather than doing everything when [`Event::EventsCleared`] is emitted, the frame can be split
+/// up into multiple phases.  Each phase is emitted, in order, once the event queue is empty.
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// # use wolf_engine::prelude::*;
+/// #
+/// let (mut event_loop, mut context) = wolf_engine::init(());
+/// event_loop.set_frame_phases(FramePhase::standard_phases());
+/// event_loop.add_frame_phase(FramePhase::Custom("Debug Overlay"));
+///
+/// while let Some(event) = event_loop.next_event() {
+///     match event {
+///         Event::FramePhase(FramePhase::Update) => (), // Update the game.
+///         Event::FramePhase(FramePhase::Render) => (), // Render the game.
+///         Event::FramePhase(FramePhase::Custom("Debug Overlay")) => context.quit(),
+///         _ => (),
+///     }
+/// }
+/// ```
 pub struct EventLoop {
-    event_queue: MpscEventQueue<Event>,
+    event_queue: Box<dyn EventQueue<Event> + Send>,
+    frame_phases: Vec<FramePhase>,
+    /// Phases set during a frame, which replace `frame_phases` when the next frame starts.
+    pending_frame_phases: Option<Vec<FramePhase>>,
+    next_frame_phase: usize,
+    frame_watchdog: Option<FrameWatchdog>,
+    control_flow: ControlFlow,
+    target_fps: Option<u32>,
     has_quit: bool,
+    has_started_frame: bool,
+    has_finished_frame: bool,
 }
 
 impl EventLoop {
     pub(crate) fn new() -> Self {
-        let event_queue = MpscEventQueue::new();
+        Self::with_event_queue(Box::new(MpscEventQueue::new()))
+    }
+
+    pub(crate) fn with_event_queue(event_queue: Box<dyn EventQueue<Event> + Send>) -> Self {
+        let frame_phases = Self::default_frame_phases();
         Self {
             event_queue,
+            next_frame_phase: frame_phases.len(),
+            frame_phases,
+            pending_frame_phases: None,
+            frame_watchdog: None,
+            control_flow: ControlFlow::default(),
+            target_fps: None,
             has_quit: false,
+            has_started_frame: false,
+            has_finished_frame: false,
+        }
+    }
+
+    fn default_frame_phases() -> Vec<FramePhase> {
+        vec![FramePhase::EventsCleared]
+    }
+
+    /// Returns the phases emitted, in order, at the end of each frame.
+    ///
+    /// This includes changes made during the current frame, which take effect starting with
+    /// the next frame.
+    pub fn frame_phases(&self) -> &[FramePhase] {
+        self.pending_frame_phases
+            .as_deref()
+            .unwrap_or(&self.frame_phases)
+    }
+
+    /// Replaces the phases emitted, in order, at the end of each frame.
+    ///
+    /// Providing an empty list of phases restores the default behavior of emitting
+    /// [`Event::EventsCleared`].  The new phases take effect starting with the next frame, so
+    /// the current frame still emits the rest of its phases.
+    pub fn set_frame_phases<I: IntoIterator<Item = FramePhase>>(&mut self, frame_phases: I) {
+        let mut frame_phases: Vec<FramePhase> = frame_phases.into_iter().collect();
+        if frame_phases.is_empty() {
+            frame_phases = Self::default_frame_phases();
+        }
+        self.pending_frame_phases = Some(frame_phases);
+    }
+
+    /// Adds a phase to the end of the frame-phase sequence.
+    ///
+    /// The new phase takes effect starting with the next frame.
+    pub fn add_frame_phase(&mut self, frame_phase: FramePhase) {
+        self.pending_frame_phases
+            .get_or_insert_with(|| self.frame_phases.clone())
+            .push(frame_phase);
+    }
+
+    /// Returns the [`FrameWatchdog`], if one has been set.
+    pub fn frame_watchdog(&self) -> Option<&FrameWatchdog> {
+        self.frame_watchdog.as_ref()
+    }
+
+    /// Sets the [`FrameWatchdog`] used to detect frames which go over budget.
+    pub fn set_frame_watchdog(&mut self, frame_watchdog: FrameWatchdog) {
+        self.frame_watchdog = Some(frame_watchdog);
+    }
+
+    /// Removes the [`FrameWatchdog`], if one has been set.
+    pub fn remove_frame_watchdog(&mut self) -> Option<FrameWatchdog> {
+        self.frame_watchdog.take()
+    }
+
+    /// Returns the [`ControlFlow`] used when [`EventLoop::run()`] starts.
+    pub fn control_flow(&self) -> ControlFlow {
+        self.control_flow
+    }
+
+    /// Sets the [`ControlFlow`] used when [`EventLoop::run()`] starts.
+    pub fn set_control_flow(&mut self, control_flow: ControlFlow) {
+        self.control_flow = control_flow;
+    }
+
+    /// Returns the maximum number of frames per second [`EventLoop::run()`] will run at, if
+    /// there is a limit.
+    pub fn target_fps(&self) -> Option<u32> {
+        self.target_fps
+    }
+
+    /// Limits the number of frames per second [`EventLoop::run()`] will run at, while using
+    /// [`ControlFlow::Poll`].
+    ///
+    /// Setting the limit to `None`, or `Some(0)`, removes the limit.
+    pub fn set_target_fps(&mut self, target_fps: Option<u32>) {
+        self.target_fps = target_fps.filter(|target_fps| *target_fps > 0);
+    }
+
+    /// Runs the engine until it quits, passing every event to the provided event handler.
+    ///
+    /// The event handler is given the [`Event`], the [`Context`], and the current
+    /// [`ControlFlow`], which starts out as [`EventLoop::control_flow()`].  If a
+    /// [target FPS](EventLoop::set_target_fps()) has been set, the Event-Loop will sleep at the
+    /// end of each frame to avoid running faster than the target.
+    ///
+    /// Returns the exit code set by [`ControlFlow::Exit`], or `0` if the engine was shut down in
+    /// some other way, such as by calling [`Context::quit()`].  The exit code is suitable for
+    /// passing to [`std::process::exit()`].
+    ///
+    /// [`Context::start_frame()`] is called at the start of every frame, before the first
+    /// [frame phase](EventLoop::frame_phases()) is passed to the event handler.  Once the engine
+    /// has quit, [`Context::shutdown()`] is called before returning.
+    pub fn run<D, F>(mut self, mut context: Context<D>, mut event_handler: F) -> i32
+    where
+        F: FnMut(Event, &mut Context<D>, &mut ControlFlow),
+    {
+        let mut control_flow = self.control_flow;
+        let mut exit_code = None;
+        let mut frame_started_at = Instant::now();
+        while let Some(event) = self.next_event_with_control_flow(control_flow) {
+            if self.has_started_frame {
+                frame_started_at = Instant::now();
+                context.start_frame();
+            }
+            context.notify_subsystems(event);
+            event_handler(event, &mut context, &mut control_flow);
+            if self.has_finished_frame && control_flow == ControlFlow::Poll {
+                self.limit_frame_rate(frame_started_at);
+            }
+            if let ControlFlow::Exit(code) = control_flow {
+                if exit_code.is_none() {
+                    exit_code = Some(code);
+                    context.quit();
+                }
+            }
+        }
+        context.shutdown();
+        exit_code.unwrap_or(0)
+    }
+
+    fn limit_frame_rate(&self, frame_started_at: Instant) {
+        if let Some(target_fps) = self.target_fps {
+            let target_frame_time = Duration::from_secs(1) / target_fps;
+            let frame_time = frame_started_at.elapsed();
+            if frame_time < target_frame_time {
+                std::thread::sleep(target_frame_time - frame_time);
+            }
+        }
+    }
+
+    fn next_event_with_control_flow(&mut self, control_flow: ControlFlow) -> Option<Event> {
+        if !self.has_finished_frame || self.has_quit {
+            return self.next_event();
+        }
+        match control_flow {
+            ControlFlow::Wait => self.wait_for_event(None),
+            ControlFlow::WaitUntil(instant) => {
+                let timeout = instant.saturating_duration_since(Instant::now());
+                self.wait_for_event(Some(timeout))
+            }
+            _ => self.next_event(),
         }
     }
 
@@ -64,24 +277,105 @@ impl EventLoop {
         if event == Event::Quit {
             self.has_quit = true;
         }
+        self.has_started_frame = false;
+        self.has_finished_frame = false;
         event
     }
 
-    fn handle_empty_event(&self) -> Option<Event> {
+    fn handle_empty_event(&mut self) -> Option<Event> {
         if self.has_quit {
             None
         } else {
-            Some(Event::EventsCleared)
+            if let Some(frame_phases) = self.pending_frame_phases.take() {
+                self.frame_phases = frame_phases;
+            }
+            self.next_frame_phase = 0;
+            self.emit_next_frame_phase()
+        }
+    }
+
+    /// Returns `true` if the first phase of the current frame was just emitted.
+    ///
+    /// Custom main-loops use this to call [`Context::start_frame()`] once per frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wolf_engine_core as wolf_engine;
+    /// # use wolf_engine::prelude::*;
+    /// #
+    /// let (mut event_loop, mut context) = wolf_engine::init(());
+    ///
+    /// while let Some(event) = event_loop.next_event() {
+    ///     if event_loop.has_started_frame() {
+    ///         context.start_frame();
+    ///     }
+    ///     if event == Event::EventsCleared {
+    ///         context.quit();
+    ///     }
+    /// }
+    /// ```
+    pub fn has_started_frame(&self) -> bool {
+        self.has_started_frame
+    }
+
+    /// Returns `true` if the last phase of the current frame has been emitted.
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn has_finished_frame(&self) -> bool {
+        self.has_finished_frame
+    }
+
+    fn watch_event(&mut self, event: Option<Event>) -> Option<Event> {
+        if let (Some(frame_watchdog), Some(event)) = (self.frame_watchdog.as_mut(), event) {
+            let now = Instant::now();
+            frame_watchdog.record_event(event, now);
+            if self.has_finished_frame {
+                if let Some(frame_timing) = frame_watchdog.finish_frame(now) {
+                    self.event_queue
+                        .event_sender()
+                        .send_event(Event::FrameOverBudget(frame_timing))
+                        .ok();
+                }
+            }
         }
+        event
+    }
+
+    fn emit_next_frame_phase(&mut self) -> Option<Event> {
+        let frame_phase = *self.frame_phases.get(self.next_frame_phase)?;
+        self.has_started_frame = self.next_frame_phase == 0;
+        self.next_frame_phase += 1;
+        self.has_finished_frame = self.next_frame_phase == self.frame_phases.len();
+        Some(frame_phase.to_event())
     }
 }
 
 impl EventQueue<Event> for EventLoop {
     fn next_event(&mut self) -> Option<Event> {
-        match self.event_queue.next_event() {
+        let event = match self.emit_next_frame_phase() {
+            Some(frame_phase) => Some(frame_phase),
+            None => match self.event_queue.next_event() {
+                Some(event) => Some(self.handle_event(event)),
+                None => self.handle_empty_event(),
+            },
+        };
+        self.watch_event(event)
+    }
+
+    fn wait_for_event(&mut self, timeout: Option<Duration>) -> Option<Event> {
+        if let Some(frame_phase) = self.emit_next_frame_phase() {
+            return self.watch_event(Some(frame_phase));
+        }
+        let wait_started_at = Instant::now();
+        let event = self.event_queue.wait_for_event(timeout);
+        if let Some(frame_watchdog) = self.frame_watchdog.as_mut() {
+            frame_watchdog.record_idle_time(wait_started_at.elapsed());
+        }
+        let event = match event {
             Some(event) => Some(self.handle_event(event)),
             None => self.handle_empty_event(),
-        }
+        };
+        self.watch_event(event)
     }
 }
 
@@ -93,6 +387,8 @@ impl HasEventSender<Event> for EventLoop {
 
 #[cfg(test)]
 mod event_loop_tests {
+    use std::time::{Duration, Instant};
+
     use ntest::timeout;
 
     use crate::prelude::*;
@@ -120,6 +416,133 @@ mod event_loop_tests {
         assert_eq!(context.data.updates, 3);
     }
 
+    #[test]
+    #[timeout(100)]
+    fn should_run_with_a_closure_and_return_the_exit_code() {
+        let (event_loop, context) = crate::init(TestData::new());
+
+        let exit_code = event_loop.run(context, |event, context, control_flow| {
+            if event == Event::EventsCleared {
+                if context.data.updates == 3 {
+                    *control_flow = ControlFlow::Exit(42);
+                } else {
+                    context.data.updates += 1;
+                }
+            }
+        });
+
+        assert_eq!(exit_code, 42);
+    }
+
+    #[test]
+    #[timeout(100)]
+    fn should_emit_quit_before_exiting() {
+        let (event_loop, context) = crate::init(TestData::new());
+        let mut has_received_quit = false;
+
+        event_loop.run(context, |event, _context, control_flow| match event {
+            Event::EventsCleared => *control_flow = ControlFlow::Exit(0),
+            Event::Quit => has_received_quit = true,
+            _ => (),
+        });
+
+        assert!(has_received_quit);
+    }
+
+    #[test]
+    #[timeout(100)]
+    fn should_return_zero_when_quit_through_the_context() {
+        let (event_loop, context) = crate::init(TestData::new());
+
+        let exit_code = event_loop.run(context, |event, context, _control_flow| {
+            if event == Event::EventsCleared {
+                context.quit();
+            }
+        });
+
+        assert_eq!(exit_code, 0);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_block_until_an_event_arrives_when_waiting() {
+        let (event_loop, context) = crate::init(TestData::new());
+        let event_sender = context.event_sender();
+        let started_at = Instant::now();
+
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            event_sender.send_event(Event::Test).unwrap();
+        });
+
+        event_loop.run(context, |event, context, control_flow| match event {
+            Event::EventsCleared => {
+                context.data.updates += 1;
+                *control_flow = ControlFlow::Wait;
+            }
+            Event::Test => *control_flow = ControlFlow::Exit(context.data.updates),
+            _ => (),
+        });
+
+        assert!(started_at.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_emit_events_cleared_when_the_wait_times_out() {
+        let (event_loop, context) = crate::init(TestData::new());
+        let started_at = Instant::now();
+
+        let updates = event_loop.run(context, |event, context, control_flow| {
+            if event == Event::EventsCleared {
+                context.data.updates += 1;
+                if context.data.updates == 3 {
+                    *control_flow = ControlFlow::Exit(context.data.updates);
+                } else {
+                    let deadline = Instant::now() + Duration::from_millis(10);
+                    *control_flow = ControlFlow::WaitUntil(deadline);
+                }
+            }
+        });
+
+        assert_eq!(updates, 3);
+        assert!(started_at.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_limit_the_frame_rate_to_the_target_fps() {
+        let (mut event_loop, context) = crate::init(TestData::new());
+        event_loop.set_target_fps(Some(100));
+        let started_at = Instant::now();
+
+        event_loop.run(context, |event, context, control_flow| {
+            if event == Event::EventsCleared {
+                context.data.updates += 1;
+                if context.data.updates == 5 {
+                    *control_flow = ControlFlow::Exit(0);
+                }
+            }
+        });
+
+        assert!(started_at.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_shut_down_the_job_pool_after_quitting() {
+        let (event_loop, context) = crate::init(TestData::new());
+        let job_pool = context.job_pool().clone();
+
+        event_loop.run(context, |event, context, _control_flow| {
+            if event == Event::EventsCleared {
+                context.quit();
+            }
+        });
+
+        assert!(job_pool.is_shut_down());
+    }
+
     fn process_event(event: Event, context: &mut Context<TestData>) {
         match event {
             Event::Quit => (),
@@ -152,3 +575,118 @@ fn should_emit_events_cleared_when_event_queue_is_empty() {
         "The event-loop did not emit the expected EventsCleared event."
     );
 }
+
+#[cfg(test)]
+mod frame_phase_tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn should_emit_only_events_cleared_by_default() {
+        let (mut event_loop, _context) = crate::init(());
+
+        assert_eq!(event_loop.frame_phases(), &[FramePhase::EventsCleared]);
+        assert_eq!(event_loop.next_event(), Some(Event::EventsCleared));
+        assert_eq!(event_loop.next_event(), Some(Event::EventsCleared));
+    }
+
+    #[test]
+    fn should_emit_frame_phases_in_order_after_queued_events() {
+        let (mut event_loop, context) = crate::init(());
+        event_loop.set_frame_phases(FramePhase::standard_phases());
+        context.event_sender().send_event(Event::Test).unwrap();
+
+        assert_eq!(event_loop.next_event(), Some(Event::Test));
+        for expected_phase in FramePhase::standard_phases() {
+            assert_eq!(
+                event_loop.next_event(),
+                Some(Event::FramePhase(expected_phase))
+            );
+        }
+        assert_eq!(
+            event_loop.next_event(),
+            Some(Event::FramePhase(FramePhase::PreUpdate))
+        );
+    }
+
+    #[test]
+    fn should_finish_the_frame_before_emitting_new_events() {
+        let (mut event_loop, context) = crate::init(());
+        event_loop.set_frame_phases(FramePhase::standard_phases());
+
+        assert_eq!(
+            event_loop.next_event(),
+            Some(Event::FramePhase(FramePhase::PreUpdate))
+        );
+        context.event_sender().send_event(Event::Test).unwrap();
+        for expected_phase in FramePhase::standard_phases().into_iter().skip(1) {
+            assert_eq!(
+                event_loop.next_event(),
+                Some(Event::FramePhase(expected_phase))
+            );
+        }
+        assert_eq!(event_loop.next_event(), Some(Event::Test));
+    }
+
+    #[test]
+    fn should_finish_the_current_frame_when_the_phases_change() {
+        let (mut event_loop, _context) = crate::init(());
+        event_loop.set_frame_phases(FramePhase::standard_phases());
+
+        assert_eq!(
+            event_loop.next_event(),
+            Some(Event::FramePhase(FramePhase::PreUpdate))
+        );
+        event_loop.set_frame_phases([FramePhase::Update]);
+        event_loop.add_frame_phase(FramePhase::Custom("Debug Overlay"));
+        assert_eq!(
+            event_loop.frame_phases(),
+            &[FramePhase::Update, FramePhase::Custom("Debug Overlay")]
+        );
+        for expected_phase in FramePhase::standard_phases().into_iter().skip(1) {
+            assert!(!event_loop.has_finished_frame());
+            assert_eq!(
+                event_loop.next_event(),
+                Some(Event::FramePhase(expected_phase))
+            );
+        }
+        assert!(event_loop.has_finished_frame());
+
+        assert_eq!(
+            event_loop.next_event(),
+            Some(Event::FramePhase(FramePhase::Update))
+        );
+        assert!(event_loop.has_started_frame());
+        assert_eq!(
+            event_loop.next_event(),
+            Some(Event::FramePhase(FramePhase::Custom("Debug Overlay")))
+        );
+        assert!(event_loop.has_finished_frame());
+    }
+
+    #[test]
+    fn should_emit_custom_phases_in_the_order_they_were_added() {
+        let (mut event_loop, _context) = crate::init(());
+        event_loop.set_frame_phases([FramePhase::Update]);
+        event_loop.add_frame_phase(FramePhase::Custom("Late Camera"));
+        event_loop.add_frame_phase(FramePhase::EventsCleared);
+
+        assert_eq!(
+            event_loop.next_event(),
+            Some(Event::FramePhase(FramePhase::Update))
+        );
+        assert_eq!(
+            event_loop.next_event(),
+            Some(Event::FramePhase(FramePhase::Custom("Late Camera")))
+        );
+        assert_eq!(event_loop.next_event(), Some(Event::EventsCleared));
+    }
+
+    #[test]
+    fn should_restore_the_default_phases_when_given_an_empty_list() {
+        let (mut event_loop, _context) = crate::init(());
+        event_loop.set_frame_phases(FramePhase::standard_phases());
+        event_loop.set_frame_phases([]);
+
+        assert_eq!(event_loop.frame_phases(), &[FramePhase::EventsCleared]);
+    }
+}
@@ -1,8 +1,149 @@
+use std::time::Duration;
+
+use crate::{ChildEngineId, JobId};
+
 /// Provides the events used by the window API.
 #[non_exhaustive]
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum WindowEvent {}
 
+/// Identifies a single phase of a frame.
+///
+/// Once the event queue is empty, the [`EventLoop`](crate::EventLoop) emits each of its
+/// configured frame phases, in order, as [`Event::FramePhase`] events.  By default, only
+/// [`FramePhase::EventsCleared`] is emitted, so frame phases must be enabled with
+/// [`EventLoop::set_frame_phases()`](crate::EventLoop::set_frame_phases()).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum FramePhase {
+    /// Runs before the main update.  Usually used for processing input.
+    PreUpdate,
+
+    /// The main update.  Most game logic goes here.
+    Update,
+
+    /// Runs after the main update.  Usually used for things like camera updates.
+    PostUpdate,
+
+    /// Draws the current frame.
+    Render,
+
+    /// Indicates the end of the frame.
+    FrameEnd,
+
+    /// A custom, user-defined phase.
+    Custom(&'static str),
+
+    /// Emitted as [`Event::EventsCleared`], rather than as an [`Event::FramePhase`].
+    ///
+    /// This is the only phase emitted by default.
+    EventsCleared,
+}
+
+impl FramePhase {
+    /// Returns the standard sequence of frame phases.
+    ///
+    /// The standard phases are: [`PreUpdate`](FramePhase::PreUpdate),
+    /// [`Update`](FramePhase::Update), [`PostUpdate`](FramePhase::PostUpdate),
+    /// [`Render`](FramePhase::Render), and [`FrameEnd`](FramePhase::FrameEnd).
+    pub fn standard_phases() -> Vec<FramePhase> {
+        vec![
+            Self::PreUpdate,
+            Self::Update,
+            Self::PostUpdate,
+            Self::Render,
+            Self::FrameEnd,
+        ]
+    }
+
+    /// Returns the event emitted for this phase.
+    pub fn to_event(self) -> Event {
+        match self {
+            Self::EventsCleared => Event::EventsCleared,
+            frame_phase => Event::FramePhase(frame_phase),
+        }
+    }
+}
+
+/// Provides timing details for a frame which went over its budget.
+///
+/// Emitted by the [`FrameWatchdog`](crate::FrameWatchdog) as part of an
+/// [`Event::FrameOverBudget`] event.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct FrameTiming {
+    /// The number of the frame which went over budget, counted from `0`.
+    pub frame: u64,
+
+    /// The amount of time the frame took.
+    pub frame_time: Duration,
+
+    /// The frame budget at the time the frame was measured.
+    pub budget: Duration,
+}
+
+/// Provides the events used to report the status of background jobs.
+///
+/// See [`JobPool`](crate::JobPool) for more details.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum JobEvent {
+    /// The job has finished, and its result is ready to be taken.
+    Finished(JobId),
+
+    /// The job panicked before it could finish.
+    Failed(JobId),
+
+    /// The job was cancelled before it could finish.
+    Cancelled(JobId),
+
+    /// The job reported its progress, as a percentage from `0` to `100`.
+    Progress(JobId, u8),
+}
+
+/// Provides the events used to report the status of child engines.
+///
+/// See [`Context::spawn_child_engine()`](crate::Context::spawn_child_engine()) for more details.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ChildEngineEvent {
+    /// The child engine has started running.
+    Started(ChildEngineId),
+
+    /// The child engine has stopped running, with the provided exit code.
+    Exited(ChildEngineId, i32),
+
+    /// The child engine panicked.
+    Panicked(ChildEngineId),
+}
+
+/// A unique identifier for an asset tracked by an asset server.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct AssetId(u64);
+
+impl AssetId {
+    /// Creates an asset id from a raw id number.
+    pub fn new(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Returns the raw id number.
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Provides the events used to report the status of assets.
+///
+/// Asset servers look up the asset, and any error, using its [`AssetId`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum AssetEvent {
+    /// The asset has finished loading, and is ready to use.
+    Loaded(AssetId),
+
+    /// The asset could not be loaded.
+    Failed(AssetId),
+
+    /// The asset's file changed, and the asset has been reloaded from it.
+    Reloaded(AssetId),
+}
+
 /// Provides the main events used by Wolf Engine.
 #[non_exhaustive]
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -15,6 +156,30 @@ pub enum Event {
     /// `EventsCleared` should be emitted only after all other events have been processed.
     EventsCleared,
 
+    /// Indicates the start of a [`FramePhase`].
+    ///
+    /// Frame phases are emitted in order, after all other events have been processed.
+    FramePhase(FramePhase),
+
+    /// Emitted by the [`FrameWatchdog`](crate::FrameWatchdog) when a frame took longer than its
+    /// budget.
+    FrameOverBudget(FrameTiming),
+
+    /// Emitted when a closure is sent to the main thread by a
+    /// [`MainThreadDispatcher`](crate::MainThreadDispatcher).
+    ///
+    /// The closure itself is run at the start of the next frame.
+    MainThreadTaskQueued,
+
+    /// A [`JobEvent`] emitted by the engine's [`JobPool`](crate::JobPool).
+    Job(JobEvent),
+
+    /// A [`ChildEngineEvent`] emitted by a child engine.
+    ChildEngine(ChildEngineEvent),
+
+    /// An [`AssetEvent`] emitted by an asset server.
+    Asset(AssetEvent),
+
     /// A [`WindowEvent`] emitted by the window system.
     WindowEvent(WindowEvent),
 
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 /// A generic, FIFO, MPSC event queue.
 ///
@@ -7,6 +8,16 @@ use std::sync::Arc;
 pub trait EventQueue<E>: HasEventSender<E> {
     /// Returns the next event in the queue.
     fn next_event(&mut self) -> Option<E>;
+
+    /// Returns the next event in the queue, blocking until one arrives, or until the timeout
+    /// expires.
+    ///
+    /// A `timeout` of `None` will block until an event arrives.  Queues which are unable to block
+    /// fall back to [`EventQueue::next_event()`], which is the default behavior.
+    fn wait_for_event(&mut self, timeout: Option<Duration>) -> Option<E> {
+        let _ = timeout;
+        self.next_event()
+    }
 }
 
 /// A type which has an [`EventSender`].
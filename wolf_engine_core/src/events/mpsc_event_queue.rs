@@ -1,5 +1,6 @@
 use std::sync::mpsc::*;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::events::*;
 
@@ -26,6 +27,13 @@ impl<E: 'static> EventQueue<E> for MpscEventQueue<E> {
     fn next_event(&mut self) -> Option<E> {
         self.receiver.try_recv().ok()
     }
+
+    fn wait_for_event(&mut self, timeout: Option<Duration>) -> Option<E> {
+        match timeout {
+            Some(timeout) => self.receiver.recv_timeout(timeout).ok(),
+            None => self.receiver.recv().ok(),
+        }
+    }
 }
 
 impl<E: 'static> HasEventSender<E> for MpscEventQueue<E> {
@@ -104,6 +112,28 @@ mod event_queue_tests {
         assert!(event_queue.next_event().is_none());
     }
 
+    #[test]
+    pub fn should_wait_for_events_sent_from_other_threads() {
+        let mut event_queue = MpscEventQueue::new();
+        let thread_sender = event_queue.event_sender();
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            thread_sender.send_event(0).unwrap();
+        });
+
+        assert_eq!(event_queue.wait_for_event(None), Some(0));
+    }
+
+    #[test]
+    pub fn should_stop_waiting_when_the_timeout_expires() {
+        let mut event_queue = MpscEventQueue::<i32>::new();
+
+        assert!(event_queue
+            .wait_for_event(Some(Duration::from_millis(1)))
+            .is_none());
+    }
+
     #[test]
     pub fn should_implement_default_trait() {
         let _event_queue = MpscEventQueue::<i32>::default();
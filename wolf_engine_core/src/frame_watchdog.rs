@@ -0,0 +1,237 @@
+use std::time::{Duration, Instant};
+
+use crate::events::*;
+
+/// Measures how long each frame takes, and reports frames which go over budget.
+///
+/// Once added to the [`EventLoop`](crate::EventLoop), the watchdog measures the time between the
+/// end of one frame, and the end of the next.  The end of a frame is marked by the last of the
+/// Event-Loop's [frame phases](crate::EventLoop::frame_phases()), which is
+/// [`Event::EventsCleared`] by default.  Time spent waiting for events, when using
+/// [`ControlFlow::Wait`](crate::ControlFlow::Wait), is not counted.
+///
+/// When a frame takes longer than the budget, an [`Event::FrameOverBudget`] event is emitted, and
+/// a warning is logged through the [`log`] crate.  Optionally, the warning can include a
+/// breakdown of how much time was spent handling each event, and phase, of the frame.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// # use wolf_engine::prelude::*;
+/// # use std::time::Duration;
+/// #
+/// let (mut event_loop, mut context) = wolf_engine::init(());
+/// event_loop.set_frame_watchdog(
+///     FrameWatchdog::new(Duration::from_millis(16)).with_phase_report(true),
+/// );
+///
+/// while let Some(event) = event_loop.next_event() {
+///     match event {
+///         Event::FrameOverBudget(timing) => println!("Hitch detected: {:?}", timing),
+///         Event::EventsCleared => context.quit(),
+///         _ => (),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameWatchdog {
+    budget: Duration,
+    has_phase_report: bool,
+    frame: u64,
+    frame_started_at: Option<Instant>,
+    last_event: Option<(Event, Instant)>,
+    idle_time: Duration,
+    event_times: Vec<(Event, Duration)>,
+    last_event_times: Vec<(Event, Duration)>,
+}
+
+impl FrameWatchdog {
+    /// Creates a new watchdog with the provided frame budget.
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            has_phase_report: false,
+            frame: 0,
+            frame_started_at: None,
+            last_event: None,
+            idle_time: Duration::ZERO,
+            event_times: Vec::new(),
+            last_event_times: Vec::new(),
+        }
+    }
+
+    /// Enables, or disables, logging a per-event timing breakdown for frames over budget.
+    pub fn with_phase_report(mut self, has_phase_report: bool) -> Self {
+        self.has_phase_report = has_phase_report;
+        self
+    }
+
+    /// Returns the frame budget.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Returns the time spent handling each event, including frame phases, during the last
+    /// finished frame.
+    ///
+    /// Times for identical events are combined.
+    pub fn last_event_times(&self) -> &[(Event, Duration)] {
+        &self.last_event_times
+    }
+
+    /// Records time spent blocking while waiting for events, which is excluded from the frame.
+    pub(crate) fn record_idle_time(&mut self, idle_time: Duration) {
+        self.idle_time += idle_time;
+    }
+
+    /// Records an event emitted by the Event-Loop.
+    ///
+    /// The time since the last recorded event is counted as time spent handling that event.
+    pub(crate) fn record_event(&mut self, event: Event, now: Instant) {
+        self.frame_started_at.get_or_insert(now);
+        if let Some((last_event, emitted_at)) = self.last_event.take() {
+            let elapsed = now.saturating_duration_since(emitted_at);
+            match self
+                .event_times
+                .iter_mut()
+                .find(|(key, _)| *key == last_event)
+            {
+                Some((_, time)) => *time += elapsed,
+                None => self.event_times.push((last_event, elapsed)),
+            }
+        }
+        self.last_event = Some((event, now));
+    }
+
+    /// Finishes the current frame, returning its timing if it went over budget.
+    pub(crate) fn finish_frame(&mut self, now: Instant) -> Option<FrameTiming> {
+        let frame_started_at = self.frame_started_at.replace(now)?;
+        let frame_time = now
+            .saturating_duration_since(frame_started_at)
+            .saturating_sub(self.idle_time);
+        let frame = self.frame;
+        self.frame += 1;
+        self.idle_time = Duration::ZERO;
+        self.last_event_times = std::mem::take(&mut self.event_times);
+
+        if frame_time <= self.budget {
+            return None;
+        }
+        let timing = FrameTiming {
+            frame,
+            frame_time,
+            budget: self.budget,
+        };
+        self.log_frame_timing(&timing);
+        Some(timing)
+    }
+
+    fn log_frame_timing(&self, timing: &FrameTiming) {
+        log::warn!(
+            "Frame {} took {:?}, which is over the {:?} budget",
+            timing.frame,
+            timing.frame_time,
+            timing.budget
+        );
+        if self.has_phase_report {
+            for (phase, time) in &self.last_event_times {
+                log::warn!("    {:?}: {:?}", phase, time);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod frame_watchdog_tests {
+    use std::thread;
+
+    use crate::testing::TestHarness;
+
+    use super::*;
+
+    #[test]
+    fn should_emit_frame_over_budget_for_long_frames() {
+        let (mut event_loop, context) = crate::init(());
+        event_loop.set_frame_watchdog(FrameWatchdog::new(Duration::from_millis(5)));
+        let mut harness = TestHarness::from_engine((event_loop, context)).with_event_handler(
+            |event, _context| {
+                if event == Event::EventsCleared {
+                    thread::sleep(Duration::from_millis(10));
+                }
+            },
+        );
+
+        harness.run_frames(3);
+
+        let timings = harness
+            .events()
+            .iter()
+            .filter_map(|event| match event {
+                Event::FrameOverBudget(timing) => Some(*timing),
+                _ => None,
+            })
+            .collect::<Vec<FrameTiming>>();
+        assert!(
+            !timings.is_empty(),
+            "No FrameOverBudget events were emitted."
+        );
+        assert!(timings[0].frame_time > timings[0].budget);
+        assert_eq!(timings[0].budget, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn should_not_emit_frame_over_budget_for_short_frames() {
+        let (mut event_loop, context) = crate::init(());
+        event_loop.set_frame_watchdog(FrameWatchdog::new(Duration::from_secs(10)));
+        let mut harness = TestHarness::from_engine((event_loop, context));
+
+        harness.run_frames(3);
+
+        harness.assert_events(&[Event::EventsCleared; 3]);
+    }
+
+    #[test]
+    fn should_record_time_spent_in_each_phase() {
+        let (mut event_loop, context) = crate::init(());
+        event_loop.set_frame_phases(FramePhase::standard_phases());
+        event_loop.set_frame_watchdog(
+            FrameWatchdog::new(Duration::from_millis(1)).with_phase_report(true),
+        );
+        let mut harness = TestHarness::from_engine((event_loop, context)).with_event_handler(
+            |event, _context| {
+                if event == Event::FramePhase(FramePhase::Render) {
+                    thread::sleep(Duration::from_millis(5));
+                }
+            },
+        );
+
+        harness.run_frames(2);
+
+        let event_times = harness
+            .event_loop_mut()
+            .frame_watchdog()
+            .unwrap()
+            .last_event_times()
+            .to_vec();
+        let (slowest_phase, _) = event_times
+            .iter()
+            .max_by_key(|(_, time)| *time)
+            .expect("No phase times were recorded.");
+        assert_eq!(*slowest_phase, Event::FramePhase(FramePhase::Render));
+    }
+
+    #[test]
+    fn should_not_count_idle_time() {
+        let mut watchdog = FrameWatchdog::new(Duration::from_millis(5));
+        let start = Instant::now();
+
+        watchdog.record_event(Event::EventsCleared, start);
+        watchdog.finish_frame(start);
+        watchdog.record_idle_time(Duration::from_millis(20));
+        let end = start + Duration::from_millis(21);
+        watchdog.record_event(Event::EventsCleared, end);
+
+        assert_eq!(watchdog.finish_frame(end), None);
+    }
+}
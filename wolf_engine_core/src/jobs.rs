@@ -0,0 +1,640 @@
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use crate::events::*;
+
+type QueuedJob = Box<dyn FnOnce() + Send>;
+
+/// A unique identifier for a job spawned on a [`JobPool`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct JobId(u64);
+
+impl JobId {
+    /// Returns the raw id number.
+    pub fn id(&self) -> u64 {
+        self.0
+    }
+}
+
+/// The status of a job spawned on a [`JobPool`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum JobStatus {
+    /// The job is waiting to run, or running, and last reported the provided progress, as a
+    /// percentage.
+    Running(u8),
+    /// The job finished, and its result can be taken.
+    Finished,
+    /// The job panicked.
+    Failed,
+    /// The job was cancelled before it could finish.
+    Cancelled,
+}
+
+/// Stores the cancellation flag, and status, of a single job.
+struct JobState {
+    is_cancelled: AtomicBool,
+    status: Mutex<JobStatus>,
+}
+
+impl JobState {
+    fn new() -> Self {
+        Self {
+            is_cancelled: AtomicBool::new(false),
+            status: Mutex::new(JobStatus::Running(0)),
+        }
+    }
+
+    fn status(&self) -> MutexGuard<'_, JobStatus> {
+        self.status
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Provides a job with access to its id, cancellation state, and progress reporting.
+pub struct JobContext {
+    id: JobId,
+    state: Arc<JobState>,
+    event_sender: Arc<dyn EventSender<Event>>,
+}
+
+impl JobContext {
+    /// Returns the id of the running job.
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    /// Returns `true` if the job has been cancelled.
+    ///
+    /// Long-running jobs should check this regularly, and return early when cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.is_cancelled.load(Ordering::Acquire)
+    }
+
+    /// Reports the job's progress, as a percentage, by emitting a [`JobEvent::Progress`] event.
+    ///
+    /// The progress can also be checked using [`JobHandle::status()`].  Values above `100` are
+    /// clamped to `100`.
+    pub fn report_progress(&self, percent: u8) {
+        let percent = percent.min(100);
+        *self.state.status() = JobStatus::Running(percent);
+        self.event_sender
+            .send_event(Event::Job(JobEvent::Progress(self.id, percent)))
+            .ok();
+    }
+}
+
+/// A typed handle to a job spawned on a [`JobPool`].
+///
+/// The handle can be used to cancel the job, and to take its result once it has finished.
+/// Dropping the handle discards the job's result, so the handle must be kept for as long as
+/// the result is wanted.
+pub struct JobHandle<T> {
+    id: JobId,
+    state: Arc<JobState>,
+    job_pool: JobPool,
+    _result: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> JobHandle<T> {
+    /// Returns the id of the job.
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    /// Returns the current status of the job.
+    ///
+    /// This allows jobs to be polled, without handling their [`JobEvent`]s.
+    pub fn status(&self) -> JobStatus {
+        *self.state.status()
+    }
+
+    /// Requests the job be cancelled.
+    ///
+    /// Jobs which have not started yet will not be run.  Running jobs are expected to check
+    /// [`JobContext::is_cancelled()`], and stop early.
+    pub fn cancel(&self) {
+        self.job_pool.cancel(self.id);
+    }
+
+    /// Takes the job's result, if the job has finished.
+    ///
+    /// Results can only be taken once.
+    pub fn take_result(&self) -> Option<T> {
+        self.job_pool.take_result(self.id)
+    }
+}
+
+impl<T> Drop for JobHandle<T> {
+    fn drop(&mut self) {
+        self.job_pool.inner.shared.results().discard(self.id);
+    }
+}
+
+/// Stores the results of finished jobs, until they are taken.
+#[derive(Default)]
+struct JobResults {
+    results: HashMap<JobId, Box<dyn Any + Send>>,
+    /// Jobs whose results are still wanted, because their [`JobHandle`] is alive.
+    wanted: HashSet<JobId>,
+}
+
+impl JobResults {
+    fn insert(&mut self, id: JobId, result: Box<dyn Any + Send>) {
+        if self.wanted.contains(&id) {
+            self.results.insert(id, result);
+        }
+    }
+
+    fn discard(&mut self, id: JobId) {
+        self.wanted.remove(&id);
+        self.results.remove(&id);
+    }
+}
+
+struct JobPoolShared {
+    event_sender: Arc<dyn EventSender<Event>>,
+    results: Mutex<JobResults>,
+    /// The states of jobs which haven't finished yet.
+    running_jobs: Mutex<HashMap<JobId, Arc<JobState>>>,
+}
+
+impl JobPoolShared {
+    fn results(&self) -> MutexGuard<'_, JobResults> {
+        self.results
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn finish_job(&self, id: JobId, job_event: JobEvent) {
+        if let Some(state) = self.running_jobs.lock().unwrap().remove(&id) {
+            *state.status() = match job_event {
+                JobEvent::Finished(_) => JobStatus::Finished,
+                JobEvent::Failed(_) => JobStatus::Failed,
+                _ => JobStatus::Cancelled,
+            };
+        }
+        self.event_sender.send_event(Event::Job(job_event)).ok();
+    }
+}
+
+struct JobPoolInner {
+    shared: Arc<JobPoolShared>,
+    thread_count: usize,
+    next_id: AtomicU64,
+    job_sender: Mutex<Option<Sender<QueuedJob>>>,
+    workers: Mutex<Vec<JoinHandle<()>>>,
+    is_shut_down: AtomicBool,
+}
+
+impl Drop for JobPoolInner {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl JobPoolInner {
+    fn start_workers(&self) -> Option<Sender<QueuedJob>> {
+        let mut job_sender = self.job_sender.lock().unwrap();
+        if job_sender.is_none() && !self.is_shut_down.load(Ordering::Acquire) {
+            let (sender, receiver) = mpsc::channel::<QueuedJob>();
+            let receiver = Arc::new(Mutex::new(receiver));
+            let mut workers = self.workers.lock().unwrap();
+            for index in 0..self.thread_count {
+                let receiver = receiver.clone();
+                let worker = thread::Builder::new()
+                    .name(format!("wolf_engine_job_worker_{}", index))
+                    .spawn(move || Self::run_worker(receiver))
+                    .expect("Failed to spawn a job worker thread");
+                workers.push(worker);
+            }
+            *job_sender = Some(sender);
+        }
+        job_sender.clone()
+    }
+
+    fn run_worker(receiver: Arc<Mutex<Receiver<QueuedJob>>>) {
+        loop {
+            let job = receiver.lock().unwrap().recv();
+            match job {
+                Ok(job) => job(),
+                Err(_) => break,
+            }
+        }
+    }
+
+    fn shutdown(&self) {
+        if self.is_shut_down.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        for state in self.shared.running_jobs.lock().unwrap().values() {
+            state.is_cancelled.store(true, Ordering::Release);
+        }
+        self.job_sender.lock().unwrap().take();
+        for worker in self.workers.lock().unwrap().drain(..) {
+            if worker.thread().id() != thread::current().id() {
+                worker.join().ok();
+            }
+        }
+    }
+}
+
+/// Provides a pool of worker threads for running background jobs.
+///
+/// Every [`Context`](crate::Context) owns a job pool, which is normally used through
+/// [`Context::spawn_job()`](crate::Context::spawn_job()).  The worker threads are started the
+/// first time a job is spawned.
+///
+/// Job results are not returned directly.  Instead, a [`JobEvent`] tagged with the job's
+/// [`JobId`] is sent through the engine's event queue when the job finishes, fails, or is
+/// cancelled.  The result can then be taken using the job's [`JobHandle`], or
+/// [`JobPool::take_result()`].  Results are kept until they are taken, the job is cancelled, or
+/// the job's handle is dropped.
+///
+/// The pool is cloneable, and all clones share the same worker threads.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// # use wolf_engine::prelude::*;
+/// #
+/// let (mut event_loop, mut context) = wolf_engine::init(());
+///
+/// let job = context.spawn_job(|job| {
+///     job.report_progress(50);
+///     2 + 2
+/// });
+///
+/// while let Some(event) = event_loop.next_event() {
+///     match event {
+///         Event::Job(JobEvent::Progress(id, percent)) => println!("{:?}: {}%", id, percent),
+///         Event::Job(JobEvent::Finished(id)) if id == job.id() => {
+///             assert_eq!(job.take_result(), Some(4));
+///             context.quit();
+///         }
+///         _ => (),
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct JobPool {
+    inner: Arc<JobPoolInner>,
+}
+
+impl JobPool {
+    /// Creates a new job pool which reports to the provided event sender.
+    ///
+    /// The number of worker threads matches the available parallelism of the system.
+    pub fn new(event_sender: Arc<dyn EventSender<Event>>) -> Self {
+        let thread_count = thread::available_parallelism()
+            .map(|count| count.get())
+            .unwrap_or(4);
+        Self::with_thread_count(event_sender, thread_count)
+    }
+
+    /// Creates a new job pool, with a specific number of worker threads, which reports to the
+    /// provided event sender.
+    ///
+    /// At least one worker thread is always used.
+    pub fn with_thread_count(event_sender: Arc<dyn EventSender<Event>>, count: usize) -> Self {
+        let shared = JobPoolShared {
+            event_sender,
+            results: Mutex::new(JobResults::default()),
+            running_jobs: Mutex::new(HashMap::new()),
+        };
+        Self {
+            inner: Arc::new(JobPoolInner {
+                shared: Arc::new(shared),
+                thread_count: count.max(1),
+                next_id: AtomicU64::new(0),
+                job_sender: Mutex::new(None),
+                workers: Mutex::new(Vec::new()),
+                is_shut_down: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Returns the number of worker threads used by the pool.
+    pub fn thread_count(&self) -> usize {
+        self.inner.thread_count
+    }
+
+    /// Spawns a job on the pool, and returns a [`JobHandle`] to it.
+    ///
+    /// Jobs which panic are reported with [`JobEvent::Failed`].  Jobs spawned after the pool has
+    /// been shut down are immediately reported as [`JobEvent::Cancelled`].
+    pub fn spawn<T, F>(&self, job: F) -> JobHandle<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&JobContext) -> T + Send + 'static,
+    {
+        let id = JobId(self.inner.next_id.fetch_add(1, Ordering::Relaxed));
+        let state = Arc::new(JobState::new());
+        let shared = self.inner.shared.clone();
+        shared.results().wanted.insert(id);
+        shared
+            .running_jobs
+            .lock()
+            .unwrap()
+            .insert(id, state.clone());
+        let job_context = JobContext {
+            id,
+            state: state.clone(),
+            event_sender: shared.event_sender.clone(),
+        };
+        let queued_job = Box::new(move || Self::run_job(id, job, job_context, &shared));
+        let was_queued = match self.inner.start_workers() {
+            Some(job_sender) => job_sender.send(queued_job).is_ok(),
+            None => false,
+        };
+        if !was_queued {
+            self.inner.shared.finish_job(id, JobEvent::Cancelled(id));
+        }
+        JobHandle {
+            id,
+            state,
+            job_pool: self.clone(),
+            _result: PhantomData,
+        }
+    }
+
+    fn run_job<T, F>(id: JobId, job: F, job_context: JobContext, shared: &JobPoolShared)
+    where
+        T: Send + 'static,
+        F: FnOnce(&JobContext) -> T,
+    {
+        if job_context.is_cancelled() {
+            shared.finish_job(id, JobEvent::Cancelled(id));
+            return;
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| job(&job_context)));
+        let job_event = match result {
+            Ok(_) if job_context.is_cancelled() => JobEvent::Cancelled(id),
+            Ok(value) => {
+                shared.results().insert(id, Box::new(value));
+                JobEvent::Finished(id)
+            }
+            Err(_) => JobEvent::Failed(id),
+        };
+        shared.finish_job(id, job_event);
+    }
+
+    /// Requests the job with the provided id be cancelled.
+    ///
+    /// If the job has already finished, its result is discarded instead.
+    pub fn cancel(&self, id: JobId) {
+        if let Some(state) = self.inner.shared.running_jobs.lock().unwrap().get(&id) {
+            state.is_cancelled.store(true, Ordering::Release);
+        }
+        self.inner.shared.results().discard(id);
+    }
+
+    /// Takes the result of a finished job.
+    ///
+    /// Returns `None` if the job hasn't finished, its result was already taken, or discarded, or
+    /// the result is not of type `T`.
+    pub fn take_result<T: 'static>(&self, id: JobId) -> Option<T> {
+        let mut results = self.inner.shared.results();
+        let result = results.results.remove(&id)?;
+        match result.downcast::<T>() {
+            Ok(result) => Some(*result),
+            Err(result) => {
+                results.results.insert(id, result);
+                None
+            }
+        }
+    }
+
+    /// Returns the number of finished jobs whose results haven't been taken yet.
+    pub fn pending_results(&self) -> usize {
+        self.inner.shared.results().results.len()
+    }
+
+    /// Cancels all unfinished jobs, and waits for the worker threads to stop.
+    ///
+    /// Jobs which are already running are allowed to finish.  No new jobs can be run after the
+    /// pool has been shut down.
+    pub fn shutdown(&self) {
+        self.inner.shutdown();
+    }
+
+    /// Returns `true` if the pool has been shut down.
+    pub fn is_shut_down(&self) -> bool {
+        self.inner.is_shut_down.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod job_pool_tests {
+    use std::time::Duration;
+
+    use ntest::timeout;
+
+    use super::*;
+
+    fn job_events(event_queue: &mut MpscEventQueue<Event>, count: usize) -> Vec<JobEvent> {
+        let mut job_events = Vec::new();
+        while job_events.len() < count {
+            if let Some(Event::Job(job_event)) = event_queue.wait_for_event(None) {
+                job_events.push(job_event);
+            }
+        }
+        job_events
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_run_jobs_and_report_results() {
+        let mut event_queue = MpscEventQueue::new();
+        let job_pool = JobPool::with_thread_count(event_queue.event_sender(), 2);
+
+        let job = job_pool.spawn(|_| "Hello, world!".to_string());
+
+        assert_eq!(
+            job_events(&mut event_queue, 1),
+            vec![JobEvent::Finished(job.id())]
+        );
+        assert_eq!(job.take_result(), Some("Hello, world!".to_string()));
+        assert_eq!(
+            job.take_result(),
+            None,
+            "Results should only be taken once."
+        );
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_keep_results_of_the_wrong_type() {
+        let mut event_queue = MpscEventQueue::new();
+        let job_pool = JobPool::with_thread_count(event_queue.event_sender(), 1);
+
+        let job = job_pool.spawn(|_| 123_u32);
+        job_events(&mut event_queue, 1);
+
+        assert_eq!(job_pool.take_result::<String>(job.id()), None);
+        assert_eq!(job_pool.take_result::<u32>(job.id()), Some(123));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_discard_results_when_the_handle_is_dropped() {
+        let mut event_queue = MpscEventQueue::new();
+        let job_pool = JobPool::with_thread_count(event_queue.event_sender(), 1);
+        let (start_sender, start_receiver) = mpsc::channel::<()>();
+
+        let finished_job = job_pool.spawn(|_| 1);
+        job_events(&mut event_queue, 1);
+        let unfinished_job = job_pool.spawn(move |_| start_receiver.recv().unwrap());
+        let unfinished_id = unfinished_job.id();
+        assert_eq!(job_pool.pending_results(), 1);
+
+        drop(finished_job);
+        drop(unfinished_job);
+        start_sender.send(()).unwrap();
+        job_events(&mut event_queue, 1);
+
+        assert_eq!(job_pool.pending_results(), 0);
+        assert_eq!(job_pool.take_result::<()>(unfinished_id), None);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_discard_results_when_a_finished_job_is_cancelled() {
+        let mut event_queue = MpscEventQueue::new();
+        let job_pool = JobPool::with_thread_count(event_queue.event_sender(), 1);
+
+        let job = job_pool.spawn(|_| 1);
+        job_events(&mut event_queue, 1);
+        job.cancel();
+
+        assert_eq!(job_pool.pending_results(), 0);
+        assert_eq!(job.take_result(), None);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_report_progress() {
+        let mut event_queue = MpscEventQueue::new();
+        let job_pool = JobPool::with_thread_count(event_queue.event_sender(), 1);
+
+        let job = job_pool.spawn(|job| {
+            job.report_progress(50);
+            job.report_progress(200);
+        });
+
+        assert_eq!(
+            job_events(&mut event_queue, 3),
+            vec![
+                JobEvent::Progress(job.id(), 50),
+                JobEvent::Progress(job.id(), 100),
+                JobEvent::Finished(job.id()),
+            ]
+        );
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_track_the_status_of_jobs_without_events() {
+        let event_queue = MpscEventQueue::new();
+        let job_pool = JobPool::with_thread_count(event_queue.event_sender(), 1);
+        let (progress_sender, progress_receiver) = mpsc::channel::<()>();
+        let (finish_sender, finish_receiver) = mpsc::channel::<()>();
+
+        let job = job_pool.spawn(move |job| {
+            job.report_progress(40);
+            progress_sender.send(()).unwrap();
+            finish_receiver.recv().unwrap();
+        });
+        let failed_job = job_pool.spawn::<(), _>(|_| panic!("Job failed"));
+        progress_receiver.recv().unwrap();
+
+        assert_eq!(job.status(), JobStatus::Running(40));
+        finish_sender.send(()).unwrap();
+        while failed_job.status() == JobStatus::Running(0) {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(job.status(), JobStatus::Finished);
+        assert_eq!(failed_job.status(), JobStatus::Failed);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_report_failed_jobs() {
+        let mut event_queue = MpscEventQueue::new();
+        let job_pool = JobPool::with_thread_count(event_queue.event_sender(), 1);
+
+        let job = job_pool.spawn::<(), _>(|_| panic!("Job failed"));
+
+        assert_eq!(
+            job_events(&mut event_queue, 1),
+            vec![JobEvent::Failed(job.id())]
+        );
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_cancel_jobs() {
+        let mut event_queue = MpscEventQueue::new();
+        let job_pool = JobPool::with_thread_count(event_queue.event_sender(), 1);
+        let (start_sender, start_receiver) = mpsc::channel::<()>();
+
+        let job = job_pool.spawn(move |job| {
+            start_receiver.recv().unwrap();
+            while !job.is_cancelled() {
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+        job.cancel();
+        start_sender.send(()).unwrap();
+
+        assert_eq!(
+            job_events(&mut event_queue, 1),
+            vec![JobEvent::Cancelled(job.id())]
+        );
+        assert_eq!(job.status(), JobStatus::Cancelled);
+        assert_eq!(job.take_result(), None);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_cancel_pending_jobs_on_shutdown() {
+        let mut event_queue = MpscEventQueue::new();
+        let job_pool = JobPool::with_thread_count(event_queue.event_sender(), 1);
+        let (start_sender, start_receiver) = mpsc::channel::<()>();
+
+        let running_job = job_pool.spawn(move |_| start_receiver.recv().unwrap());
+        let pending_job = job_pool.spawn(|_| ());
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            start_sender.send(()).unwrap();
+        });
+        job_pool.shutdown();
+
+        assert!(job_pool.is_shut_down());
+        let job_events = job_events(&mut event_queue, 2);
+        assert!(job_events.contains(&JobEvent::Cancelled(running_job.id())));
+        assert!(job_events.contains(&JobEvent::Cancelled(pending_job.id())));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_not_run_jobs_after_shutdown() {
+        let mut event_queue = MpscEventQueue::new();
+        let job_pool = JobPool::with_thread_count(event_queue.event_sender(), 1);
+        job_pool.shutdown();
+
+        let job = job_pool.spawn(|_| ());
+
+        assert_eq!(
+            job_events(&mut event_queue, 1),
+            vec![JobEvent::Cancelled(job.id())]
+        );
+    }
+}
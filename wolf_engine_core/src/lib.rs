@@ -54,6 +54,30 @@
 //! }
 //! ```
 //!
+//! If you'd rather not write the main-loop yourself, [`EventLoop::run()`] will run it for you,
+//! and return an exit code once the engine shuts down.
+//!
+//! ```
+//! # use wolf_engine_core as wolf_engine;
+//! use wolf_engine::prelude::*;
+//!
+//! pub fn main() {
+//!     let (event_loop, context) = wolf_engine::init(0);
+//!
+//!     let exit_code = event_loop.run(context, |event, context, control_flow| {
+//!         if event == Event::EventsCleared {
+//!             context.data += 1;
+//!             if context.data == 3 {
+//!                 *control_flow = ControlFlow::Exit(0);
+//!             }
+//!         }
+//!     });
+//! #   assert_eq!(exit_code, 0);
+//! #   return;
+//!     std::process::exit(exit_code);
+//! }
+//! ```
+//!
 //! You can use this example as a jumping-off point for your game.  Most of Wolf Engine's libraries
 //! are built against `core`, so you can very likely pull in other modules and start using them
 //! without to much trouble.
@@ -62,16 +86,50 @@
 //! [examples folder](https://github.com/AlexiWolf/wolf_engine/tree/main/examples) for additional
 //! examples.
 
+mod child_engines;
+pub use child_engines::*;
+mod clock;
+pub use clock::*;
 mod context;
 pub use context::*;
+mod control_flow;
+pub use control_flow::*;
+mod dependencies;
+pub use dependencies::*;
+mod engine_builder;
+pub use engine_builder::*;
 mod event_loop;
 pub use event_loop::*;
+mod frame_watchdog;
+pub use frame_watchdog::*;
+mod jobs;
+pub use jobs::*;
+mod main_thread;
+pub use main_thread::*;
+mod resources;
+pub use resources::*;
+#[cfg(feature = "serde")]
+mod snapshots;
+#[cfg(feature = "serde")]
+pub use snapshots::*;
+mod subsystems;
+pub use subsystems::*;
+mod timers;
+pub use timers::TimerHandle;
 
 pub mod events;
 
+#[cfg(feature = "config")]
+pub mod cli;
+#[cfg(feature = "config")]
+pub mod config;
+
 #[cfg(feature = "logging")]
 pub mod logging;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 #[doc(hidden)]
 pub mod prelude {
     pub use super::*;
@@ -84,6 +142,8 @@ pub type Engine<D> = (EventLoop, Context<D>);
 /// Initializes a new instance of the [`EventLoop`], and its associated [`Context`], with the
 /// provided data.
 ///
+/// This uses the default options.  Use an [`EngineBuilder`] to configure the engine.
+///
 /// #  Examples
 ///
 /// ```
@@ -116,7 +176,20 @@ pub type Engine<D> = (EventLoop, Context<D>);
 /// let (mut event_loop, mut context) = wolf_engine::init(SomeCustomDataType {});
 /// ```
 pub fn init<D>(data: D) -> Engine<D> {
-    let event_loop = EventLoop::new();
-    let context = Context::new(&event_loop, data);
-    (event_loop, context)
+    EngineBuilder::new(data)
+        .build()
+        .expect("The default engine options should always be valid")
+}
+
+#[cfg(test)]
+mod send_tests {
+    use super::*;
+
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn should_be_able_to_send_the_engine_to_other_threads() {
+        assert_send::<Context<()>>();
+        assert_send::<EventLoop>();
+    }
 }
@@ -1,8 +1,13 @@
 //! Provides a default logging implementation using [`SimpleLogger`].
 
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
 use simple_logger::SimpleLogger;
 
 /// Indicates the verbosity of the log system.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum LogLevel {
     /// Log all messages.
     Trace,
@@ -36,11 +41,196 @@ impl From<LogLevel> for log::LevelFilter {
     }
 }
 
+impl From<log::LevelFilter> for LogLevel {
+    fn from(value: log::LevelFilter) -> Self {
+        match value {
+            log::LevelFilter::Trace => LogLevel::Trace,
+            log::LevelFilter::Debug => LogLevel::Debug,
+            log::LevelFilter::Info => LogLevel::Info,
+            log::LevelFilter::Warn => LogLevel::Warn,
+            log::LevelFilter::Error => LogLevel::Error,
+            log::LevelFilter::Off => LogLevel::Off,
+        }
+    }
+}
+
+/// Parses a log level from its name, such as `"debug"`, ignoring case.
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        log::LevelFilter::from_str(value)
+            .map(LogLevel::from)
+            .map_err(|_| format!("`{}` is not a valid log level", value))
+    }
+}
+
+/// The environment variable [`initialize_logging()`] reads a [`LogFilter`] from.
+pub const LOG_ENV_VAR: &str = "RUST_LOG";
+
+/// Indicates a problem parsing a [`LogFilter`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LogFilterError {
+    directive: String,
+}
+
+impl Display for LogFilterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid log filter directive `{}`, expected `LEVEL`, `MODULE`, or `MODULE=LEVEL`",
+            self.directive
+        )
+    }
+}
+
+impl Error for LogFilterError {}
+
+/// Provides per-module log filtering, using `RUST_LOG`-style filter strings.
+///
+/// A filter string is a comma-separated list of directives.  Each directive is one of:
+///
+/// - `LEVEL`: Sets the default level, such as `warn`.
+/// - `MODULE=LEVEL`: Sets the level for a module, and its children, such as
+///   `my_game::ai=trace`.
+/// - `MODULE`: Enables all messages for a module.
+///
+/// The most specific module level is used, so `my_game=info,my_game::ai=trace` logs everything
+/// from `my_game::ai`, and only info messages from the rest of `my_game`.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core::logging::*;
+/// #
+/// let filter = LogFilter::new(LogLevel::Warn)
+///     .with_filter_str("wolf_engine_core=debug,my_game::ai=trace")
+///     .unwrap();
+///
+/// assert_eq!(filter.level_for("wolf_engine_core::events"), log::LevelFilter::Debug);
+/// assert_eq!(filter.level_for("my_game::ai::pathfinding"), log::LevelFilter::Trace);
+/// assert_eq!(filter.level_for("my_game::audio"), log::LevelFilter::Warn);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LogFilter {
+    default_level: log::LevelFilter,
+    module_levels: Vec<(String, log::LevelFilter)>,
+}
+
+impl LogFilter {
+    /// Creates a filter which uses the provided level for all modules.
+    pub fn new(default_level: LogLevel) -> Self {
+        Self {
+            default_level: default_level.into(),
+            module_levels: Vec::new(),
+        }
+    }
+
+    /// Sets the level for a module, and its children.
+    pub fn with_module(mut self, module: &str, level: LogLevel) -> Self {
+        self.set_module_level(module, level.into());
+        self
+    }
+
+    /// Applies the directives in a `RUST_LOG`-style filter string on top of this filter.
+    pub fn with_filter_str(mut self, filter: &str) -> Result<Self, LogFilterError> {
+        for directive in filter.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let invalid_directive = || LogFilterError {
+                directive: directive.to_string(),
+            };
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    let level = log::LevelFilter::from_str(level.trim())
+                        .map_err(|_| invalid_directive())?;
+                    let module = module.trim();
+                    if module.is_empty() {
+                        return Err(invalid_directive());
+                    }
+                    self.set_module_level(module, level);
+                }
+                None => match log::LevelFilter::from_str(directive) {
+                    Ok(level) => self.default_level = level,
+                    Err(_) => self.set_module_level(directive, log::LevelFilter::Trace),
+                },
+            }
+        }
+        Ok(self)
+    }
+
+    /// Applies the filter string stored in the environment variable, if it is set.
+    pub fn with_env_var(self, name: &str) -> Result<Self, LogFilterError> {
+        match std::env::var(name) {
+            Ok(filter) => self.with_filter_str(&filter),
+            Err(_) => Ok(self),
+        }
+    }
+
+    /// Creates a filter from the `log_level`, and `log_filter` engine settings.
+    #[cfg(feature = "config")]
+    pub fn from_engine_settings(
+        settings: &crate::config::EngineSettings,
+    ) -> Result<Self, LogFilterError> {
+        let filter = Self::new(settings.log_level.into());
+        match &settings.log_filter {
+            Some(log_filter) => filter.with_filter_str(log_filter),
+            None => Ok(filter),
+        }
+    }
+
+    /// Returns the level used for modules without a specific level.
+    pub fn default_level(&self) -> log::LevelFilter {
+        self.default_level
+    }
+
+    /// Returns the level used for the provided module path.
+    pub fn level_for(&self, module: &str) -> log::LevelFilter {
+        self.module_levels
+            .iter()
+            .filter(|(filter_module, _)| {
+                module == filter_module
+                    || module
+                        .strip_prefix(filter_module.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(filter_module, _)| filter_module.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default_level)
+    }
+
+    /// Returns the most verbose level used by any module.
+    fn max_level(&self) -> log::LevelFilter {
+        self.module_levels
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default_level, Ord::max)
+    }
+
+    fn set_module_level(&mut self, module: &str, level: log::LevelFilter) {
+        self.module_levels
+            .retain(|(filter_module, _)| filter_module != module);
+        self.module_levels.push((module.to_string(), level));
+    }
+}
+
+impl FromStr for LogFilter {
+    type Err = LogFilterError;
+
+    /// Parses a filter string, using [`LogLevel::Error`] as the default level.
+    fn from_str(filter: &str) -> Result<Self, Self::Err> {
+        Self::new(LogLevel::Error).with_filter_str(filter)
+    }
+}
+
 /// Initializes the logging system with a pre-configured [SimpleLogger] instance.
 ///
 /// This function is provided for those who don't need a complicated logging setup.  Messages will
 /// be logged to the terminal.
 ///
+/// The provided [LogLevel] is used as the default level.  If the [`LOG_ENV_VAR`] environment
+/// variable is set, its [filter string](LogFilter) is applied on top, which allows, for example,
+/// debug logs to be enabled for a single module in a shipped build.  Use
+/// [`initialize_logging_with_filter()`] for full control.
+///
 /// # Examples
 ///
 /// To use the default logger, just initialize it by calling this function and providing it with
@@ -58,10 +248,162 @@ impl From<LogLevel> for log::LevelFilter {
 /// info!("Hello, world!");
 /// ```
 pub fn initialize_logging(log_level: LogLevel) {
-    SimpleLogger::new()
-        .with_colors(true)
-        .with_level(log_level.into())
-        .with_utc_timestamps()
-        .init()
-        .expect("Failed to initialize the logger");
+    let filter = LogFilter::new(log_level);
+    match filter.clone().with_env_var(LOG_ENV_VAR) {
+        Ok(filter) => initialize_logging_with_filter(filter),
+        Err(error) => {
+            initialize_logging_with_filter(filter);
+            log::warn!(
+                "Ignoring the {} environment variable: {}",
+                LOG_ENV_VAR,
+                error
+            );
+        }
+    }
+}
+
+/// Initializes the logging system with a pre-configured [SimpleLogger] instance, which uses the
+/// provided [`LogFilter`].
+///
+/// Unlike [`initialize_logging()`], the environment is not read.
+pub fn initialize_logging_with_filter(filter: LogFilter) {
+    let max_level = filter.max_level();
+    let logger = Box::leak(Box::new(FilteredLogger::new(filter)));
+    log::set_logger(logger).expect("Failed to initialize the logger");
+    log::set_max_level(max_level);
+}
+
+/// Writes messages to a [`SimpleLogger`], after checking them against a [`LogFilter`].
+///
+/// The filter is checked here, rather than by the `SimpleLogger`, so module levels only apply
+/// to whole path segments, as described by [`LogFilter::level_for()`].
+struct FilteredLogger {
+    filter: LogFilter,
+    logger: SimpleLogger,
+}
+
+impl FilteredLogger {
+    fn new(filter: LogFilter) -> Self {
+        Self {
+            filter,
+            logger: SimpleLogger::new()
+                .with_colors(true)
+                .with_level(log::LevelFilter::Trace)
+                .with_utc_timestamps(),
+        }
+    }
+}
+
+impl log::Log for FilteredLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            self.logger.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.logger.flush();
+    }
+}
+
+#[cfg(test)]
+mod log_filter_tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test]
+    fn should_use_the_most_specific_module_level() {
+        let filter = LogFilter::new(LogLevel::Info)
+            .with_filter_str("my_game=warn, my_game::ai=trace")
+            .unwrap();
+
+        assert_eq!(
+            filter.level_for("my_game::ai::pathfinding"),
+            log::LevelFilter::Trace
+        );
+        assert_eq!(filter.level_for("my_game::ai"), log::LevelFilter::Trace);
+        assert_eq!(filter.level_for("my_game::audio"), log::LevelFilter::Warn);
+        assert_eq!(filter.level_for("my_game_tools"), log::LevelFilter::Info);
+        assert_eq!(filter.level_for("other"), log::LevelFilter::Info);
+    }
+
+    #[test]
+    fn should_parse_default_levels_and_bare_modules() {
+        let filter = "debug,my_game".parse::<LogFilter>().unwrap();
+
+        assert_eq!(filter.default_level(), log::LevelFilter::Debug);
+        assert_eq!(filter.level_for("my_game"), log::LevelFilter::Trace);
+    }
+
+    #[test]
+    fn should_let_later_directives_override_earlier_ones() {
+        let filter = LogFilter::new(LogLevel::Info)
+            .with_module("my_game", LogLevel::Error)
+            .with_filter_str("my_game=debug")
+            .unwrap();
+
+        assert_eq!(filter.level_for("my_game"), log::LevelFilter::Debug);
+    }
+
+    #[test]
+    fn should_read_the_filter_from_the_environment() {
+        std::env::set_var("WOLF_ENGINE_LOG_FILTER_TEST", "my_game=off");
+
+        let filter = LogFilter::new(LogLevel::Info)
+            .with_env_var("WOLF_ENGINE_LOG_FILTER_TEST")
+            .unwrap()
+            .with_env_var("WOLF_ENGINE_LOG_FILTER_TEST_UNSET")
+            .unwrap();
+
+        assert_eq!(filter.level_for("my_game"), log::LevelFilter::Off);
+        assert_eq!(filter.default_level(), log::LevelFilter::Info);
+    }
+
+    #[test_case("my_game=loud" ; "invalid level")]
+    #[test_case("=debug" ; "missing module")]
+    fn should_reject_invalid_directives(filter: &str) {
+        let error = LogFilter::new(LogLevel::Info).with_filter_str(filter).err();
+
+        assert!(error.is_some());
+        assert!(error.unwrap().to_string().contains(filter));
+    }
+
+    #[test]
+    fn should_only_log_messages_allowed_by_the_filter() {
+        use log::Log;
+
+        let logger = FilteredLogger::new(
+            LogFilter::new(LogLevel::Info)
+                .with_filter_str("my_game=debug")
+                .unwrap(),
+        );
+        let is_enabled = |target: &str, level: log::Level| {
+            logger.enabled(&log::Metadata::builder().target(target).level(level).build())
+        };
+
+        assert!(is_enabled("my_game::ai", log::Level::Debug));
+        assert!(!is_enabled("my_game::ai", log::Level::Trace));
+        assert!(!is_enabled("my_game_tools", log::Level::Debug));
+        assert!(is_enabled("my_game_tools", log::Level::Info));
+    }
+
+    #[test]
+    fn should_use_the_most_verbose_level_as_the_max_level() {
+        let filter = LogFilter::new(LogLevel::Warn)
+            .with_filter_str("my_game::ai=trace,my_game=off")
+            .unwrap();
+
+        assert_eq!(filter.max_level(), log::LevelFilter::Trace);
+    }
+
+    #[test]
+    fn should_parse_log_levels() {
+        assert_eq!("DEBUG".parse::<LogLevel>(), Ok(LogLevel::Debug));
+        assert!("loud".parse::<LogLevel>().is_err());
+    }
 }
@@ -0,0 +1,389 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Poll, Waker};
+
+use crate::events::*;
+use crate::Context;
+
+/// A closure which is run on the main thread, with access to the [`Context`].
+///
+/// See [`MainThreadDispatcher`] for more details.
+pub type MainThreadTask<D> = Box<dyn FnOnce(&mut Context<D>) + Send>;
+
+/// Sends closures to be run on the main thread, which owns the [`EventLoop`](crate::EventLoop).
+///
+/// Some operations, such as window calls, or working with non-`Send` resources, must be run on
+/// the main thread.  A `MainThreadDispatcher` can be freely cloned, and sent to other threads,
+/// which can then use it to run closures on the main thread.
+///
+/// Dispatched closures are run, in the order they were sent, at the start of the next frame,
+/// right before the first [frame phase](crate::EventLoop::frame_phases()) is emitted.  They are
+/// run by [`Context::start_frame()`], which is called automatically by
+/// [`EventLoop::run()`](crate::EventLoop::run()).
+///
+/// Sending a closure also sends an [`Event::MainThreadTaskQueued`] event, so the Event-Loop will
+/// wake up when using [`ControlFlow::Wait`](crate::ControlFlow::Wait).
+///
+/// The dispatcher is an [`EventSender`] for [`MainThreadTask`]s.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// # use wolf_engine::prelude::*;
+/// #
+/// let (event_loop, context) = wolf_engine::init(0);
+/// let dispatcher = context.main_thread_dispatcher();
+///
+/// let worker = std::thread::spawn(move || {
+///     // Run a closure on the main thread, and wait for the result.
+///     let data = dispatcher.dispatch_blocking(|context| {
+///         context.data += 1;
+///         context.data
+///     });
+///     dispatcher.dispatch(|context| context.quit()).unwrap();
+///     data
+/// });
+///
+/// event_loop.run(context, |_event, _context, _control_flow| ());
+/// assert_eq!(worker.join().unwrap(), Ok(1));
+/// ```
+pub struct MainThreadDispatcher<D> {
+    task_sender: Sender<MainThreadTask<D>>,
+    event_sender: Arc<dyn EventSender<Event>>,
+}
+
+impl<D> Clone for MainThreadDispatcher<D> {
+    fn clone(&self) -> Self {
+        Self {
+            task_sender: self.task_sender.clone(),
+            event_sender: self.event_sender.clone(),
+        }
+    }
+}
+
+impl<D> MainThreadDispatcher<D> {
+    pub(crate) fn new(
+        task_sender: Sender<MainThreadTask<D>>,
+        event_sender: Arc<dyn EventSender<Event>>,
+    ) -> Self {
+        Self {
+            task_sender,
+            event_sender,
+        }
+    }
+
+    /// Sends a closure to be run on the main thread.
+    ///
+    /// Returns an error once the engine has [shut down](Context::shutdown()).
+    pub fn dispatch<F>(&self, task: F) -> Result<(), String>
+    where
+        F: FnOnce(&mut Context<D>) + Send + 'static,
+    {
+        self.send_event(Box::new(task))
+    }
+
+    /// Sends a closure to be run on the main thread, and returns a [`MainThreadResult`] which can
+    /// be used to wait for its return value.
+    ///
+    /// The [`MainThreadResult`] can be waited on directly, or `.await`ed.
+    pub fn dispatch_with_result<T, F>(&self, task: F) -> MainThreadResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Context<D>) -> T + Send + 'static,
+    {
+        let result = MainThreadResult::new();
+        let completer = MainThreadResultCompleter(result.shared.clone());
+        let was_sent = self.dispatch(move |context| completer.complete(Ok(task(context))));
+        if let Err(error) = was_sent {
+            result.shared.complete(Err(error));
+        }
+        result
+    }
+
+    /// Sends a closure to be run on the main thread, then blocks until it returns a value.
+    ///
+    /// This must **not** be called from the main thread, as the closure will never run, and the
+    /// calling thread will block forever.  If the engine shuts down before the closure is run,
+    /// an error is returned.
+    pub fn dispatch_blocking<T, F>(&self, task: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Context<D>) -> T + Send + 'static,
+    {
+        self.dispatch_with_result(task).wait()
+    }
+}
+
+impl<D> EventSender<MainThreadTask<D>> for MainThreadDispatcher<D> {
+    fn send_event(&self, task: MainThreadTask<D>) -> Result<(), String> {
+        self.task_sender
+            .send(task)
+            .map_err(|error| error.to_string())?;
+        self.event_sender.send_event(Event::MainThreadTaskQueued)
+    }
+}
+
+struct MainThreadResultState<T> {
+    result: Option<Result<T, String>>,
+    waker: Option<Waker>,
+    is_complete: bool,
+}
+
+struct MainThreadResultShared<T> {
+    state: Mutex<MainThreadResultState<T>>,
+    condvar: Condvar,
+}
+
+impl<T> MainThreadResultShared<T> {
+    fn complete(&self, result: Result<T, String>) {
+        let mut state = self.state.lock().unwrap();
+        if state.is_complete {
+            return;
+        }
+        state.is_complete = true;
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+        self.condvar.notify_all();
+    }
+}
+
+/// Completes the result with an error if the closure is dropped without being run, such as when
+/// the engine shuts down first.
+struct MainThreadResultCompleter<T>(Arc<MainThreadResultShared<T>>);
+
+impl<T> MainThreadResultCompleter<T> {
+    fn complete(&self, result: Result<T, String>) {
+        self.0.complete(result);
+    }
+}
+
+impl<T> Drop for MainThreadResultCompleter<T> {
+    fn drop(&mut self) {
+        self.0.complete(Err(
+            "The engine shut down before the task could be run".to_string()
+        ));
+    }
+}
+
+/// The eventual result of a closure sent with
+/// [`MainThreadDispatcher::dispatch_with_result()`].
+///
+/// The result can be waited on with [`MainThreadResult::wait()`], polled with
+/// [`MainThreadResult::try_take()`], or `.await`ed as a [`Future`].
+pub struct MainThreadResult<T> {
+    shared: Arc<MainThreadResultShared<T>>,
+}
+
+impl<T> MainThreadResult<T> {
+    fn new() -> Self {
+        Self {
+            shared: Arc::new(MainThreadResultShared {
+                state: Mutex::new(MainThreadResultState {
+                    result: None,
+                    waker: None,
+                    is_complete: false,
+                }),
+                condvar: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Blocks until the closure has been run on the main thread, and returns its result.
+    ///
+    /// Returns an error if the closure could not be sent to the main thread, or if it was
+    /// dropped without being run.
+    pub fn wait(self) -> Result<T, String> {
+        let mut state = self.shared.state.lock().unwrap();
+        loop {
+            if let Some(result) = state.result.take() {
+                return result;
+            }
+            state = self.shared.condvar.wait(state).unwrap();
+        }
+    }
+
+    /// Returns the result, if the closure has been run, without blocking.
+    pub fn try_take(&mut self) -> Option<Result<T, String>> {
+        self.shared.state.lock().unwrap().result.take()
+    }
+}
+
+impl<T> Future for MainThreadResult<T> {
+    type Output = Result<T, String>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod main_thread_dispatcher_tests {
+    use std::future::Future;
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::task::Wake;
+    use std::thread;
+    use std::time::Duration;
+
+    use ntest::timeout;
+
+    use crate::prelude::*;
+
+    #[test]
+    #[timeout(1000)]
+    fn should_run_tasks_at_the_start_of_the_frame() {
+        let (mut event_loop, mut context) = crate::init(Vec::new());
+        event_loop.set_frame_phases(FramePhase::standard_phases());
+        let dispatcher = context.main_thread_dispatcher();
+
+        dispatcher
+            .dispatch(|context| context.data.push("Task"))
+            .unwrap();
+        while let Some(event) = event_loop.next_event() {
+            if event_loop.has_started_frame() {
+                context.start_frame();
+            }
+            match event {
+                Event::FramePhase(FramePhase::PreUpdate) => context.data.push("PreUpdate"),
+                Event::FramePhase(FramePhase::FrameEnd) => context.quit(),
+                _ => (),
+            }
+        }
+
+        assert_eq!(context.data, vec!["Task", "PreUpdate"]);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_wake_the_event_loop_when_a_task_is_queued() {
+        let (event_loop, context) = crate::init(thread::current().id());
+        let dispatcher = context.main_thread_dispatcher();
+
+        thread::spawn(move || {
+            dispatcher.dispatch(|context| context.quit()).unwrap();
+        });
+        event_loop.run(context, |_event, _context, control_flow| {
+            *control_flow = ControlFlow::Wait;
+        });
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_run_blocking_tasks_on_the_main_thread() {
+        let (event_loop, context) = crate::init(thread::current().id());
+        let dispatcher = context.main_thread_dispatcher();
+
+        let worker = thread::spawn(move || {
+            let main_thread_id = dispatcher.dispatch_blocking(|context| context.data);
+            dispatcher.dispatch(|context| context.quit()).unwrap();
+            main_thread_id
+        });
+        event_loop.run(context, |_event, _context, _control_flow| ());
+
+        assert_eq!(worker.join().unwrap(), Ok(thread::current().id()));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_not_block_shutdown_on_tasks_dispatched_while_quitting() {
+        let (event_loop, context) = crate::init(1);
+        let dispatcher = context.main_thread_dispatcher();
+        let (start_sender, start_receiver) = mpsc::channel();
+        let result = Arc::new(Mutex::new(None));
+
+        let job_dispatcher = dispatcher.clone();
+        let job_result = result.clone();
+        context.spawn_job(move |_job_context| {
+            start_receiver.recv().unwrap();
+            let data = job_dispatcher.dispatch_blocking(|context| context.data);
+            *job_result.lock().unwrap() = Some(data);
+        });
+        let mut start_sender = Some(start_sender);
+        event_loop.run(context, move |event, context, _control_flow| {
+            if let Event::EventsCleared = event {
+                if let Some(start_sender) = start_sender.take() {
+                    start_sender.send(()).unwrap();
+                    thread::sleep(Duration::from_millis(10));
+                    context.quit();
+                }
+            }
+        });
+
+        assert!(
+            result.lock().unwrap().is_some(),
+            "The job should finish before the engine shuts down"
+        );
+        assert!(dispatcher.dispatch(|_context| ()).is_err());
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_return_results_through_a_future() {
+        struct ThreadWaker(thread::Thread);
+
+        impl Wake for ThreadWaker {
+            fn wake(self: std::sync::Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let (event_loop, context) = crate::init(());
+        let dispatcher = context.main_thread_dispatcher();
+        let (result_sender, result_receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut result = Box::pin(dispatcher.dispatch_with_result(|_context| 123));
+            let waker = std::sync::Arc::new(ThreadWaker(thread::current())).into();
+            let mut task_context = std::task::Context::from_waker(&waker);
+            let value = loop {
+                match result.as_mut().poll(&mut task_context) {
+                    std::task::Poll::Ready(value) => break value,
+                    std::task::Poll::Pending => thread::park(),
+                }
+            };
+            result_sender.send(value).unwrap();
+            dispatcher.dispatch(|context| context.quit()).unwrap();
+        });
+        event_loop.run(context, |_event, _context, _control_flow| ());
+
+        assert_eq!(result_receiver.recv().unwrap(), Ok(123));
+    }
+
+    #[test]
+    fn should_fail_if_the_task_is_dropped_without_running() {
+        let (_event_loop, context) = crate::init(());
+        let result = context
+            .main_thread_dispatcher()
+            .dispatch_with_result(|_context| ());
+
+        drop(context);
+
+        assert!(result.wait().is_err());
+    }
+
+    #[test]
+    fn should_send_tasks_through_the_event_sender_api() {
+        let (mut event_loop, mut context) = crate::init(0);
+        let dispatcher: std::sync::Arc<dyn EventSender<MainThreadTask<i32>>> =
+            std::sync::Arc::new(context.main_thread_dispatcher());
+
+        dispatcher
+            .send_event(Box::new(|context| context.data = 1))
+            .unwrap();
+
+        assert_eq!(event_loop.next_event(), Some(Event::MainThreadTaskQueued));
+        context.start_frame();
+        assert_eq!(context.data, 1);
+    }
+}
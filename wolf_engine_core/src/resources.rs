@@ -0,0 +1,185 @@
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Indicates a problem accessing a resource stored in [`Resources`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ResourceError {
+    /// There is no resource of the requested type.
+    Missing {
+        /// The name of the requested type.
+        type_name: &'static str,
+    },
+}
+
+impl ResourceError {
+    fn missing<T>() -> Self {
+        Self::Missing {
+            type_name: type_name::<T>(),
+        }
+    }
+}
+
+impl Display for ResourceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing { type_name } => write!(
+                f,
+                "No resource of type `{}` exists, it must be inserted before it can be used",
+                type_name
+            ),
+        }
+    }
+}
+
+impl Error for ResourceError {}
+
+/// Provides a type-keyed store of resources.
+///
+/// Each resource is stored, and looked up, by its type, so only one resource of each type can be
+/// stored at a time.  Resources allow independent parts of the engine, such as subsystems, and
+/// plugins, to store their own state without needing to be part of the
+/// [`Context`](crate::Context)'s data.  Resources must be [`Send`], so the Context can be moved
+/// to another thread.
+///
+/// The [`Context`](crate::Context) owns a `Resources` store, which is normally used through
+/// [`Context::insert_resource()`](crate::Context::insert_resource()), and related methods.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core::Resources;
+/// #
+/// struct Score(u32);
+///
+/// let mut resources = Resources::new();
+/// resources.insert(Score(0));
+///
+/// resources.get_mut::<Score>().unwrap().0 += 10;
+///
+/// assert_eq!(resources.get::<Score>().unwrap().0, 10);
+/// assert!(resources.get::<String>().is_err());
+/// ```
+#[derive(Default)]
+pub struct Resources {
+    resources: HashMap<TypeId, Box<dyn Any + Send>>,
+}
+
+impl Resources {
+    /// Creates a new, empty resource store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a resource, returning the previous resource of the same type, if there was one.
+    pub fn insert<T: Send + 'static>(&mut self, resource: T) -> Option<T> {
+        self.resources
+            .insert(TypeId::of::<T>(), Box::new(resource))
+            .and_then(|resource| resource.downcast::<T>().ok())
+            .map(|resource| *resource)
+    }
+
+    /// Returns a reference to the resource of type `T`.
+    pub fn get<T: 'static>(&self) -> Result<&T, ResourceError> {
+        self.resources
+            .get(&TypeId::of::<T>())
+            .and_then(|resource| resource.downcast_ref::<T>())
+            .ok_or_else(ResourceError::missing::<T>)
+    }
+
+    /// Returns a mutable reference to the resource of type `T`.
+    pub fn get_mut<T: 'static>(&mut self) -> Result<&mut T, ResourceError> {
+        self.resources
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|resource| resource.downcast_mut::<T>())
+            .ok_or_else(ResourceError::missing::<T>)
+    }
+
+    /// Removes, and returns, the resource of type `T`.
+    pub fn remove<T: 'static>(&mut self) -> Result<T, ResourceError> {
+        self.resources
+            .remove(&TypeId::of::<T>())
+            .and_then(|resource| resource.downcast::<T>().ok())
+            .map(|resource| *resource)
+            .ok_or_else(ResourceError::missing::<T>)
+    }
+
+    /// Returns `true` if there is a resource of type `T`.
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Returns the number of stored resources.
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Returns `true` if there are no stored resources.
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod resources_tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct TestResource(i32);
+
+    #[test]
+    fn should_insert_and_get_resources() {
+        let mut resources = Resources::new();
+
+        assert_eq!(resources.insert(TestResource(1)), None);
+        assert_eq!(resources.insert(TestResource(2)), Some(TestResource(1)));
+
+        assert_eq!(resources.get::<TestResource>(), Ok(&TestResource(2)));
+        assert_eq!(resources.len(), 1);
+    }
+
+    #[test]
+    fn should_modify_resources() {
+        let mut resources = Resources::new();
+        resources.insert(TestResource(1));
+
+        resources.get_mut::<TestResource>().unwrap().0 = 5;
+
+        assert_eq!(resources.get::<TestResource>(), Ok(&TestResource(5)));
+    }
+
+    #[test]
+    fn should_remove_resources() {
+        let mut resources = Resources::new();
+        resources.insert(TestResource(1));
+
+        assert_eq!(resources.remove::<TestResource>(), Ok(TestResource(1)));
+        assert!(!resources.contains::<TestResource>());
+        assert!(resources.is_empty());
+    }
+
+    #[test]
+    fn should_report_missing_resources() {
+        let mut resources = Resources::new();
+        let expected_error = ResourceError::Missing {
+            type_name: type_name::<TestResource>(),
+        };
+
+        assert_eq!(resources.get::<TestResource>(), Err(expected_error.clone()));
+        assert_eq!(
+            resources.get_mut::<TestResource>(),
+            Err(expected_error.clone())
+        );
+        assert_eq!(resources.remove::<TestResource>(), Err(expected_error));
+    }
+
+    #[test]
+    fn should_name_the_missing_type_in_the_error_message() {
+        let resources = Resources::new();
+
+        let error = resources.get::<TestResource>().unwrap_err();
+
+        assert!(error.to_string().contains("TestResource"));
+    }
+}
@@ -0,0 +1,535 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use serde::de::{self, DeserializeOwned, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Context, Resources};
+
+/// Indicates a problem creating, encoding, or restoring a [`Snapshot`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SnapshotError {
+    /// The context data, or a resource, could not be serialized.
+    Serialize(String),
+    /// The context data, or a resource, could not be deserialized from the snapshot.
+    Deserialize(String),
+    /// The snapshot could not be encoded using the requested format.
+    Encode(SnapshotFormat, String),
+    /// The snapshot could not be decoded using the requested format.
+    Decode(SnapshotFormat, String),
+    /// The snapshot contains a resource which has not been registered on the [`Context`].
+    UnregisteredResource {
+        /// The key the resource was stored under.
+        key: String,
+    },
+    /// A resource with the same key has already been registered on the [`Context`].
+    DuplicateKey {
+        /// The duplicated key.
+        key: &'static str,
+    },
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Serialize(error) => write!(f, "Failed to serialize snapshot data: {}", error),
+            Self::Deserialize(error) => {
+                write!(f, "Failed to deserialize snapshot data: {}", error)
+            }
+            Self::Encode(format, error) => {
+                write!(f, "Failed to encode snapshot as {:?}: {}", format, error)
+            }
+            Self::Decode(format, error) => {
+                write!(f, "Failed to decode snapshot as {:?}: {}", format, error)
+            }
+            Self::UnregisteredResource { key } => write!(
+                f,
+                "The snapshot contains resource `{}`, which is not registered for snapshots",
+                key
+            ),
+            Self::DuplicateKey { key } => write!(
+                f,
+                "A snapshot resource with key `{}` is already registered",
+                key
+            ),
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+/// The formats a [`Snapshot`] can be encoded in.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+pub enum SnapshotFormat {
+    /// Human-readable [JSON](https://www.json.org/).
+    ///
+    /// JSON can't represent everything the other formats can, such as maps with non-string keys,
+    /// or `NaN`, and infinite floats.
+    Json,
+    /// Human-readable [Rusty Object Notation](https://github.com/ron-rs/ron).
+    Ron,
+    /// Compact, binary [MessagePack](https://msgpack.org/).
+    MessagePack,
+}
+
+impl SnapshotFormat {
+    fn serialize<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|error| error.to_string()),
+            Self::Ron => ron::to_string(value)
+                .map(String::into_bytes)
+                .map_err(|error| error.to_string()),
+            Self::MessagePack => rmp_serde::to_vec(value).map_err(|error| error.to_string()),
+        }
+    }
+
+    fn deserialize<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, String> {
+        match self {
+            Self::Json => serde_json::from_slice(bytes).map_err(|error| error.to_string()),
+            Self::Ron => ron::de::from_bytes(bytes).map_err(|error| error.to_string()),
+            Self::MessagePack => rmp_serde::from_slice(bytes).map_err(|error| error.to_string()),
+        }
+    }
+}
+
+/// A value which has already been serialized using a [`Snapshot`]'s format.
+///
+/// Payloads are stored as strings by human-readable formats, so they stay readable, and as
+/// bytes by everything else.  Payloads which are not valid UTF-8, such as a MessagePack
+/// snapshot stored in a JSON file, are stored as a list of bytes by human-readable formats.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Payload(Vec<u8>);
+
+impl Serialize for Payload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(&self.0);
+        }
+        match std::str::from_utf8(&self.0) {
+            Ok(text) => serializer.serialize_str(text),
+            Err(_) => serializer.collect_seq(&self.0),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Payload {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        struct PayloadVisitor;
+
+        impl<'de> Visitor<'de> for PayloadVisitor {
+            type Value = Payload;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                formatter.write_str("a string, or bytes")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Payload, E> {
+                Ok(Payload(value.as_bytes().to_vec()))
+            }
+
+            fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Payload, E> {
+                Ok(Payload(value.to_vec()))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Payload, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(Payload(bytes))
+            }
+        }
+
+        deserializer.deserialize_any(PayloadVisitor)
+    }
+}
+
+/// A serialized copy of the serializable parts of a [`Context`].
+///
+/// Snapshots contain the Context data, and any resources which have been registered using
+/// [`Context::register_snapshot_resource()`].  Everything else, such as event senders, jobs,
+/// and timers, is left out, and is not touched when the snapshot is restored.
+///
+/// Snapshots are created by [`Context::snapshot()`], and restored by
+/// [`Context::restore_snapshot()`].  The data, and resources, are serialized straight into the
+/// chosen [`SnapshotFormat`], so a snapshot keeps everything the format supports.  Snapshots
+/// can be encoded to, and decoded from, bytes for saving to disk, or sending over the network.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// use wolf_engine::prelude::*;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Score(u32);
+///
+/// let (_event_loop, mut context) = wolf_engine::init(String::from("Level 1"));
+/// context.insert_resource(Score(10));
+/// context.register_snapshot_resource::<Score>("score").unwrap();
+///
+/// let checkpoint = context.snapshot(SnapshotFormat::Ron).unwrap();
+/// let bytes = checkpoint.encode().unwrap();
+///
+/// context.data = String::from("Level 2");
+/// context.resource_mut::<Score>().unwrap().0 = 0;
+///
+/// let checkpoint = Snapshot::decode(SnapshotFormat::Ron, &bytes).unwrap();
+/// context.restore_snapshot(&checkpoint).unwrap();
+///
+/// assert_eq!(context.data, "Level 1");
+/// assert_eq!(context.resource::<Score>().unwrap().0, 10);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    format: SnapshotFormat,
+    data: Payload,
+    resources: BTreeMap<String, Payload>,
+}
+
+impl Snapshot {
+    /// Returns the format the snapshot's data, and resources, were serialized in.
+    pub fn format(&self) -> SnapshotFormat {
+        self.format
+    }
+
+    /// Encodes the snapshot using its [format](Snapshot::format()).
+    pub fn encode(&self) -> Result<Vec<u8>, SnapshotError> {
+        self.format
+            .serialize(self)
+            .map_err(|error| SnapshotError::Encode(self.format, error))
+    }
+
+    /// Decodes a snapshot which was encoded using the provided format.
+    pub fn decode(format: SnapshotFormat, bytes: &[u8]) -> Result<Self, SnapshotError> {
+        format
+            .deserialize(bytes)
+            .map_err(|error| SnapshotError::Decode(format, error))
+    }
+
+    /// Returns the keys of the resources stored in the snapshot.
+    pub fn resource_keys(&self) -> impl Iterator<Item = &str> {
+        self.resources.keys().map(String::as_str)
+    }
+}
+
+type StagedResource = Box<dyn Any>;
+
+/// Knows how to save, and load, one type of resource.
+pub(crate) struct SnapshotResource {
+    key: &'static str,
+    save: fn(&Resources, SnapshotFormat) -> Option<Result<Payload, SnapshotError>>,
+    load: fn(&Payload, SnapshotFormat) -> Result<StagedResource, SnapshotError>,
+    insert: fn(&mut Resources, StagedResource),
+}
+
+fn save_resource<T: Serialize + 'static>(
+    resources: &Resources,
+    format: SnapshotFormat,
+) -> Option<Result<Payload, SnapshotError>> {
+    resources.get::<T>().ok().map(|resource| {
+        format
+            .serialize(resource)
+            .map(Payload)
+            .map_err(SnapshotError::Serialize)
+    })
+}
+
+fn load_resource<T: DeserializeOwned + 'static>(
+    payload: &Payload,
+    format: SnapshotFormat,
+) -> Result<StagedResource, SnapshotError> {
+    format
+        .deserialize::<T>(&payload.0)
+        .map(|resource| Box::new(resource) as StagedResource)
+        .map_err(SnapshotError::Deserialize)
+}
+
+fn insert_resource<T: Send + 'static>(resources: &mut Resources, resource: StagedResource) {
+    if let Ok(resource) = resource.downcast::<T>() {
+        resources.insert(*resource);
+    }
+}
+
+impl<D> Context<D> {
+    /// Opts a resource type into [snapshots](Snapshot), storing it under the provided key.
+    ///
+    /// The key must be unique, and should stay the same between versions of the game, so older
+    /// snapshots can still be restored.  Resources which have not been inserted when a snapshot
+    /// is taken are left out of the snapshot.
+    pub fn register_snapshot_resource<T>(&mut self, key: &'static str) -> Result<(), SnapshotError>
+    where
+        T: Serialize + DeserializeOwned + Send + 'static,
+    {
+        if self
+            .snapshot_resources
+            .iter()
+            .any(|snapshot_resource| snapshot_resource.key == key)
+        {
+            return Err(SnapshotError::DuplicateKey { key });
+        }
+        self.snapshot_resources.push(SnapshotResource {
+            key,
+            save: save_resource::<T>,
+            load: load_resource::<T>,
+            insert: insert_resource::<T>,
+        });
+        Ok(())
+    }
+
+    /// Creates a [`Snapshot`] of the Context data, and registered resources, serialized in the
+    /// provided format.
+    pub fn snapshot(&self, format: SnapshotFormat) -> Result<Snapshot, SnapshotError>
+    where
+        D: Serialize,
+    {
+        let data = format
+            .serialize(&self.data)
+            .map(Payload)
+            .map_err(SnapshotError::Serialize)?;
+        let mut resources = BTreeMap::new();
+        for snapshot_resource in self.snapshot_resources.iter() {
+            if let Some(payload) = (snapshot_resource.save)(self.resources(), format) {
+                resources.insert(snapshot_resource.key.to_string(), payload?);
+            }
+        }
+        Ok(Snapshot {
+            format,
+            data,
+            resources,
+        })
+    }
+
+    /// Restores the Context data, and registered resources, from a [`Snapshot`].
+    ///
+    /// The whole snapshot is deserialized before anything is changed, so if an error is
+    /// returned, the Context is left untouched.  Resources which are not in the snapshot are
+    /// left as they are.
+    pub fn restore_snapshot(&mut self, snapshot: &Snapshot) -> Result<(), SnapshotError>
+    where
+        D: DeserializeOwned,
+    {
+        let data = snapshot
+            .format
+            .deserialize::<D>(&snapshot.data.0)
+            .map_err(SnapshotError::Deserialize)?;
+        let mut staged_resources = Vec::with_capacity(snapshot.resources.len());
+        for (key, payload) in snapshot.resources.iter() {
+            let snapshot_resource = self
+                .snapshot_resources
+                .iter()
+                .find(|snapshot_resource| snapshot_resource.key == key)
+                .ok_or_else(|| SnapshotError::UnregisteredResource { key: key.clone() })?;
+            let resource = (snapshot_resource.load)(payload, snapshot.format)?;
+            staged_resources.push((snapshot_resource.insert, resource));
+        }
+        self.data = data;
+        for (insert, resource) in staged_resources {
+            insert(self.resources_mut(), resource);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+    use test_case::test_case;
+
+    use crate::prelude::*;
+
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    struct GameData {
+        level: String,
+        lives: u8,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Score(u32);
+
+    #[derive(Debug, PartialEq)]
+    struct NotSaved(u32);
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Terrain {
+        heights: HashMap<(i32, i32), f64>,
+        seed: u128,
+        offset: i128,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Gravity(f32);
+
+    fn terrain() -> Terrain {
+        Terrain {
+            heights: HashMap::from([
+                ((0, 0), 1.5),
+                ((-1, 2), f64::NAN),
+                ((3, -4), f64::INFINITY),
+                ((5, 6), f64::NEG_INFINITY),
+            ]),
+            seed: u128::MAX,
+            offset: i128::MIN,
+        }
+    }
+
+    fn game_data() -> GameData {
+        GameData {
+            level: "Level 1".to_string(),
+            lives: 3,
+        }
+    }
+
+    #[test_case(SnapshotFormat::Json)]
+    #[test_case(SnapshotFormat::Ron)]
+    #[test_case(SnapshotFormat::MessagePack)]
+    fn should_round_trip_through_each_format(format: SnapshotFormat) {
+        let (_event_loop, mut context) = crate::init(game_data());
+        context.insert_resource(Score(100));
+        context
+            .register_snapshot_resource::<Score>("score")
+            .unwrap();
+        let snapshot = context.snapshot(format).unwrap();
+
+        let bytes = snapshot.encode().unwrap();
+
+        assert_eq!(Snapshot::decode(format, &bytes).unwrap(), snapshot);
+    }
+
+    #[test_case(SnapshotFormat::Ron)]
+    #[test_case(SnapshotFormat::MessagePack)]
+    fn should_round_trip_values_json_can_not_represent(format: SnapshotFormat) {
+        let (_event_loop, mut context) = crate::init(terrain());
+        context.insert_resource(Gravity(f32::INFINITY));
+        context
+            .register_snapshot_resource::<Gravity>("gravity")
+            .unwrap();
+        let bytes = context.snapshot(format).unwrap().encode().unwrap();
+
+        context.data = Terrain {
+            heights: HashMap::new(),
+            seed: 0,
+            offset: 0,
+        };
+        context.resource_mut::<Gravity>().unwrap().0 = 0.0;
+        let snapshot = Snapshot::decode(format, &bytes).unwrap();
+        context.restore_snapshot(&snapshot).unwrap();
+
+        let heights = &context.data.heights;
+        assert_eq!(heights.len(), 4);
+        assert_eq!(heights[&(0, 0)], 1.5);
+        assert!(heights[&(-1, 2)].is_nan());
+        assert_eq!(heights[&(3, -4)], f64::INFINITY);
+        assert_eq!(heights[&(5, 6)], f64::NEG_INFINITY);
+        assert_eq!(context.data.seed, u128::MAX);
+        assert_eq!(context.data.offset, i128::MIN);
+        assert_eq!(context.resource::<Gravity>().unwrap().0, f32::INFINITY);
+    }
+
+    #[test]
+    fn should_report_values_the_format_can_not_represent() {
+        let (_event_loop, context) = crate::init(terrain());
+
+        let result = context.snapshot(SnapshotFormat::Json);
+
+        assert!(matches!(result, Err(SnapshotError::Serialize(_))));
+    }
+
+    #[test]
+    fn should_decode_snapshots_stored_in_another_format() {
+        let (_event_loop, mut context) = crate::init(game_data());
+        let snapshot = context.snapshot(SnapshotFormat::MessagePack).unwrap();
+
+        let json = serde_json::to_vec(&snapshot).unwrap();
+        let snapshot = serde_json::from_slice::<Snapshot>(&json).unwrap();
+        context.data.lives = 0;
+        context.restore_snapshot(&snapshot).unwrap();
+
+        assert_eq!(snapshot.format(), SnapshotFormat::MessagePack);
+        assert_eq!(context.data, game_data());
+    }
+
+    #[test]
+    fn should_restore_data_and_registered_resources() {
+        let (_event_loop, mut context) = crate::init(game_data());
+        context.insert_resource(Score(100));
+        context.insert_resource(NotSaved(1));
+        context
+            .register_snapshot_resource::<Score>("score")
+            .unwrap();
+        let snapshot = context.snapshot(SnapshotFormat::Json).unwrap();
+
+        context.data.lives = 0;
+        context.resource_mut::<Score>().unwrap().0 = 0;
+        context.resource_mut::<NotSaved>().unwrap().0 = 2;
+        context.restore_snapshot(&snapshot).unwrap();
+
+        assert_eq!(context.data, game_data());
+        assert_eq!(context.resource::<Score>(), Ok(&Score(100)));
+        assert_eq!(context.resource::<NotSaved>(), Ok(&NotSaved(2)));
+        assert_eq!(snapshot.resource_keys().collect::<Vec<_>>(), vec!["score"]);
+    }
+
+    #[test]
+    fn should_keep_the_event_sender_working_after_restoring() {
+        let (mut event_loop, mut context) = crate::init(game_data());
+        let snapshot = context.snapshot(SnapshotFormat::Json).unwrap();
+
+        context.restore_snapshot(&snapshot).unwrap();
+        context.quit();
+
+        assert_eq!(event_loop.next_event(), Some(Event::Quit));
+    }
+
+    #[test]
+    fn should_leave_the_context_untouched_when_restoring_fails() {
+        let (_event_loop, mut context) = crate::init(game_data());
+        context.insert_resource(Score(100));
+        context
+            .register_snapshot_resource::<Score>("score")
+            .unwrap();
+        let snapshot = context.snapshot(SnapshotFormat::Json).unwrap();
+        let (_other_event_loop, mut other_context) = crate::init(GameData {
+            level: "Level 2".to_string(),
+            lives: 1,
+        });
+
+        let result = other_context.restore_snapshot(&snapshot);
+
+        assert_eq!(
+            result,
+            Err(SnapshotError::UnregisteredResource {
+                key: "score".to_string()
+            })
+        );
+        assert_eq!(other_context.data.level, "Level 2");
+    }
+
+    #[test]
+    fn should_reject_duplicate_keys() {
+        let (_event_loop, mut context) = crate::init(game_data());
+        context
+            .register_snapshot_resource::<Score>("score")
+            .unwrap();
+
+        assert_eq!(
+            context.register_snapshot_resource::<Score>("score"),
+            Err(SnapshotError::DuplicateKey { key: "score" })
+        );
+    }
+
+    #[test]
+    fn should_report_decoding_errors() {
+        let result = Snapshot::decode(SnapshotFormat::Json, b"Not a snapshot");
+
+        assert!(matches!(
+            result,
+            Err(SnapshotError::Decode(SnapshotFormat::Json, _))
+        ));
+    }
+}
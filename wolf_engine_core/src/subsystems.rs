@@ -0,0 +1,473 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::events::*;
+use crate::{sort_by_dependencies, Context, DependencyError};
+
+/// Indicates a problem registering, or starting, a [`Subsystem`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SubsystemError {
+    /// A subsystem with the same name was already registered.
+    Duplicate {
+        /// The name of the subsystem.
+        name: &'static str,
+    },
+    /// A subsystem depends on a subsystem which was never registered.
+    MissingDependency {
+        /// The name of the subsystem.
+        name: &'static str,
+        /// The name of the missing dependency.
+        dependency: &'static str,
+    },
+    /// The dependencies of some subsystems form a cycle, so they can't be ordered.
+    DependencyCycle {
+        /// The names of the subsystems which could not be ordered, in registration order.
+        names: Vec<&'static str>,
+    },
+}
+
+impl Display for SubsystemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Duplicate { name } => {
+                write!(f, "A subsystem named `{}` is already registered", name)
+            }
+            Self::MissingDependency { name, dependency } => write!(
+                f,
+                "Subsystem `{}` depends on `{}`, which is not registered",
+                name, dependency
+            ),
+            Self::DependencyCycle { names } => write!(
+                f,
+                "The dependencies of these subsystems form a cycle: {}",
+                names.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for SubsystemError {}
+
+impl From<DependencyError> for SubsystemError {
+    fn from(error: DependencyError) -> Self {
+        match error {
+            DependencyError::Missing { name, dependency } => {
+                Self::MissingDependency { name, dependency }
+            }
+            DependencyError::Cycle { names } => Self::DependencyCycle { names },
+        }
+    }
+}
+
+/// Provides a standard interface for parts of the engine which hook into its lifecycle.
+///
+/// Subsystems are registered on the [`Context`] using [`Context::add_subsystem()`], and are
+/// then driven by the engine:
+///
+/// - [`Subsystem::on_start()`] is called once, at the start of the first frame after the
+///   subsystem is registered.
+/// - [`Subsystem::on_event()`] is called for every event, before the event handler sees it.
+/// - [`Subsystem::on_frame()`] is called at the start of every frame.
+/// - [`Subsystem::on_shutdown()`] is called once the engine has quit.
+///
+/// Subsystems are called in a deterministic order:  A subsystem is always called after the
+/// subsystems listed in its [`Subsystem::dependencies()`], and otherwise in the order they were
+/// registered.  Shutdown happens in the reverse order.
+///
+/// Subsystems are owned by the [`Context`], so they must be [`Send`].
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core as wolf_engine;
+/// use wolf_engine::prelude::*;
+///
+/// struct FrameCounter {
+///     frames: u32,
+/// }
+///
+/// impl Subsystem<()> for FrameCounter {
+///     fn name(&self) -> &'static str {
+///         "frame_counter"
+///     }
+///
+///     fn on_frame(&mut self, context: &mut Context<()>) {
+///         self.frames += 1;
+///         if self.frames == 3 {
+///             context.quit();
+///         }
+///     }
+/// }
+///
+/// let (event_loop, mut context) = wolf_engine::init(());
+/// context.add_subsystem(FrameCounter { frames: 0 }).unwrap();
+///
+/// event_loop.run(context, |_event, _context, _control_flow| ());
+/// ```
+pub trait Subsystem<D>: Send {
+    /// Returns the subsystem's name, which must be unique.
+    ///
+    /// Other subsystems use this name to depend on this one.  Defaults to the name of the type.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Returns the names of the subsystems this one depends on.
+    fn dependencies(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Called once, before the subsystem's first frame.
+    fn on_start(&mut self, _context: &mut Context<D>) {}
+
+    /// Called for every event.
+    fn on_event(&mut self, _event: Event, _context: &mut Context<D>) {}
+
+    /// Called at the start of every frame.
+    fn on_frame(&mut self, _context: &mut Context<D>) {}
+
+    /// Called once the engine has quit.
+    fn on_shutdown(&mut self, _context: &mut Context<D>) {}
+}
+
+struct SubsystemEntry<D> {
+    subsystem: Box<dyn Subsystem<D>>,
+    is_started: bool,
+}
+
+/// Stores the subsystems owned by the [`Context`].
+pub(crate) struct Subsystems<D> {
+    entries: Vec<SubsystemEntry<D>>,
+    names: Vec<&'static str>,
+    needs_start: bool,
+}
+
+impl<D> Subsystems<D> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            names: Vec::new(),
+            needs_start: false,
+        }
+    }
+
+    pub fn add(&mut self, subsystem: Box<dyn Subsystem<D>>) -> Result<(), SubsystemError> {
+        let name = subsystem.name();
+        if self.names.contains(&name) {
+            return Err(SubsystemError::Duplicate { name });
+        }
+        self.names.push(name);
+        self.entries.push(SubsystemEntry {
+            subsystem,
+            is_started: false,
+        });
+        self.needs_start = true;
+        Ok(())
+    }
+
+    /// Sorts the subsystems so each one comes after its dependencies, keeping the registration
+    /// order wherever possible.
+    fn sort(&mut self) -> Result<(), SubsystemError> {
+        sort_by_dependencies(
+            &mut self.entries,
+            |entry| entry.subsystem.name(),
+            |entry| entry.subsystem.dependencies(),
+        )?;
+        Ok(())
+    }
+
+    /// Checks the subsystems can be ordered, without starting them.
+    pub fn validate(&mut self) -> Result<(), SubsystemError> {
+        self.sort()
+    }
+}
+
+impl<D> Context<D> {
+    /// Registers a [`Subsystem`], which will be started at the start of the next frame.
+    ///
+    /// Returns an error if a subsystem with the same name is already registered.  Dependencies
+    /// are not checked until the subsystem is started, so subsystems can be registered in any
+    /// order.
+    pub fn add_subsystem<S: Subsystem<D> + 'static>(
+        &mut self,
+        subsystem: S,
+    ) -> Result<(), SubsystemError> {
+        self.subsystems.add(Box::new(subsystem))
+    }
+
+    /// Returns `true` if a subsystem with the provided name is registered.
+    pub fn has_subsystem(&self, name: &str) -> bool {
+        self.subsystems.names.contains(&name)
+    }
+
+    /// Returns the names of the registered subsystems, in the order they are called.
+    pub fn subsystem_names(&self) -> Vec<&'static str> {
+        self.subsystems
+            .entries
+            .iter()
+            .map(|entry| entry.subsystem.name())
+            .collect()
+    }
+
+    /// Orders the registered subsystems, then calls [`Subsystem::on_start()`] on any which have
+    /// not been started yet.
+    ///
+    /// This is called automatically by [`Context::start_frame()`], which logs the error, and
+    /// quits, if the subsystems can't be started.
+    pub fn start_subsystems(&mut self) -> Result<(), SubsystemError> {
+        if !self.subsystems.needs_start {
+            return Ok(());
+        }
+        self.subsystems.sort()?;
+        self.subsystems.needs_start = false;
+        self.for_each_subsystem(|subsystem, is_started, context| {
+            if !*is_started {
+                *is_started = true;
+                subsystem.on_start(context);
+            }
+        });
+        Ok(())
+    }
+
+    /// Passes the event to every started subsystem.
+    ///
+    /// This is called automatically by [`EventLoop::run()`](crate::EventLoop::run()) before the
+    /// event handler is called.  Custom main-loops should do the same.
+    pub fn notify_subsystems(&mut self, event: Event) {
+        self.for_each_subsystem(|subsystem, is_started, context| {
+            if *is_started {
+                subsystem.on_event(event, context);
+            }
+        });
+    }
+
+    pub(crate) fn update_subsystems(&mut self) {
+        self.for_each_subsystem(|subsystem, is_started, context| {
+            if *is_started {
+                subsystem.on_frame(context);
+            }
+        });
+    }
+
+    pub(crate) fn shut_down_subsystems(&mut self) {
+        let mut entries = std::mem::take(&mut self.subsystems.entries);
+        for entry in entries.iter_mut().rev() {
+            if entry.is_started {
+                entry.is_started = false;
+                entry.subsystem.on_shutdown(self);
+            }
+        }
+        entries.append(&mut self.subsystems.entries);
+        self.subsystems.entries = entries;
+    }
+
+    fn for_each_subsystem<F>(&mut self, mut function: F)
+    where
+        F: FnMut(&mut dyn Subsystem<D>, &mut bool, &mut Context<D>),
+    {
+        let mut entries = std::mem::take(&mut self.subsystems.entries);
+        for entry in entries.iter_mut() {
+            function(entry.subsystem.as_mut(), &mut entry.is_started, self);
+        }
+        // Keep any subsystems which were registered by the subsystems themselves.
+        entries.append(&mut self.subsystems.entries);
+        self.subsystems.entries = entries;
+    }
+}
+
+#[cfg(test)]
+mod subsystem_tests {
+    use ntest::timeout;
+
+    use crate::prelude::*;
+    use crate::testing::CallLog;
+
+    struct TestSubsystem {
+        name: &'static str,
+        dependencies: Vec<&'static str>,
+        log: CallLog,
+    }
+
+    impl TestSubsystem {
+        fn new(name: &'static str, dependencies: Vec<&'static str>, log: &CallLog) -> Self {
+            Self {
+                name,
+                dependencies,
+                log: log.clone(),
+            }
+        }
+
+        fn record(&self, hook: &str) {
+            self.log.push(format!("{} {}", self.name, hook));
+        }
+    }
+
+    impl Subsystem<()> for TestSubsystem {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn dependencies(&self) -> Vec<&'static str> {
+            self.dependencies.clone()
+        }
+
+        fn on_start(&mut self, _context: &mut Context<()>) {
+            self.record("start");
+        }
+
+        fn on_event(&mut self, event: Event, _context: &mut Context<()>) {
+            if event == Event::Quit {
+                self.record("quit");
+            }
+        }
+
+        fn on_frame(&mut self, _context: &mut Context<()>) {
+            self.record("frame");
+        }
+
+        fn on_shutdown(&mut self, _context: &mut Context<()>) {
+            self.record("shutdown");
+        }
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_call_hooks_in_dependency_order() {
+        let log = CallLog::new();
+        let (event_loop, mut context) = crate::init(());
+        context
+            .add_subsystem(TestSubsystem::new("renderer", vec!["window"], &log))
+            .unwrap();
+        context
+            .add_subsystem(TestSubsystem::new("window", vec![], &log))
+            .unwrap();
+
+        event_loop.run(context, |event, context, _control_flow| {
+            if event == Event::EventsCleared {
+                context.quit();
+            }
+        });
+
+        assert_eq!(
+            log.messages(),
+            vec![
+                "window start",
+                "renderer start",
+                "window frame",
+                "renderer frame",
+                "window quit",
+                "renderer quit",
+                "renderer shutdown",
+                "window shutdown",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_keep_registration_order_without_dependencies() {
+        let log = CallLog::new();
+        let (_event_loop, mut context) = crate::init(());
+        for name in ["c", "a", "b"] {
+            context
+                .add_subsystem(TestSubsystem::new(name, vec![], &log))
+                .unwrap();
+        }
+
+        context.start_subsystems().unwrap();
+
+        assert_eq!(context.subsystem_names(), vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn should_reject_duplicate_subsystems() {
+        let log = CallLog::new();
+        let (_event_loop, mut context) = crate::init(());
+        context
+            .add_subsystem(TestSubsystem::new("audio", vec![], &log))
+            .unwrap();
+
+        let result = context.add_subsystem(TestSubsystem::new("audio", vec![], &log));
+
+        assert_eq!(result, Err(SubsystemError::Duplicate { name: "audio" }));
+    }
+
+    #[test]
+    fn should_report_missing_dependencies() {
+        let log = CallLog::new();
+        let (_event_loop, mut context) = crate::init(());
+        context
+            .add_subsystem(TestSubsystem::new("renderer", vec!["window"], &log))
+            .unwrap();
+
+        assert_eq!(
+            context.start_subsystems(),
+            Err(SubsystemError::MissingDependency {
+                name: "renderer",
+                dependency: "window"
+            })
+        );
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn should_report_dependency_cycles() {
+        let log = CallLog::new();
+        let (_event_loop, mut context) = crate::init(());
+        context
+            .add_subsystem(TestSubsystem::new("a", vec!["b"], &log))
+            .unwrap();
+        context
+            .add_subsystem(TestSubsystem::new("b", vec!["a"], &log))
+            .unwrap();
+        context
+            .add_subsystem(TestSubsystem::new("c", vec![], &log))
+            .unwrap();
+
+        assert_eq!(
+            context.start_subsystems(),
+            Err(SubsystemError::DependencyCycle {
+                names: vec!["a", "b"]
+            })
+        );
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_quit_when_subsystems_fail_to_start() {
+        let log = CallLog::new();
+        let (event_loop, mut context) = crate::init(());
+        context
+            .add_subsystem(TestSubsystem::new("a", vec!["missing"], &log))
+            .unwrap();
+
+        event_loop.run(context, |_event, _context, _control_flow| ());
+
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn should_start_subsystems_added_while_running() {
+        let log = CallLog::new();
+        let (_event_loop, mut context) = crate::init(());
+        context
+            .add_subsystem(TestSubsystem::new("first", vec![], &log))
+            .unwrap();
+        context.start_frame();
+
+        context
+            .add_subsystem(TestSubsystem::new("second", vec!["first"], &log))
+            .unwrap();
+        context.start_frame();
+
+        assert_eq!(
+            log.messages(),
+            vec![
+                "first start",
+                "first frame",
+                "second start",
+                "first frame",
+                "second frame",
+            ]
+        );
+    }
+}
@@ -0,0 +1,453 @@
+//! Provides a headless test harness for driving the engine frame-by-frame.
+//!
+//! The [`TestHarness`] runs the [`EventLoop`] for a set number of frames, or until a condition
+//! is met, and records every event it emits.  It removes the need for timeouts and manual frame
+//! counters when testing game logic against the engine.
+//!
+//! # Examples
+//!
+//! ```
+//! # use wolf_engine_core as wolf_engine;
+//! use wolf_engine::prelude::*;
+//! use wolf_engine::testing::TestHarness;
+//!
+//! let mut harness = TestHarness::new(0).with_event_handler(|event, context| {
+//!     if event == Event::EventsCleared {
+//!         context.data += 1;
+//!     }
+//! });
+//!
+//! harness.run_frames(3);
+//!
+//! assert_eq!(harness.context().data, 3);
+//! harness.assert_events(&[Event::EventsCleared; 3]);
+//! ```
+//!
+//! Events can be injected at the start of a specific frame.
+//!
+//! ```
+//! # use wolf_engine_core as wolf_engine;
+//! # use wolf_engine::prelude::*;
+//! # use wolf_engine::testing::TestHarness;
+//! #
+//! let mut harness = TestHarness::new(()).with_event_at(1, Event::Quit);
+//!
+//! let frames = harness.run_frames(10);
+//!
+//! assert_eq!(frames, 1, "The engine should quit during the second frame.");
+//! harness.assert_events(&[Event::EventsCleared, Event::Quit]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::events::*;
+use crate::{Context, Engine, EventLoop};
+
+type TestEventHandler<D> = Box<dyn FnMut(Event, &mut Context<D>)>;
+
+/// Drives the engine for a controlled number of frames, and captures the emitted events.
+///
+/// A frame is considered finished once the last of the Event-Loop's
+/// [frame phases](EventLoop::frame_phases()) has been emitted.  Like
+/// [`EventLoop::run()`], the harness calls [`Context::start_frame()`] at the start of each
+/// frame, and [`Context::shutdown()`] once the engine has quit.
+pub struct TestHarness<D> {
+    event_loop: EventLoop,
+    context: Context<D>,
+    event_handler: TestEventHandler<D>,
+    scheduled_events: BTreeMap<u64, Vec<Event>>,
+    captured_events: Vec<Event>,
+    frame: u64,
+    has_shut_down: bool,
+}
+
+impl<D> TestHarness<D> {
+    /// Creates a new test harness for a freshly-initialized engine using the provided data.
+    pub fn new(data: D) -> Self {
+        Self::from_engine(crate::init(data))
+    }
+
+    /// Creates a new test harness for an existing [`Engine`].
+    pub fn from_engine(engine: Engine<D>) -> Self {
+        let (event_loop, context) = engine;
+        Self {
+            event_loop,
+            context,
+            event_handler: Box::from(|_, _: &mut Context<D>| ()),
+            scheduled_events: BTreeMap::new(),
+            captured_events: Vec::new(),
+            frame: 0,
+            has_shut_down: false,
+        }
+    }
+
+    /// Sets the event handler which is called for every event emitted by the Event-Loop.
+    pub fn with_event_handler<F>(mut self, event_handler: F) -> Self
+    where
+        F: FnMut(Event, &mut Context<D>) + 'static,
+    {
+        self.event_handler = Box::from(event_handler);
+        self
+    }
+
+    /// Schedules an event to be sent at the start of the provided frame.
+    ///
+    /// Frames are counted from `0`.
+    pub fn with_event_at(mut self, frame: u64, event: Event) -> Self {
+        self.inject_event_at(frame, event);
+        self
+    }
+
+    /// Schedules an event to be sent at the start of the provided frame.
+    ///
+    /// Frames are counted from `0`.  Events scheduled for a frame which has already run are sent
+    /// at the start of the next frame instead.
+    pub fn inject_event_at(&mut self, frame: u64, event: Event) {
+        self.scheduled_events
+            .entry(frame.max(self.frame))
+            .or_default()
+            .push(event);
+    }
+
+    /// Runs the engine for the provided number of frames.
+    ///
+    /// Returns the number of frames which were actually finished.  This will be less than
+    /// requested if the engine quits early.
+    pub fn run_frames(&mut self, frames: u64) -> u64 {
+        let mut finished_frames = 0;
+        while finished_frames < frames && self.run_frame() {
+            finished_frames += 1;
+        }
+        finished_frames
+    }
+
+    /// Runs the engine until the predicate returns `true`, checking it after every frame.
+    ///
+    /// Returns the number of frames it took for the predicate to be satisfied, or an error if
+    /// it wasn't satisfied within `max_frames`, or the engine quit first.
+    pub fn run_until<P>(&mut self, max_frames: u64, mut predicate: P) -> Result<u64, String>
+    where
+        P: FnMut(&Context<D>) -> bool,
+    {
+        for finished_frames in 0..max_frames {
+            if predicate(&self.context) {
+                return Ok(finished_frames);
+            }
+            if !self.run_frame() {
+                return Err(format!(
+                    "The engine quit after {} frames, before the predicate was satisfied",
+                    finished_frames
+                ));
+            }
+        }
+        if predicate(&self.context) {
+            Ok(max_frames)
+        } else {
+            Err(format!(
+                "The predicate was not satisfied within {} frames",
+                max_frames
+            ))
+        }
+    }
+
+    fn run_frame(&mut self) -> bool {
+        self.send_scheduled_events();
+        while let Some(event) = self.event_loop.next_event() {
+            self.captured_events.push(event);
+            if self.event_loop.has_started_frame() {
+                self.context.start_frame();
+            }
+            self.context.notify_subsystems(event);
+            (self.event_handler)(event, &mut self.context);
+            if self.event_loop.has_finished_frame() {
+                self.frame += 1;
+                return true;
+            }
+        }
+        if !self.has_shut_down {
+            self.has_shut_down = true;
+            self.context.shutdown();
+        }
+        false
+    }
+
+    fn send_scheduled_events(&mut self) {
+        if let Some(events) = self.scheduled_events.remove(&self.frame) {
+            let event_sender = self.context.event_sender();
+            for event in events {
+                event_sender.send_event(event).ok();
+            }
+        }
+    }
+
+    /// Returns the number of frames which have been run so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns every event emitted by the Event-Loop so far, in order.
+    pub fn events(&self) -> &[Event] {
+        &self.captured_events
+    }
+
+    /// Clears the captured events.
+    pub fn clear_events(&mut self) {
+        self.captured_events.clear();
+    }
+
+    /// Returns a reference to the [`Context`].
+    pub fn context(&self) -> &Context<D> {
+        &self.context
+    }
+
+    /// Returns a mutable reference to the [`Context`].
+    pub fn context_mut(&mut self) -> &mut Context<D> {
+        &mut self.context
+    }
+
+    /// Returns a mutable reference to the [`EventLoop`].
+    pub fn event_loop_mut(&mut self) -> &mut EventLoop {
+        &mut self.event_loop
+    }
+
+    /// Asserts the captured events match the expected events exactly.
+    ///
+    /// # Panics
+    ///
+    /// Panics with a line-by-line diff if the events do not match.
+    pub fn assert_events(&self, expected: &[Event]) {
+        assert_event_sequence(expected, &self.captured_events);
+    }
+
+    /// Asserts the captured events, for which the filter returns `true`, match the expected
+    /// events exactly.
+    ///
+    /// This is useful for ignoring the frame events emitted every frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics with a line-by-line diff if the events do not match.
+    pub fn assert_events_where<F>(&self, filter: F, expected: &[Event])
+    where
+        F: Fn(&Event) -> bool,
+    {
+        let actual = self
+            .captured_events
+            .iter()
+            .copied()
+            .filter(|event| filter(event))
+            .collect::<Vec<Event>>();
+        assert_event_sequence(expected, &actual);
+    }
+}
+
+fn assert_event_sequence(expected: &[Event], actual: &[Event]) {
+    if expected != actual {
+        panic!(
+            "The captured events did not match the expected events:\n{}",
+            event_diff(expected, actual)
+        );
+    }
+}
+
+fn event_diff(expected: &[Event], actual: &[Event]) -> String {
+    let mut diff = String::from("  (- expected, + actual)\n");
+    for index in 0..expected.len().max(actual.len()) {
+        match (expected.get(index), actual.get(index)) {
+            (Some(expected), Some(actual)) if expected == actual => {
+                writeln!(diff, "  {:>4}: {:?}", index, expected).ok();
+            }
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    writeln!(diff, "- {:>4}: {:?}", index, expected).ok();
+                }
+                if let Some(actual) = actual {
+                    writeln!(diff, "+ {:>4}: {:?}", index, actual).ok();
+                }
+            }
+        }
+    }
+    diff
+}
+
+/// A list of messages, which is shared between its clones, and can be sent to other threads.
+///
+/// Tests can give a clone to each subsystem, plugin, or event handler, then check the order
+/// things happened in afterwards.
+///
+/// # Examples
+///
+/// ```
+/// # use wolf_engine_core::testing::CallLog;
+/// #
+/// let log = CallLog::new();
+/// let handler_log = log.clone();
+///
+/// std::thread::spawn(move || handler_log.push("thread"))
+///     .join()
+///     .unwrap();
+/// log.push("main");
+///
+/// assert_eq!(log.take(), vec!["thread", "main"]);
+/// assert!(log.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CallLog {
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl CallLog {
+    /// Creates an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a message to the end of the log.
+    pub fn push<S: Into<String>>(&self, message: S) {
+        self.lock().push(message.into());
+    }
+
+    /// Returns a copy of the messages, in the order they were added.
+    pub fn messages(&self) -> Vec<String> {
+        self.lock().clone()
+    }
+
+    /// Removes, and returns, the messages, in the order they were added.
+    pub fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// Returns `true` if the log has no messages.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<String>> {
+        self.messages
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod test_harness_tests {
+    use super::*;
+    use crate::Subsystem;
+
+    #[test]
+    fn should_run_for_exactly_n_frames() {
+        let mut harness = TestHarness::new(0).with_event_handler(|event, context| {
+            if event == Event::EventsCleared {
+                context.data += 1;
+            }
+        });
+
+        assert_eq!(harness.run_frames(5), 5);
+        assert_eq!(harness.frame(), 5);
+        assert_eq!(harness.context().data, 5);
+    }
+
+    #[test]
+    fn should_count_frames_using_the_last_frame_phase() {
+        let mut harness = TestHarness::new(());
+        harness
+            .event_loop_mut()
+            .set_frame_phases(FramePhase::standard_phases());
+
+        harness.run_frames(2);
+
+        assert_eq!(harness.events().len(), 10);
+    }
+
+    #[test]
+    fn should_run_until_the_predicate_holds() {
+        let mut harness = TestHarness::new(0).with_event_handler(|event, context| {
+            if event == Event::EventsCleared {
+                context.data += 2;
+            }
+        });
+
+        assert_eq!(harness.run_until(100, |context| context.data >= 10), Ok(5));
+    }
+
+    #[test]
+    fn should_fail_if_the_predicate_does_not_hold_in_time() {
+        let mut harness = TestHarness::new(0);
+
+        assert!(harness.run_until(3, |context| context.data > 0).is_err());
+        assert_eq!(harness.frame(), 3);
+    }
+
+    #[test]
+    fn should_stop_early_if_the_engine_quits() {
+        let mut harness = TestHarness::new(()).with_event_at(2, Event::Quit);
+
+        assert_eq!(harness.run_frames(10), 2);
+        assert!(harness.run_until(10, |_| false).is_err());
+    }
+
+    #[test]
+    fn should_shut_down_the_engine_once_it_quits() {
+        struct ShutdownRecorder(CallLog);
+
+        impl Subsystem<()> for ShutdownRecorder {
+            fn name(&self) -> &'static str {
+                "shutdown_recorder"
+            }
+
+            fn on_shutdown(&mut self, _context: &mut Context<()>) {
+                self.0.push("shutdown");
+            }
+        }
+
+        let log = CallLog::new();
+        let mut harness = TestHarness::new(()).with_event_at(1, Event::Quit);
+        harness
+            .context_mut()
+            .add_subsystem(ShutdownRecorder(log.clone()))
+            .unwrap();
+
+        assert_eq!(harness.run_frames(10), 1);
+        assert_eq!(harness.run_frames(10), 0);
+        assert_eq!(log.messages(), vec!["shutdown"]);
+        assert!(harness.context().job_pool().is_shut_down());
+    }
+
+    #[test]
+    fn should_inject_events_at_the_start_of_the_given_frame() {
+        let mut harness = TestHarness::new(())
+            .with_event_at(1, Event::Test)
+            .with_event_at(1, Event::Test);
+
+        harness.run_frames(3);
+
+        harness.assert_events(&[
+            Event::EventsCleared,
+            Event::Test,
+            Event::Test,
+            Event::EventsCleared,
+            Event::EventsCleared,
+        ]);
+    }
+
+    #[test]
+    fn should_filter_captured_events() {
+        let mut harness = TestHarness::new(()).with_event_at(0, Event::Test);
+
+        harness.run_frames(3);
+
+        harness.assert_events_where(|event| *event != Event::EventsCleared, &[Event::Test]);
+    }
+
+    #[test]
+    #[should_panic(expected = "+    1: Test")]
+    fn should_panic_with_a_diff_when_events_do_not_match() {
+        let mut harness = TestHarness::new(()).with_event_at(1, Event::Test);
+
+        harness.run_frames(2);
+
+        harness.assert_events(&[Event::EventsCleared, Event::EventsCleared]);
+    }
+}
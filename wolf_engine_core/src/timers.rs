@@ -0,0 +1,393 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::Context;
+
+type TimerCallback<D> = Box<dyn FnMut(&mut Context<D>) + Send>;
+
+#[derive(Default)]
+struct TimerControl {
+    is_paused: AtomicBool,
+    is_cancelled: AtomicBool,
+    is_finished: AtomicBool,
+    should_reset: AtomicBool,
+}
+
+/// A handle used to control a timer created through the [`Context`].
+///
+/// Handles are cheap to clone, and all clones control the same timer.  Dropping the handle does
+/// *not* cancel the timer.
+#[derive(Clone)]
+pub struct TimerHandle {
+    control: Arc<TimerControl>,
+}
+
+impl TimerHandle {
+    /// Pauses the timer.  Paused timers do not count down.
+    pub fn pause(&self) {
+        self.control.is_paused.store(true, Ordering::Release);
+    }
+
+    /// Resumes a paused timer.
+    pub fn resume(&self) {
+        self.control.is_paused.store(false, Ordering::Release);
+    }
+
+    /// Restarts the timer's countdown from the beginning.
+    pub fn reset(&self) {
+        self.control.should_reset.store(true, Ordering::Release);
+    }
+
+    /// Cancels the timer.  Its callback will not be called again.
+    pub fn cancel(&self) {
+        self.control.is_cancelled.store(true, Ordering::Release);
+    }
+
+    /// Returns `true` if the timer is paused.
+    pub fn is_paused(&self) -> bool {
+        self.control.is_paused.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if the timer has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.control.is_cancelled.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if the timer has finished, and will not be called again.
+    ///
+    /// Repeating timers only finish when they are cancelled.
+    pub fn is_finished(&self) -> bool {
+        self.control.is_finished.load(Ordering::Acquire) || self.is_cancelled()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TimerKind {
+    After(Duration),
+    Every(Duration),
+    AfterFrames(u64),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Countdown {
+    Time(Duration),
+    Frames(u64),
+}
+
+impl TimerKind {
+    fn countdown(&self) -> Countdown {
+        match self {
+            Self::After(duration) | Self::Every(duration) => Countdown::Time(*duration),
+            Self::AfterFrames(frames) => Countdown::Frames(*frames),
+        }
+    }
+}
+
+struct Timer<D> {
+    kind: TimerKind,
+    countdown: Countdown,
+    control: Arc<TimerControl>,
+    callback: TimerCallback<D>,
+}
+
+impl<D> Timer<D> {
+    /// Counts down the timer, returning the number of times the callback should be called.
+    fn count_down(&mut self, delta: Duration) -> u32 {
+        if self.control.should_reset.swap(false, Ordering::AcqRel) {
+            self.countdown = self.kind.countdown();
+        }
+        if self.control.is_paused.load(Ordering::Acquire) {
+            return 0;
+        }
+        match (&mut self.countdown, self.kind) {
+            (Countdown::Frames(frames), _) => {
+                *frames = frames.saturating_sub(1);
+                u32::from(*frames == 0)
+            }
+            (Countdown::Time(remaining), TimerKind::Every(interval)) => {
+                if interval.is_zero() {
+                    return 1;
+                }
+                let mut calls = 0;
+                let mut elapsed = delta;
+                while elapsed >= *remaining {
+                    elapsed -= *remaining;
+                    *remaining = interval;
+                    calls += 1;
+                }
+                *remaining -= elapsed;
+                calls
+            }
+            (Countdown::Time(remaining), _) => {
+                *remaining = remaining.saturating_sub(delta);
+                u32::from(remaining.is_zero())
+            }
+        }
+    }
+
+    fn is_repeating(&self) -> bool {
+        matches!(self.kind, TimerKind::Every(_))
+    }
+}
+
+/// Stores the timers owned by the [`Context`].
+pub(crate) struct Timers<D> {
+    timers: Vec<Timer<D>>,
+}
+
+impl<D> Timers<D> {
+    pub fn new() -> Self {
+        Self { timers: Vec::new() }
+    }
+
+    fn add(&mut self, kind: TimerKind, callback: TimerCallback<D>) -> TimerHandle {
+        let control = Arc::new(TimerControl::default());
+        self.timers.push(Timer {
+            kind,
+            countdown: kind.countdown(),
+            control: control.clone(),
+            callback,
+        });
+        TimerHandle { control }
+    }
+}
+
+impl<D> Context<D> {
+    /// Calls the callback once the provided amount of game time has passed.
+    ///
+    /// Timers are driven by the [`Clock`](crate::Clock), so they respect paused, and scaled, game
+    /// time.  They are updated at the start of every frame by [`Context::start_frame()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use wolf_engine_core as wolf_engine;
+    /// # use wolf_engine::prelude::*;
+    /// # use std::time::Duration;
+    /// #
+    /// let (event_loop, mut context) = wolf_engine::init(());
+    ///
+    /// context.after(Duration::from_millis(10), |context| context.quit());
+    ///
+    /// event_loop.run(context, |_event, _context, _control_flow| ());
+    /// ```
+    pub fn after<F>(&mut self, duration: Duration, callback: F) -> TimerHandle
+    where
+        F: FnOnce(&mut Context<D>) + Send + 'static,
+    {
+        let mut callback = Some(callback);
+        self.timers.add(
+            TimerKind::After(duration),
+            Box::new(move |context| {
+                if let Some(callback) = callback.take() {
+                    callback(context);
+                }
+            }),
+        )
+    }
+
+    /// Calls the callback repeatedly, every time the provided interval of game time passes.
+    ///
+    /// If more than one interval passes in a single frame, the callback is called once for each
+    /// interval.  An interval of zero calls the callback once per frame.
+    pub fn every<F>(&mut self, interval: Duration, callback: F) -> TimerHandle
+    where
+        F: FnMut(&mut Context<D>) + Send + 'static,
+    {
+        self.timers
+            .add(TimerKind::Every(interval), Box::new(callback))
+    }
+
+    /// Calls the callback once the provided number of frames has passed.
+    ///
+    /// Frames which pass while game time is paused are not counted.  A value of `0` is treated as
+    /// `1`, so the callback is called during the next frame.
+    pub fn after_frames<F>(&mut self, frames: u64, callback: F) -> TimerHandle
+    where
+        F: FnOnce(&mut Context<D>) + Send + 'static,
+    {
+        let mut callback = Some(callback);
+        self.timers.add(
+            TimerKind::AfterFrames(frames.max(1)),
+            Box::new(move |context| {
+                if let Some(callback) = callback.take() {
+                    callback(context);
+                }
+            }),
+        )
+    }
+
+    /// Counts down all timers, and calls the callbacks of timers which have finished.
+    pub(crate) fn update_timers(&mut self) {
+        let delta = self.clock().delta();
+        let is_paused = self.clock().is_paused();
+        let mut timers = std::mem::take(&mut self.timers.timers);
+        for timer in timers.iter_mut() {
+            if timer.control.is_cancelled.load(Ordering::Acquire)
+                || (is_paused && matches!(timer.countdown, Countdown::Frames(_)))
+            {
+                continue;
+            }
+            for _ in 0..timer.count_down(delta) {
+                (timer.callback)(self);
+                if timer.control.is_cancelled.load(Ordering::Acquire) {
+                    break;
+                }
+            }
+            if !timer.is_repeating() && timer_has_run_out(timer) {
+                timer.control.is_finished.store(true, Ordering::Release);
+            }
+        }
+        timers.retain(|timer| {
+            !timer.control.is_cancelled.load(Ordering::Acquire)
+                && !timer.control.is_finished.load(Ordering::Acquire)
+        });
+        timers.append(&mut self.timers.timers);
+        self.timers.timers = timers;
+    }
+}
+
+fn timer_has_run_out<D>(timer: &Timer<D>) -> bool {
+    match timer.countdown {
+        Countdown::Time(remaining) => remaining.is_zero(),
+        Countdown::Frames(frames) => frames == 0,
+    }
+}
+
+#[cfg(test)]
+mod timer_tests {
+    use std::time::Duration;
+
+    use crate::prelude::*;
+
+    fn tick(context: &mut Context<Vec<&'static str>>, milliseconds: u64) {
+        context
+            .clock_mut()
+            .tick_by(Duration::from_millis(milliseconds));
+        context.update_timers();
+    }
+
+    #[test]
+    fn should_call_after_callbacks_once() {
+        let (_event_loop, mut context) = crate::init(Vec::new());
+        let timer = context.after(Duration::from_millis(100), |context| {
+            context.data.push("After")
+        });
+
+        tick(&mut context, 50);
+        assert!(context.data.is_empty());
+        tick(&mut context, 50);
+        tick(&mut context, 100);
+
+        assert_eq!(context.data, vec!["After"]);
+        assert!(timer.is_finished());
+    }
+
+    #[test]
+    fn should_call_every_callbacks_repeatedly() {
+        let (_event_loop, mut context) = crate::init(Vec::new());
+        let timer = context.every(Duration::from_millis(10), |context| {
+            context.data.push("Every")
+        });
+
+        tick(&mut context, 10);
+        tick(&mut context, 25);
+        assert_eq!(context.data.len(), 3);
+
+        timer.cancel();
+        tick(&mut context, 100);
+        assert_eq!(context.data.len(), 3);
+        assert!(timer.is_finished());
+    }
+
+    #[test]
+    fn should_call_after_frames_callbacks_after_n_frames() {
+        let (_event_loop, mut context) = crate::init(Vec::new());
+        context.after_frames(3, |context| context.data.push("Frames"));
+
+        tick(&mut context, 0);
+        tick(&mut context, 0);
+        assert!(context.data.is_empty());
+        tick(&mut context, 0);
+
+        assert_eq!(context.data, vec!["Frames"]);
+    }
+
+    #[test]
+    fn should_not_count_down_while_game_time_is_paused() {
+        let (_event_loop, mut context) = crate::init(Vec::new());
+        context.after(Duration::from_millis(10), |context| {
+            context.data.push("After")
+        });
+        context.after_frames(1, |context| context.data.push("Frames"));
+
+        context.clock_mut().pause();
+        tick(&mut context, 100);
+        assert!(context.data.is_empty());
+
+        context.clock_mut().resume();
+        tick(&mut context, 10);
+        assert_eq!(context.data, vec!["After", "Frames"]);
+    }
+
+    #[test]
+    fn should_respect_the_time_scale() {
+        let (_event_loop, mut context) = crate::init(Vec::new());
+        context.after(Duration::from_millis(10), |context| {
+            context.data.push("After")
+        });
+        context.clock_mut().set_time_scale(0.5);
+
+        tick(&mut context, 10);
+        assert!(context.data.is_empty());
+        tick(&mut context, 10);
+
+        assert_eq!(context.data, vec!["After"]);
+    }
+
+    #[test]
+    fn should_pause_resume_and_reset_timers() {
+        let (_event_loop, mut context) = crate::init(Vec::new());
+        let timer = context.after(Duration::from_millis(10), |context| {
+            context.data.push("After")
+        });
+
+        timer.pause();
+        tick(&mut context, 100);
+        assert!(timer.is_paused());
+        assert!(context.data.is_empty());
+
+        timer.resume();
+        tick(&mut context, 5);
+        timer.reset();
+        tick(&mut context, 5);
+        assert!(context.data.is_empty());
+        tick(&mut context, 5);
+
+        assert_eq!(context.data, vec!["After"]);
+    }
+
+    #[test]
+    fn should_allow_callbacks_to_create_new_timers() {
+        let (_event_loop, mut context) = crate::init(Vec::new());
+        context.after_frames(1, |context| {
+            context.data.push("First");
+            context.after_frames(1, |context| context.data.push("Second"));
+        });
+
+        tick(&mut context, 0);
+        tick(&mut context, 0);
+
+        assert_eq!(context.data, vec!["First", "Second"]);
+    }
+
+    #[test]
+    #[ntest::timeout(1000)]
+    fn should_be_driven_by_the_event_loop() {
+        let (event_loop, mut context) = crate::init(());
+        context.after_frames(3, |context| context.quit());
+
+        event_loop.run(context, |_event, _context, _control_flow| ());
+    }
+}
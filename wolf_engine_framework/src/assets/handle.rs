@@ -0,0 +1,74 @@
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+
+use wolf_engine_core::prelude::*;
+
+/// The shared part of a [`Handle`], which tells the asset server when the last handle drops.
+pub(crate) struct HandleInner {
+    id: AssetId,
+    drop_sender: Sender<AssetId>,
+}
+
+impl HandleInner {
+    pub fn new(id: AssetId, drop_sender: Sender<AssetId>) -> Self {
+        Self { id, drop_sender }
+    }
+}
+
+impl Drop for HandleInner {
+    fn drop(&mut self) {
+        self.drop_sender.send(self.id).ok();
+    }
+}
+
+/// A reference-counted handle to an asset of type `T`.
+///
+/// Handles are returned by [`AssetServer::load()`](crate::assets::AssetServer::load()), and
+/// are cheap to clone.  The asset is unloaded once every handle to it has been dropped.
+pub struct Handle<T> {
+    inner: Arc<HandleInner>,
+    _asset: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    pub(crate) fn new(inner: Arc<HandleInner>) -> Self {
+        Self {
+            inner,
+            _asset: PhantomData,
+        }
+    }
+
+    /// Returns the id of the asset.
+    pub fn id(&self) -> AssetId {
+        self.inner.id
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone())
+    }
+}
+
+impl<T> Debug for Handle<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Handle").field(&self.id()).finish()
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
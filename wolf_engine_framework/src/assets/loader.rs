@@ -0,0 +1,153 @@
+use std::any::{type_name, Any, TypeId};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// Indicates why an asset could not be loaded.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AssetError {
+    /// No [`AssetLoader`] is registered for the file's extension.
+    NoLoader {
+        /// The path of the asset.
+        path: PathBuf,
+    },
+    /// The loader registered for the file's extension loads a different type of asset.
+    WrongType {
+        /// The path of the asset.
+        path: PathBuf,
+        /// The type of asset which was requested.
+        expected: &'static str,
+        /// The type of asset the loader produces.
+        found: &'static str,
+    },
+    /// The file could not be read.
+    Io {
+        /// The path of the asset.
+        path: PathBuf,
+        /// A description of the error.
+        message: String,
+    },
+    /// The loader failed to load the file, or panicked.
+    Loader {
+        /// The path of the asset.
+        path: PathBuf,
+        /// A description of the error.
+        message: String,
+    },
+    /// The load job was cancelled, such as when the engine is shutting down.
+    Cancelled {
+        /// The path of the asset.
+        path: PathBuf,
+    },
+}
+
+impl AssetError {
+    /// Returns the path of the asset which failed to load.
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::NoLoader { path }
+            | Self::WrongType { path, .. }
+            | Self::Io { path, .. }
+            | Self::Loader { path, .. }
+            | Self::Cancelled { path } => path,
+        }
+    }
+}
+
+impl Display for AssetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoLoader { path } => {
+                write!(f, "No asset loader is registered for {:?}", path)
+            }
+            Self::WrongType {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{:?} was requested as `{}`, but its loader produces `{}`",
+                path, expected, found
+            ),
+            Self::Io { path, message } => write!(f, "Failed to read {:?}: {}", path, message),
+            Self::Loader { path, message } => {
+                write!(f, "Failed to load {:?}: {}", path, message)
+            }
+            Self::Cancelled { path } => write!(f, "Loading {:?} was cancelled", path),
+        }
+    }
+}
+
+impl Error for AssetError {}
+
+/// Loads a type of asset from the contents of a file.
+///
+/// Loaders are registered with an [`AssetServer`](crate::assets::AssetServer), which uses them
+/// to load files with any of their [extensions](AssetLoader::extensions()).  Loading is done on
+/// the engine's [`JobPool`](wolf_engine_core::JobPool), so loaders must be thread-safe.
+///
+/// # Examples
+///
+/// ```
+/// # use std::path::Path;
+/// use wolf_engine_framework::assets::*;
+///
+/// struct TextLoader;
+///
+/// impl AssetLoader for TextLoader {
+///     type Asset = String;
+///
+///     fn extensions(&self) -> &[&'static str] {
+///         &["txt"]
+///     }
+///
+///     fn load(&self, bytes: &[u8], _path: &Path) -> Result<String, String> {
+///         String::from_utf8(bytes.to_vec()).map_err(|error| error.to_string())
+///     }
+/// }
+/// ```
+pub trait AssetLoader: Send + Sync + 'static {
+    /// The type of asset produced by the loader.
+    type Asset: Send + Sync + 'static;
+
+    /// Returns the file extensions handled by the loader, without the leading `.`.
+    fn extensions(&self) -> &[&'static str];
+
+    /// Loads an asset from the contents of a file.
+    ///
+    /// `path` is the path the asset was requested with, relative to the asset server's root.
+    fn load(&self, bytes: &[u8], path: &Path) -> Result<Self::Asset, String>;
+}
+
+pub(crate) type LoadedAsset = Box<dyn Any + Send + Sync>;
+
+/// A type-erased [`AssetLoader`].
+pub(crate) trait ErasedLoader: Send + Sync {
+    fn extensions(&self) -> &[&'static str];
+    fn asset_type(&self) -> TypeId;
+    fn asset_type_name(&self) -> &'static str;
+    fn load(&self, bytes: &[u8], path: &Path) -> Result<LoadedAsset, AssetError>;
+}
+
+impl<L: AssetLoader> ErasedLoader for L {
+    fn extensions(&self) -> &[&'static str] {
+        AssetLoader::extensions(self)
+    }
+
+    fn asset_type(&self) -> TypeId {
+        TypeId::of::<L::Asset>()
+    }
+
+    fn asset_type_name(&self) -> &'static str {
+        type_name::<L::Asset>()
+    }
+
+    fn load(&self, bytes: &[u8], path: &Path) -> Result<LoadedAsset, AssetError> {
+        AssetLoader::load(self, bytes, path)
+            .map(|asset| Box::new(asset) as LoadedAsset)
+            .map_err(|message| AssetError::Loader {
+                path: path.to_path_buf(),
+                message,
+            })
+    }
+}
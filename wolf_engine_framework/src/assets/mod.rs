@@ -0,0 +1,225 @@
+//! Provides asset loading, with typed, reference-counted, handles.
+//!
+//! An [`AssetServer`] loads files in the background, using the [`AssetLoader`] registered for
+//! each file's extension.  Loading a file returns a [`Handle`] right away, and an
+//! [`AssetEvent`] is sent once the asset has loaded, or failed to load.  Assets are unloaded
+//! once their last handle is dropped.
+//!
+//! Assets can be hot-reloaded: when given a [`FileWatcher`], the server reloads assets when
+//! their files change, and sends an [`AssetEvent::Reloaded`] event.
+//!
+//! The [`AssetPlugin`] adds an asset server to the [`Context`] as a
+//! [resource](Context::resource()).
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::path::Path;
+//! use wolf_engine_core::prelude::*;
+//! use wolf_engine_framework::*;
+//! use wolf_engine_framework::assets::*;
+//!
+//! struct TextLoader;
+//!
+//! impl AssetLoader for TextLoader {
+//!     type Asset = String;
+//!
+//!     fn extensions(&self) -> &[&'static str] {
+//!         &["txt"]
+//!     }
+//!
+//!     fn load(&self, bytes: &[u8], _path: &Path) -> Result<String, String> {
+//!         String::from_utf8(bytes.to_vec()).map_err(|error| error.to_string())
+//!     }
+//! }
+//!
+//! # let root = std::env::temp_dir().join("wolf_engine_asset_plugin_example");
+//! # std::fs::create_dir_all(&root).unwrap();
+//! # std::fs::write(root.join("greeting.txt"), "Hello, World!").unwrap();
+//! let framework = FrameworkBuilder::new(None)
+//!     .with_plugin(AssetPlugin::new(root).with_loader(TextLoader))
+//!     .build()
+//!     .unwrap();
+//!
+//! framework.run(|event, context, _control_flow| match event {
+//!     Event::EventsCleared if context.data.is_none() => {
+//!         let asset_server = context.resource_mut::<AssetServer>().unwrap();
+//!         context.data = Some(asset_server.load::<String>("greeting.txt"));
+//!     }
+//!     Event::Asset(AssetEvent::Loaded(_)) => {
+//!         let greeting = context.data.as_ref().unwrap();
+//!         let asset_server = context.resource::<AssetServer>().unwrap();
+//!         assert_eq!(asset_server.get(greeting).unwrap(), "Hello, World!");
+//!         context.quit();
+//!     }
+//!     _ => (),
+//! });
+//! ```
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use wolf_engine_core::prelude::*;
+
+use crate::*;
+
+mod handle;
+pub use handle::Handle;
+mod loader;
+pub use loader::{AssetError, AssetLoader};
+mod server;
+pub use server::*;
+mod watcher;
+pub use watcher::*;
+
+pub use wolf_engine_core::events::{AssetEvent, AssetId};
+
+/// Adds an [`AssetServer`] to the [`Context`] as a [resource](Context::resource()).
+///
+/// The server is added when the engine starts, and is given every event, so load results are
+/// collected, and dropped assets are unloaded, automatically.  If hot-reloading is enabled,
+/// changed files are checked at the start of every frame.
+pub struct AssetPlugin {
+    root: PathBuf,
+    loaders: Vec<Arc<dyn loader::ErasedLoader>>,
+    file_watcher: Option<FileWatcher>,
+}
+
+impl AssetPlugin {
+    /// Creates a plugin for an asset server which loads files relative to `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            loaders: Vec::new(),
+            file_watcher: None,
+        }
+    }
+
+    /// Registers an [`AssetLoader`] with the asset server.
+    pub fn with_loader<L: AssetLoader>(mut self, loader: L) -> Self {
+        self.loaders.push(Arc::new(loader));
+        self
+    }
+
+    /// Enables hot-reloading, using the provided [`FileWatcher`].
+    ///
+    /// See [`AssetServer::with_file_watcher()`].
+    pub fn with_file_watcher(mut self, file_watcher: FileWatcher) -> Self {
+        self.file_watcher = Some(file_watcher);
+        self
+    }
+}
+
+impl<D: 'static> Plugin<D> for AssetPlugin {
+    fn name(&self) -> &'static str {
+        "assets"
+    }
+
+    fn build(&mut self, builder: &mut PluginBuilder<D>) {
+        builder.add_subsystem(AssetSubsystem {
+            root: std::mem::take(&mut self.root),
+            loaders: std::mem::take(&mut self.loaders),
+            file_watcher: self.file_watcher.take(),
+        });
+    }
+}
+
+struct AssetSubsystem {
+    root: PathBuf,
+    loaders: Vec<Arc<dyn loader::ErasedLoader>>,
+    file_watcher: Option<FileWatcher>,
+}
+
+impl<D> Subsystem<D> for AssetSubsystem {
+    fn name(&self) -> &'static str {
+        "assets"
+    }
+
+    fn on_start(&mut self, context: &mut Context<D>) {
+        let mut asset_server = AssetServer::new(std::mem::take(&mut self.root), context);
+        for loader in self.loaders.drain(..) {
+            asset_server.add_erased_loader(loader);
+        }
+        if let Some(file_watcher) = self.file_watcher.take() {
+            asset_server = asset_server.with_file_watcher(file_watcher);
+        }
+        context.insert_resource(asset_server);
+    }
+
+    fn on_event(&mut self, event: Event, context: &mut Context<D>) {
+        if let Ok(asset_server) = context.resource_mut::<AssetServer>() {
+            asset_server.handle_event(event);
+        }
+    }
+
+    fn on_frame(&mut self, context: &mut Context<D>) {
+        if let Ok(asset_server) = context.resource_mut::<AssetServer>() {
+            asset_server.reload_changed_files();
+        }
+    }
+}
+
+#[cfg(test)]
+mod asset_plugin_tests {
+    use std::path::Path;
+    use std::time::Duration;
+
+    use ntest::timeout;
+
+    use super::*;
+
+    struct TextLoader;
+
+    impl AssetLoader for TextLoader {
+        type Asset = String;
+
+        fn extensions(&self) -> &[&'static str] {
+            &["txt"]
+        }
+
+        fn load(&self, bytes: &[u8], _path: &Path) -> Result<String, String> {
+            String::from_utf8(bytes.to_vec()).map_err(|error| error.to_string())
+        }
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_hot_reload_assets_with_frame_phases_enabled() {
+        let root = std::env::temp_dir().join(format!(
+            "wolf_engine_asset_plugin_tests_{}_frame_phases",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("hello.txt"), "Hello!").unwrap();
+        let file_watcher = FileWatcher::new()
+            .with_poll_interval(Duration::ZERO)
+            .with_debounce(Duration::ZERO);
+        let engine_builder = EngineBuilder::new(None).with_frame_phases([FramePhase::Update]);
+        let framework = FrameworkBuilder::from_engine_builder(engine_builder)
+            .with_plugin(
+                AssetPlugin::new(root.clone())
+                    .with_loader(TextLoader)
+                    .with_file_watcher(file_watcher),
+            )
+            .build()
+            .unwrap();
+
+        framework.run(move |event, context, _control_flow| match event {
+            Event::EventsCleared => panic!("Frame phases should replace EventsCleared"),
+            Event::FramePhase(FramePhase::Update) if context.data.is_none() => {
+                let asset_server = context.resource_mut::<AssetServer>().unwrap();
+                context.data = Some(asset_server.load::<String>("hello.txt"));
+            }
+            Event::Asset(AssetEvent::Loaded(_)) => {
+                std::fs::write(root.join("hello.txt"), "Hello, again!").unwrap();
+            }
+            Event::Asset(AssetEvent::Reloaded(_)) => {
+                let handle = context.data.as_ref().unwrap();
+                let asset_server = context.resource::<AssetServer>().unwrap();
+                assert_eq!(asset_server.get(handle).unwrap(), "Hello, again!");
+                context.quit();
+            }
+            _ => (),
+        });
+    }
+}
@@ -0,0 +1,689 @@
+use std::any::{type_name, TypeId};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Weak};
+use std::time::Instant;
+
+use wolf_engine_core::prelude::*;
+
+use crate::assets::handle::HandleInner;
+use crate::assets::loader::{ErasedLoader, LoadedAsset};
+use crate::assets::*;
+
+type LoadResult = Result<LoadedAsset, AssetError>;
+
+/// The load state of an asset.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LoadState {
+    /// The asset is still being loaded.
+    Loading,
+    /// The asset has loaded, and is ready to use.
+    Loaded,
+    /// The asset could not be loaded.
+    Failed(AssetError),
+}
+
+enum AssetData {
+    Loading(JobId),
+    Loaded(LoadedAsset),
+    Failed(AssetError),
+}
+
+struct AssetEntry {
+    path: PathBuf,
+    type_id: TypeId,
+    handle: Weak<HandleInner>,
+    data: AssetData,
+    reload_job: Option<JobId>,
+}
+
+/// Loads, and stores, assets.
+///
+/// Assets are requested with [`AssetServer::load()`], which returns a [`Handle`] right away,
+/// while the file is loaded in the background by the [`AssetLoader`] registered for its
+/// extension.  Once the asset has loaded, or failed to load, an [`AssetEvent`] with its
+/// [`AssetId`] is sent through the engine's event queue.
+///
+/// Loading the same path again, while a handle to it is still alive, returns another handle to
+/// the same asset.  Once every handle to an asset has been dropped, the asset is unloaded.
+///
+/// The server must be given every event using [`AssetServer::handle_event()`], so it can collect
+/// finished load jobs, and unload dropped assets.  The [`AssetPlugin`] adds a server as a
+/// [resource](Context::resource()), and does this automatically.
+///
+/// # Hot-Reloading
+///
+/// A server with a [`FileWatcher`] reloads assets when their files change, which is checked by
+/// [`AssetServer::reload_changed_files()`].  The new asset replaces the old one behind the
+/// existing handles, and an [`AssetEvent::Reloaded`] event is sent.  If reloading fails, the old
+/// asset is kept, and the error is logged.
+///
+/// # Examples
+///
+/// ```
+/// # use std::path::Path;
+/// use wolf_engine_core::prelude::*;
+/// use wolf_engine_framework::assets::*;
+///
+/// struct TextLoader;
+///
+/// impl AssetLoader for TextLoader {
+///     type Asset = String;
+///
+///     fn extensions(&self) -> &[&'static str] {
+///         &["txt"]
+///     }
+///
+///     fn load(&self, bytes: &[u8], _path: &Path) -> Result<String, String> {
+///         String::from_utf8(bytes.to_vec()).map_err(|error| error.to_string())
+///     }
+/// }
+///
+/// # let root = std::env::temp_dir().join("wolf_engine_asset_server_example");
+/// # std::fs::create_dir_all(&root).unwrap();
+/// # std::fs::write(root.join("greeting.txt"), "Hello, World!").unwrap();
+/// let (mut event_loop, mut context) = wolf_engine_core::init(());
+/// let mut asset_server = AssetServer::new(root, &context).with_loader(TextLoader);
+///
+/// let greeting = asset_server.load::<String>("greeting.txt");
+///
+/// while let Some(event) = event_loop.next_event() {
+///     asset_server.handle_event(event);
+///     if event == Event::Asset(AssetEvent::Loaded(greeting.id())) {
+///         assert_eq!(asset_server.get(&greeting).unwrap(), "Hello, World!");
+///         context.quit();
+///     }
+/// }
+/// ```
+pub struct AssetServer {
+    root: PathBuf,
+    loaders: HashMap<String, Arc<dyn ErasedLoader>>,
+    assets: HashMap<AssetId, AssetEntry>,
+    paths: HashMap<PathBuf, AssetId>,
+    jobs: HashMap<JobId, (AssetId, JobHandle<LoadResult>)>,
+    next_id: u64,
+    job_pool: JobPool,
+    event_sender: Arc<dyn EventSender<Event>>,
+    drop_sender: Sender<AssetId>,
+    drop_receiver: Receiver<AssetId>,
+    file_watcher: Option<FileWatcher>,
+}
+
+impl AssetServer {
+    /// Creates an asset server, which loads files relative to `root` using the context's
+    /// [`JobPool`].
+    pub fn new<D>(root: impl Into<PathBuf>, context: &Context<D>) -> Self {
+        let (drop_sender, drop_receiver) = mpsc::channel();
+        Self {
+            root: root.into(),
+            loaders: HashMap::new(),
+            assets: HashMap::new(),
+            paths: HashMap::new(),
+            jobs: HashMap::new(),
+            next_id: 0,
+            job_pool: context.job_pool().clone(),
+            event_sender: context.event_sender(),
+            drop_sender,
+            drop_receiver,
+            file_watcher: None,
+        }
+    }
+
+    /// Registers an [`AssetLoader`] for each of its extensions.
+    ///
+    /// Replaces any loader previously registered for the same extensions.
+    pub fn with_loader<L: AssetLoader>(mut self, loader: L) -> Self {
+        self.add_loader(loader);
+        self
+    }
+
+    /// Registers an [`AssetLoader`] for each of its extensions.
+    ///
+    /// Replaces any loader previously registered for the same extensions.
+    pub fn add_loader<L: AssetLoader>(&mut self, loader: L) {
+        self.add_erased_loader(Arc::new(loader));
+    }
+
+    pub(crate) fn add_erased_loader(&mut self, loader: Arc<dyn ErasedLoader>) {
+        for extension in loader.extensions() {
+            self.loaders
+                .insert(extension.to_ascii_lowercase(), loader.clone());
+        }
+    }
+
+    /// Enables hot-reloading, using the provided [`FileWatcher`] to watch loaded assets for
+    /// changes.
+    ///
+    /// Files are checked when [`AssetServer::reload_changed_files()`] is called.
+    pub fn with_file_watcher(mut self, file_watcher: FileWatcher) -> Self {
+        self.file_watcher = Some(file_watcher);
+        self
+    }
+
+    /// Returns the directory assets are loaded from.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Starts loading the asset at `path`, relative to the root, and returns a handle to it.
+    ///
+    /// If the asset is already loaded, or loading, a handle to the existing asset is returned
+    /// instead.  Problems are reported through the asset's [`LoadState`].
+    pub fn load<T: Send + Sync + 'static>(&mut self, path: impl AsRef<Path>) -> Handle<T> {
+        let path = path.as_ref().to_path_buf();
+        let type_id = TypeId::of::<T>();
+        let existing_handle = self
+            .paths
+            .get(&path)
+            .and_then(|id| self.assets.get(id))
+            .filter(|entry| entry.type_id == type_id)
+            .and_then(|entry| entry.handle.upgrade());
+        if let Some(handle) = existing_handle {
+            return Handle::new(handle);
+        }
+
+        let id = AssetId::new(self.next_id);
+        self.next_id += 1;
+        let handle = Arc::new(HandleInner::new(id, self.drop_sender.clone()));
+        let data = match self.loader_for(&path) {
+            None => AssetData::Failed(AssetError::NoLoader { path: path.clone() }),
+            Some(loader) if loader.asset_type() != type_id => {
+                AssetData::Failed(AssetError::WrongType {
+                    path: path.clone(),
+                    expected: type_name::<T>(),
+                    found: loader.asset_type_name(),
+                })
+            }
+            Some(loader) => {
+                self.paths.insert(path.clone(), id);
+                if let Some(file_watcher) = self.file_watcher.as_mut() {
+                    file_watcher.watch(self.root.join(&path));
+                }
+                AssetData::Loading(self.spawn_load_job(id, loader, &path))
+            }
+        };
+        if let AssetData::Failed(error) = &data {
+            log::warn!("{}", error);
+            self.send_asset_event(AssetEvent::Failed(id));
+        }
+        self.assets.insert(
+            id,
+            AssetEntry {
+                path,
+                type_id,
+                handle: Arc::downgrade(&handle),
+                data,
+                reload_job: None,
+            },
+        );
+        Handle::new(handle)
+    }
+
+    /// Returns the asset, if it has loaded.
+    pub fn get<T: 'static>(&self, handle: &Handle<T>) -> Option<&T> {
+        match &self.assets.get(&handle.id())?.data {
+            AssetData::Loaded(asset) => asset.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the asset mutably, if it has loaded.
+    pub fn get_mut<T: 'static>(&mut self, handle: &Handle<T>) -> Option<&mut T> {
+        match &mut self.assets.get_mut(&handle.id())?.data {
+            AssetData::Loaded(asset) => asset.downcast_mut(),
+            _ => None,
+        }
+    }
+
+    /// Returns the load state of an asset, or `None` if the asset isn't tracked by this server.
+    pub fn load_state(&self, id: AssetId) -> Option<LoadState> {
+        let state = match &self.assets.get(&id)?.data {
+            AssetData::Loading(_) => LoadState::Loading,
+            AssetData::Loaded(_) => LoadState::Loaded,
+            AssetData::Failed(error) => LoadState::Failed(error.clone()),
+        };
+        Some(state)
+    }
+
+    /// Returns the path an asset was loaded from.
+    pub fn path(&self, id: AssetId) -> Option<&Path> {
+        self.assets.get(&id).map(|entry| entry.path.as_path())
+    }
+
+    /// Returns the number of assets tracked by the server, including assets which are still
+    /// loading, or failed to load.
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    /// Returns `true` if the server isn't tracking any assets.
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Unloads assets whose handles have all been dropped, and collects the results of
+    /// finished load jobs.
+    pub fn handle_event(&mut self, event: Event) {
+        while let Ok(id) = self.drop_receiver.try_recv() {
+            self.unload(id);
+        }
+        match event {
+            Event::Job(JobEvent::Finished(job_id)) => {
+                if let Some((id, job)) = self.jobs.remove(&job_id) {
+                    let result = job
+                        .take_result()
+                        .expect("Finished load jobs should have a result");
+                    self.finish_job(id, job_id, result);
+                }
+            }
+            Event::Job(JobEvent::Failed(job_id)) => {
+                if let Some((id, _job)) = self.jobs.remove(&job_id) {
+                    self.finish_job_with_error(id, job_id, |path| AssetError::Loader {
+                        path,
+                        message: "The loader panicked".to_string(),
+                    });
+                }
+            }
+            Event::Job(JobEvent::Cancelled(job_id)) => {
+                if let Some((id, _job)) = self.jobs.remove(&job_id) {
+                    self.finish_job_with_error(id, job_id, |path| AssetError::Cancelled { path });
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Reloads assets whose files have changed, if hot-reloading is enabled.
+    ///
+    /// This should be called once per frame.  The [`AssetPlugin`] calls it at the start of
+    /// every frame, so it works with, or without, [frame phases](EventLoop::frame_phases()).
+    pub fn reload_changed_files(&mut self) {
+        let Some(file_watcher) = self.file_watcher.as_mut() else {
+            return;
+        };
+        for full_path in file_watcher.poll(Instant::now()) {
+            let Ok(path) = full_path.strip_prefix(&self.root) else {
+                continue;
+            };
+            if let Some(&id) = self.paths.get(path) {
+                self.reload(id);
+            }
+        }
+    }
+
+    /// Starts reloading an asset, keeping the current asset available until the new one is
+    /// ready.
+    fn reload(&mut self, id: AssetId) {
+        let Some(entry) = self.assets.get(&id) else {
+            return;
+        };
+        let Some(loader) = self.loader_for(&entry.path) else {
+            return;
+        };
+        log::debug!("Reloading {:?}", entry.path);
+        let path = entry.path.clone();
+        let job_id = self.spawn_load_job(id, loader, &path);
+        let entry = self.assets.get_mut(&id).expect("The asset should exist");
+        let old_job_id = match &mut entry.data {
+            AssetData::Loading(loading_job_id) => Some(std::mem::replace(loading_job_id, job_id)),
+            _ => entry.reload_job.replace(job_id),
+        };
+        if let Some((_, old_job)) = old_job_id.and_then(|old_job_id| self.jobs.remove(&old_job_id))
+        {
+            old_job.cancel();
+        }
+    }
+
+    fn loader_for(&self, path: &Path) -> Option<Arc<dyn ErasedLoader>> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        self.loaders.get(&extension).cloned()
+    }
+
+    fn spawn_load_job(&mut self, id: AssetId, loader: Arc<dyn ErasedLoader>, path: &Path) -> JobId {
+        let path = path.to_path_buf();
+        let full_path = self.root.join(&path);
+        let job = self.job_pool.spawn(move |_job| -> LoadResult {
+            let bytes = std::fs::read(full_path).map_err(|error| AssetError::Io {
+                path: path.clone(),
+                message: error.to_string(),
+            })?;
+            loader.load(&bytes, &path)
+        });
+        let job_id = job.id();
+        self.jobs.insert(job_id, (id, job));
+        job_id
+    }
+
+    fn finish_job(&mut self, id: AssetId, job_id: JobId, result: LoadResult) {
+        let Some(entry) = self.assets.get_mut(&id) else {
+            return;
+        };
+        let is_reload = entry.reload_job == Some(job_id);
+        if is_reload {
+            entry.reload_job = None;
+        }
+        let asset_event = match result {
+            Ok(asset) if is_reload => {
+                entry.data = AssetData::Loaded(asset);
+                AssetEvent::Reloaded(id)
+            }
+            Err(error) if is_reload && matches!(entry.data, AssetData::Loaded(_)) => {
+                log::warn!("{}, keeping the previously loaded asset", error);
+                return;
+            }
+            Ok(asset) => {
+                entry.data = AssetData::Loaded(asset);
+                AssetEvent::Loaded(id)
+            }
+            Err(error) => {
+                log::warn!("{}", error);
+                entry.data = AssetData::Failed(error);
+                AssetEvent::Failed(id)
+            }
+        };
+        self.send_asset_event(asset_event);
+    }
+
+    fn finish_job_with_error<F>(&mut self, id: AssetId, job_id: JobId, error: F)
+    where
+        F: FnOnce(PathBuf) -> AssetError,
+    {
+        if let Some(entry) = self.assets.get(&id) {
+            let error = error(entry.path.clone());
+            self.finish_job(id, job_id, Err(error));
+        }
+    }
+
+    fn unload(&mut self, id: AssetId) {
+        if let Some(entry) = self.assets.remove(&id) {
+            let loading_job = match entry.data {
+                AssetData::Loading(job_id) => Some(job_id),
+                _ => None,
+            };
+            for job_id in loading_job.into_iter().chain(entry.reload_job) {
+                if let Some((_, job)) = self.jobs.remove(&job_id) {
+                    job.cancel();
+                }
+            }
+            if self.paths.get(&entry.path) == Some(&id) {
+                self.paths.remove(&entry.path);
+                if let Some(file_watcher) = self.file_watcher.as_mut() {
+                    file_watcher.unwatch(&self.root.join(&entry.path));
+                }
+            }
+        }
+    }
+
+    fn send_asset_event(&self, asset_event: AssetEvent) {
+        self.event_sender.send_event(Event::Asset(asset_event)).ok();
+    }
+}
+
+#[cfg(test)]
+mod asset_server_tests {
+    use std::time::Duration;
+
+    use ntest::timeout;
+    use test_case::test_case;
+
+    use super::*;
+
+    struct TextLoader;
+
+    impl AssetLoader for TextLoader {
+        type Asset = String;
+
+        fn extensions(&self) -> &[&'static str] {
+            &["txt"]
+        }
+
+        fn load(&self, bytes: &[u8], _path: &Path) -> Result<String, String> {
+            match bytes {
+                b"panic" => panic!("The loader was told to panic"),
+                b"" => Err("The file is empty".to_string()),
+                bytes => String::from_utf8(bytes.to_vec()).map_err(|error| error.to_string()),
+            }
+        }
+    }
+
+    fn asset_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "wolf_engine_asset_server_tests_{}_{}",
+            std::process::id(),
+            name
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("hello.txt"), "Hello!").unwrap();
+        std::fs::write(root.join("empty.txt"), "").unwrap();
+        std::fs::write(root.join("panic.txt"), "panic").unwrap();
+        std::fs::write(root.join("data.bin"), "").unwrap();
+        root
+    }
+
+    fn init(name: &str) -> (EventLoop, Context<()>, AssetServer) {
+        let (event_loop, context) = wolf_engine_core::init(());
+        let asset_server = AssetServer::new(asset_root(name), &context).with_loader(TextLoader);
+        (event_loop, context, asset_server)
+    }
+
+    /// Handles events until an asset event arrives.
+    fn next_asset_event(event_loop: &mut EventLoop, asset_server: &mut AssetServer) -> AssetEvent {
+        loop {
+            let event = event_loop
+                .next_event()
+                .expect("The event loop should not stop");
+            asset_server.handle_event(event);
+            asset_server.reload_changed_files();
+            if let Event::Asset(asset_event) = event {
+                return asset_event;
+            }
+        }
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_load_assets_in_the_background() {
+        let (mut event_loop, _context, mut asset_server) = init("load");
+
+        let handle = asset_server.load::<String>("hello.txt");
+        assert_eq!(
+            asset_server.load_state(handle.id()),
+            Some(LoadState::Loading)
+        );
+        assert_eq!(asset_server.get(&handle), None);
+
+        let asset_event = next_asset_event(&mut event_loop, &mut asset_server);
+
+        assert_eq!(asset_event, AssetEvent::Loaded(handle.id()));
+        assert_eq!(
+            asset_server.load_state(handle.id()),
+            Some(LoadState::Loaded)
+        );
+        assert_eq!(asset_server.get(&handle).unwrap(), "Hello!");
+        asset_server.get_mut(&handle).unwrap().push('!');
+        assert_eq!(asset_server.get(&handle).unwrap(), "Hello!!");
+        assert_eq!(asset_server.path(handle.id()), Some(Path::new("hello.txt")));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_deduplicate_loads_and_unload_dropped_assets() {
+        let (mut event_loop, _context, mut asset_server) = init("dedup");
+        let handle = asset_server.load::<String>("hello.txt");
+        let duplicate = asset_server.load::<String>("hello.txt");
+        next_asset_event(&mut event_loop, &mut asset_server);
+
+        assert_eq!(handle, duplicate);
+        assert_eq!(asset_server.len(), 1);
+
+        let id = handle.id();
+        drop(handle);
+        asset_server.handle_event(Event::EventsCleared);
+        assert_eq!(asset_server.load_state(id), Some(LoadState::Loaded));
+
+        drop(duplicate);
+        asset_server.handle_event(Event::EventsCleared);
+        assert_eq!(asset_server.load_state(id), None);
+        assert!(asset_server.is_empty());
+
+        let reloaded = asset_server.load::<String>("hello.txt");
+        assert_ne!(reloaded.id(), id);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_discard_the_results_of_assets_unloaded_while_loading() {
+        let (mut event_loop, _context, mut asset_server) = init("unload_while_loading");
+        let handle = asset_server.load::<String>("hello.txt");
+
+        drop(handle);
+        while let Some(event) = event_loop.next_event() {
+            asset_server.handle_event(event);
+            if let Event::Job(_) = event {
+                break;
+            }
+        }
+
+        assert!(asset_server.is_empty());
+        assert_eq!(asset_server.job_pool.pending_results(), 0);
+    }
+
+    #[test_case("missing.txt", AssetError::Io {
+        path: "missing.txt".into(),
+        message: String::new(),
+    }; "missing file")]
+    #[test_case("empty.txt", AssetError::Loader {
+        path: "empty.txt".into(),
+        message: "The file is empty".to_string(),
+    }; "loader error")]
+    #[test_case("panic.txt", AssetError::Loader {
+        path: "panic.txt".into(),
+        message: "The loader panicked".to_string(),
+    }; "loader panic")]
+    #[timeout(1000)]
+    fn should_report_load_failures(path: &str, expected_error: AssetError) {
+        let (mut event_loop, _context, mut asset_server) = init("failures");
+        let handle = asset_server.load::<String>(path);
+
+        let asset_event = next_asset_event(&mut event_loop, &mut asset_server);
+
+        assert_eq!(asset_event, AssetEvent::Failed(handle.id()));
+        let Some(LoadState::Failed(error)) = asset_server.load_state(handle.id()) else {
+            panic!("The asset should have failed to load");
+        };
+        match (error, expected_error) {
+            (
+                AssetError::Io { path, .. },
+                AssetError::Io {
+                    path: expected_path,
+                    ..
+                },
+            ) => {
+                assert_eq!(path, expected_path)
+            }
+            (error, expected_error) => assert_eq!(error, expected_error),
+        }
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_fail_immediately_without_a_matching_loader() {
+        let (mut event_loop, _context, mut asset_server) = init("no_loader");
+
+        let data = asset_server.load::<Vec<u8>>("data.bin");
+        let wrong_type = asset_server.load::<Vec<u8>>("hello.txt");
+
+        assert_eq!(
+            asset_server.load_state(data.id()),
+            Some(LoadState::Failed(AssetError::NoLoader {
+                path: "data.bin".into()
+            }))
+        );
+        assert_eq!(
+            asset_server.load_state(wrong_type.id()),
+            Some(LoadState::Failed(AssetError::WrongType {
+                path: "hello.txt".into(),
+                expected: type_name::<Vec<u8>>(),
+                found: type_name::<String>(),
+            }))
+        );
+        assert_eq!(
+            next_asset_event(&mut event_loop, &mut asset_server),
+            AssetEvent::Failed(data.id())
+        );
+        assert_eq!(
+            next_asset_event(&mut event_loop, &mut asset_server),
+            AssetEvent::Failed(wrong_type.id())
+        );
+    }
+
+    fn init_with_hot_reloading(name: &str) -> (EventLoop, Context<()>, AssetServer) {
+        let (event_loop, context, asset_server) = init(name);
+        let file_watcher = FileWatcher::new()
+            .with_poll_interval(Duration::ZERO)
+            .with_debounce(Duration::ZERO);
+        (
+            event_loop,
+            context,
+            asset_server.with_file_watcher(file_watcher),
+        )
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_reload_assets_when_their_files_change() {
+        let (mut event_loop, _context, mut asset_server) = init_with_hot_reloading("reload");
+        let handle = asset_server.load::<String>("hello.txt");
+        next_asset_event(&mut event_loop, &mut asset_server);
+
+        std::fs::write(asset_server.root().join("hello.txt"), "Hello, again!").unwrap();
+        let asset_event = next_asset_event(&mut event_loop, &mut asset_server);
+
+        assert_eq!(asset_event, AssetEvent::Reloaded(handle.id()));
+        assert_eq!(asset_server.get(&handle).unwrap(), "Hello, again!");
+        assert_eq!(asset_server.len(), 1);
+        assert_eq!(asset_server.job_pool.pending_results(), 0);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_keep_the_old_asset_if_reloading_fails() {
+        let (mut event_loop, _context, mut asset_server) = init_with_hot_reloading("reload_fail");
+        let handle = asset_server.load::<String>("hello.txt");
+        next_asset_event(&mut event_loop, &mut asset_server);
+
+        std::fs::write(asset_server.root().join("hello.txt"), "").unwrap();
+        for _ in 0..100 {
+            let event = event_loop.next_event().unwrap();
+            asset_server.handle_event(event);
+            asset_server.reload_changed_files();
+            assert!(!matches!(event, Event::Asset(_)));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(
+            asset_server.load_state(handle.id()),
+            Some(LoadState::Loaded)
+        );
+        assert_eq!(asset_server.get(&handle).unwrap(), "Hello!");
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_not_reload_assets_without_a_file_watcher() {
+        let (mut event_loop, _context, mut asset_server) = init("no_reload");
+        let handle = asset_server.load::<String>("hello.txt");
+        next_asset_event(&mut event_loop, &mut asset_server);
+
+        std::fs::write(asset_server.root().join("hello.txt"), "Hello, again!").unwrap();
+        for _ in 0..10 {
+            let event = event_loop.next_event().unwrap();
+            asset_server.handle_event(event);
+            asset_server.reload_changed_files();
+            assert!(!matches!(event, Event::Asset(_)));
+        }
+
+        assert_eq!(asset_server.get(&handle).unwrap(), "Hello!");
+    }
+}
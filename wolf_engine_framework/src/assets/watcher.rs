@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// The last known state of a file, used to detect changes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: Option<u64>,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Self {
+        match std::fs::metadata(path) {
+            Ok(metadata) => Self {
+                modified: metadata.modified().ok(),
+                len: Some(metadata.len()),
+            },
+            Err(_) => Self {
+                modified: None,
+                len: None,
+            },
+        }
+    }
+}
+
+struct WatchedFile {
+    stamp: FileStamp,
+    changed_at: Option<Instant>,
+}
+
+/// Watches files for changes, by polling their modification time and size.
+///
+/// Changes are debounced: a file is only reported once it has stopped changing for the
+/// [debounce time](FileWatcher::with_debounce()), so an editor saving a file several times in a
+/// row only causes a single reload.
+///
+/// An [`AssetServer`](crate::assets::AssetServer) with a file watcher reloads its assets when
+/// their files change.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::{Duration, Instant};
+/// use wolf_engine_framework::assets::*;
+///
+/// # let path = std::env::temp_dir().join("wolf_engine_file_watcher_example.txt");
+/// # std::fs::write(&path, "Hello").unwrap();
+/// let mut watcher = FileWatcher::new()
+///     .with_poll_interval(Duration::from_millis(50))
+///     .with_debounce(Duration::from_millis(100));
+/// watcher.watch(&path);
+///
+/// std::fs::write(&path, "Hello, World!").unwrap();
+/// let now = Instant::now();
+///
+/// // The change is seen, but not reported until the file has settled.
+/// assert!(watcher.poll(now).is_empty());
+/// assert_eq!(watcher.poll(now + Duration::from_millis(100)), vec![path]);
+/// ```
+pub struct FileWatcher {
+    poll_interval: Duration,
+    debounce: Duration,
+    last_poll: Option<Instant>,
+    files: HashMap<PathBuf, WatchedFile>,
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileWatcher {
+    /// Creates a file watcher which polls every 250 ms, with a debounce time of 100 ms.
+    pub fn new() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(250),
+            debounce: Duration::from_millis(100),
+            last_poll: None,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Sets the minimum time between checks for changes.
+    ///
+    /// Calls to [`FileWatcher::poll()`] made sooner than this don't check any files.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Sets how long a file must go unchanged, before the change is reported.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Starts watching a file for changes.
+    ///
+    /// Watching a file which is already watched does nothing.
+    pub fn watch(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        let stamp = FileStamp::read(&path);
+        self.files.entry(path).or_insert(WatchedFile {
+            stamp,
+            changed_at: None,
+        });
+    }
+
+    /// Stops watching a file for changes.
+    pub fn unwatch(&mut self, path: &Path) {
+        self.files.remove(path);
+    }
+
+    /// Returns `true` if the file is being watched.
+    pub fn is_watching(&self, path: &Path) -> bool {
+        self.files.contains_key(path)
+    }
+
+    /// Checks the watched files for changes, and returns the files which changed, then stayed
+    /// unchanged for the debounce time.
+    pub fn poll(&mut self, now: Instant) -> Vec<PathBuf> {
+        if let Some(last_poll) = self.last_poll {
+            if now.saturating_duration_since(last_poll) < self.poll_interval {
+                return Vec::new();
+            }
+        }
+        self.last_poll = Some(now);
+        let mut changed_files = Vec::new();
+        for (path, file) in self.files.iter_mut() {
+            let stamp = FileStamp::read(path);
+            if stamp != file.stamp {
+                file.stamp = stamp;
+                file.changed_at = Some(now);
+            }
+            if let Some(changed_at) = file.changed_at {
+                if now.saturating_duration_since(changed_at) >= self.debounce {
+                    file.changed_at = None;
+                    changed_files.push(path.clone());
+                }
+            }
+        }
+        changed_files.sort();
+        changed_files
+    }
+}
+
+#[cfg(test)]
+mod file_watcher_tests {
+    use super::*;
+
+    fn watched_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "wolf_engine_file_watcher_tests_{}_{}.txt",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, "0").unwrap();
+        path
+    }
+
+    #[test]
+    fn should_report_changed_files() {
+        let path = watched_file("changes");
+        let mut watcher = FileWatcher::new()
+            .with_poll_interval(Duration::ZERO)
+            .with_debounce(Duration::ZERO);
+        watcher.watch(&path);
+        let now = Instant::now();
+
+        assert!(watcher.poll(now).is_empty());
+        std::fs::write(&path, "01").unwrap();
+
+        assert_eq!(watcher.poll(now), vec![path.clone()]);
+        assert!(watcher.poll(now).is_empty());
+    }
+
+    #[test]
+    fn should_debounce_repeated_changes() {
+        let path = watched_file("debounce");
+        let debounce = Duration::from_millis(100);
+        let mut watcher = FileWatcher::new()
+            .with_poll_interval(Duration::ZERO)
+            .with_debounce(debounce);
+        watcher.watch(&path);
+        let start = Instant::now();
+
+        std::fs::write(&path, "01").unwrap();
+        assert!(watcher.poll(start).is_empty());
+        std::fs::write(&path, "012").unwrap();
+        assert!(watcher.poll(start + debounce / 2).is_empty());
+        assert!(watcher.poll(start + debounce).is_empty());
+
+        assert_eq!(
+            watcher.poll(start + debounce / 2 + debounce),
+            vec![path.clone()]
+        );
+        assert!(watcher.poll(start + debounce * 3).is_empty());
+    }
+
+    #[test]
+    fn should_only_check_files_once_per_poll_interval() {
+        let path = watched_file("interval");
+        let poll_interval = Duration::from_millis(250);
+        let mut watcher = FileWatcher::new()
+            .with_poll_interval(poll_interval)
+            .with_debounce(Duration::ZERO);
+        watcher.watch(&path);
+        let start = Instant::now();
+        assert!(watcher.poll(start).is_empty());
+
+        std::fs::write(&path, "01").unwrap();
+
+        assert!(watcher.poll(start + poll_interval / 2).is_empty());
+        assert_eq!(watcher.poll(start + poll_interval), vec![path.clone()]);
+    }
+
+    #[test]
+    fn should_report_deleted_files_and_stop_watching_unwatched_files() {
+        let path = watched_file("deleted");
+        let mut watcher = FileWatcher::new()
+            .with_poll_interval(Duration::ZERO)
+            .with_debounce(Duration::ZERO);
+        watcher.watch(&path);
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(watcher.poll(Instant::now()), vec![path.clone()]);
+
+        watcher.unwatch(&path);
+        std::fs::write(&path, "0").unwrap();
+        assert!(!watcher.is_watching(&path));
+        assert!(watcher.poll(Instant::now()).is_empty());
+    }
+}
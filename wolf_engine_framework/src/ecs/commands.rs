@@ -0,0 +1,285 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::ecs::*;
+
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+type LocalCommand = Box<dyn FnOnce(&mut World)>;
+
+/// A handle to a [`World`]'s command queue, used to make changes which can't be made right away,
+/// such as spawning entities while the world is being queried.
+///
+/// Handles are created with [`World::commands()`], and are cheap to clone.  Queued commands are
+/// applied, in order, by [`World::apply_commands()`], or [`World::end_frame()`].
+///
+/// Handles can be sent to other threads, such as by [parallel systems](System::parallel()), so
+/// the commands they queue must be [`Send`].  Commands which aren't can be queued with
+/// [`LocalCommands`] instead.
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_framework::ecs::*;
+///
+/// struct Health(i32);
+///
+/// let mut world = World::new();
+/// world.spawn((Health(0),));
+/// world.spawn((Health(10),));
+///
+/// let commands = world.commands();
+/// world.query::<&Health>().for_each(|entity, health| {
+///     if health.0 <= 0 {
+///         commands.despawn(entity);
+///     }
+/// });
+/// assert_eq!(world.len(), 2);
+///
+/// world.apply_commands();
+/// assert_eq!(world.len(), 1);
+/// ```
+#[derive(Clone, Default)]
+pub struct Commands {
+    queue: Arc<Mutex<Vec<Command>>>,
+}
+
+impl Commands {
+    /// Queues an entity to be spawned with the provided components.
+    pub fn spawn<B: Bundle>(&self, bundle: B) {
+        self.add(move |world| {
+            world.spawn(bundle);
+        });
+    }
+
+    /// Queues an entity to be despawned.
+    pub fn despawn(&self, entity: Entity) {
+        self.add(move |world| {
+            world.despawn(entity);
+        });
+    }
+
+    /// Queues a component to be added to an entity.
+    ///
+    /// The component is dropped if the entity no longer exists when the command is applied.
+    pub fn insert<T: Send + 'static>(&self, entity: Entity, component: T) {
+        self.add(move |world| {
+            world.insert(entity, component).ok();
+        });
+    }
+
+    /// Queues a component to be removed from an entity.
+    pub fn remove<T: 'static>(&self, entity: Entity) {
+        self.add(move |world| {
+            world.remove::<T>(entity);
+        });
+    }
+
+    /// Queues a custom command.
+    pub fn add<F: FnOnce(&mut World) + Send + 'static>(&self, command: F) {
+        self.queue().push(Box::new(command));
+    }
+
+    /// Returns the number of queued commands.
+    pub fn len(&self) -> usize {
+        self.queue().len()
+    }
+
+    /// Returns `true` if there are no queued commands.
+    pub fn is_empty(&self) -> bool {
+        self.queue().is_empty()
+    }
+
+    pub(crate) fn take(&self) -> Vec<Command> {
+        std::mem::take(&mut *self.queue())
+    }
+
+    fn queue(&self) -> MutexGuard<'_, Vec<Command>> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A command queue which stays on the thread it was created on, so its commands don't need to
+/// be [`Send`].
+///
+/// Unlike [`Commands`], a local queue isn't owned by a [`World`], so it can be used wherever
+/// the world is accessed from a single thread, such as in
+/// [exclusive systems](System::exclusive()), or by a [`State`] which owns its world.  Queued
+/// commands are applied, in order, by [`LocalCommands::apply()`].
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use wolf_engine_framework::ecs::*;
+///
+/// struct Health(i32);
+///
+/// let mut world = World::new();
+/// world.spawn((Health(0),));
+/// world.spawn((Health(10),));
+/// let despawned = Rc::new(std::cell::Cell::new(0));
+///
+/// let commands = LocalCommands::new();
+/// world.query::<&Health>().for_each(|entity, health| {
+///     if health.0 <= 0 {
+///         let despawned = despawned.clone();
+///         commands.add(move |world| {
+///             world.despawn(entity);
+///             despawned.set(despawned.get() + 1);
+///         });
+///     }
+/// });
+///
+/// commands.apply(&mut world);
+/// assert_eq!(world.len(), 1);
+/// assert_eq!(despawned.get(), 1);
+/// ```
+#[derive(Clone, Default)]
+pub struct LocalCommands {
+    queue: Rc<RefCell<Vec<LocalCommand>>>,
+}
+
+impl LocalCommands {
+    /// Creates an empty command queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues an entity to be spawned with the provided components.
+    pub fn spawn<B: Bundle>(&self, bundle: B) {
+        self.add(move |world| {
+            world.spawn(bundle);
+        });
+    }
+
+    /// Queues an entity to be despawned.
+    pub fn despawn(&self, entity: Entity) {
+        self.add(move |world| {
+            world.despawn(entity);
+        });
+    }
+
+    /// Queues a component to be added to an entity.
+    ///
+    /// The component is dropped if the entity no longer exists when the command is applied.
+    pub fn insert<T: Send + 'static>(&self, entity: Entity, component: T) {
+        self.add(move |world| {
+            world.insert(entity, component).ok();
+        });
+    }
+
+    /// Queues a component to be removed from an entity.
+    pub fn remove<T: 'static>(&self, entity: Entity) {
+        self.add(move |world| {
+            world.remove::<T>(entity);
+        });
+    }
+
+    /// Queues a custom command.
+    pub fn add<F: FnOnce(&mut World) + 'static>(&self, command: F) {
+        self.queue.borrow_mut().push(Box::new(command));
+    }
+
+    /// Returns the number of queued commands.
+    pub fn len(&self) -> usize {
+        self.queue.borrow().len()
+    }
+
+    /// Returns `true` if there are no queued commands.
+    pub fn is_empty(&self) -> bool {
+        self.queue.borrow().is_empty()
+    }
+
+    /// Applies the queued commands to the world, in order.
+    ///
+    /// Commands queued while applying are also applied.
+    pub fn apply(&self, world: &mut World) {
+        loop {
+            let commands = std::mem::take(&mut *self.queue.borrow_mut());
+            if commands.is_empty() {
+                break;
+            }
+            for command in commands {
+                command(world);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod command_tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Marker(u32);
+
+    #[test]
+    fn should_apply_commands_in_order() {
+        let mut world = World::new();
+        let entity = world.spawn(());
+        let commands = world.commands();
+
+        commands.insert(entity, Marker(1));
+        commands.remove::<Marker>(entity);
+        commands.insert(entity, Marker(2));
+        commands.spawn((Marker(3),));
+
+        assert_eq!(commands.len(), 4);
+        world.apply_commands();
+
+        assert!(commands.is_empty());
+        assert_eq!(world.get::<Marker>(entity), Some(&Marker(2)));
+        assert_eq!(world.len(), 2);
+    }
+
+    #[test]
+    fn should_apply_commands_queued_by_commands() {
+        let mut world = World::new();
+        let commands = world.commands();
+        let inner_commands = commands.clone();
+
+        commands.add(move |_world| inner_commands.spawn((Marker(1),)));
+        world.apply_commands();
+
+        assert_eq!(world.len(), 1);
+    }
+
+    #[test]
+    fn should_apply_local_commands_which_are_not_send() {
+        let mut world = World::new();
+        let entity = world.spawn((Marker(1),));
+        let commands = LocalCommands::new();
+        let applied = Rc::new(RefCell::new(Vec::new()));
+        let inner_commands = commands.clone();
+        let inner_applied = applied.clone();
+
+        commands.remove::<Marker>(entity);
+        commands.add(move |_world| {
+            inner_applied.borrow_mut().push(1);
+            inner_commands.spawn((Marker(2),));
+        });
+        assert_eq!(commands.len(), 2);
+        commands.apply(&mut world);
+
+        assert!(commands.is_empty());
+        assert_eq!(*applied.borrow(), vec![1]);
+        assert_eq!(world.get::<Marker>(entity), None);
+        assert_eq!(world.len(), 2);
+    }
+
+    #[test]
+    fn should_make_spawned_entities_visible_as_added_after_the_frame_ends() {
+        let mut world = World::new();
+        let commands = world.commands();
+
+        commands.spawn((Marker(1),));
+        world.end_frame();
+
+        assert_eq!(world.added::<Marker>().len(), 1);
+        world.end_frame();
+        assert!(world.added::<Marker>().is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+/// A handle to an entity in a [`World`](crate::ecs::World).
+///
+/// Entity handles are generational:  When an entity is despawned, its index may be reused by a
+/// new entity, but the new entity will have a different generation, so old handles to the
+/// despawned entity won't refer to the new one.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    /// Returns the entity's index, which may be reused once the entity is despawned.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the entity's generation, which is incremented every time its index is reused.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// Allocates, and tracks the liveness of, [`Entity`] handles.
+pub(crate) struct Entities {
+    generations: Vec<u32>,
+    is_alive: Vec<bool>,
+    free_indices: Vec<u32>,
+    count: usize,
+}
+
+impl Entities {
+    pub fn new() -> Self {
+        Self {
+            generations: Vec::new(),
+            is_alive: Vec::new(),
+            free_indices: Vec::new(),
+            count: 0,
+        }
+    }
+
+    pub fn allocate(&mut self) -> Entity {
+        self.count += 1;
+        match self.free_indices.pop() {
+            Some(index) => {
+                self.is_alive[index as usize] = true;
+                Entity {
+                    index,
+                    generation: self.generations[index as usize],
+                }
+            }
+            None => {
+                let index = self.generations.len() as u32;
+                self.generations.push(0);
+                self.is_alive.push(true);
+                Entity {
+                    index,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// Frees the entity, returning `false` if it was not alive.
+    pub fn free(&mut self, entity: Entity) -> bool {
+        if !self.contains(entity) {
+            return false;
+        }
+        let index = entity.index as usize;
+        self.is_alive[index] = false;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free_indices.push(entity.index);
+        self.count -= 1;
+        true
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        let index = entity.index as usize;
+        index < self.generations.len()
+            && self.is_alive[index]
+            && self.generations[index] == entity.generation
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.generations
+            .iter()
+            .zip(self.is_alive.iter())
+            .enumerate()
+            .filter(|(_, (_, is_alive))| **is_alive)
+            .map(|(index, (generation, _))| Entity {
+                index: index as u32,
+                generation: *generation,
+            })
+    }
+}
+
+#[cfg(test)]
+mod entity_tests {
+    use super::*;
+
+    #[test]
+    fn should_reuse_indices_with_a_new_generation() {
+        let mut entities = Entities::new();
+        let first = entities.allocate();
+
+        assert!(entities.free(first));
+        let second = entities.allocate();
+
+        assert_eq!(first.index(), second.index());
+        assert_ne!(first.generation(), second.generation());
+        assert!(!entities.contains(first));
+        assert!(entities.contains(second));
+    }
+
+    #[test]
+    fn should_not_free_dead_entities_twice() {
+        let mut entities = Entities::new();
+        let entity = entities.allocate();
+
+        assert!(entities.free(entity));
+        assert!(!entities.free(entity));
+        assert_eq!(entities.len(), 0);
+    }
+
+    #[test]
+    fn should_iterate_over_live_entities() {
+        let mut entities = Entities::new();
+        let first = entities.allocate();
+        let second = entities.allocate();
+        let third = entities.allocate();
+        entities.free(second);
+
+        assert_eq!(entities.iter().collect::<Vec<_>>(), vec![first, third]);
+    }
+}
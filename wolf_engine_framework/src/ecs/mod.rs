@@ -0,0 +1,185 @@
+//! Provides a lightweight Entity-Component-System.
+//!
+//! A [`World`] stores entities, and their components.  Entities are referred to using
+//! generational [`Entity`] handles, and any [`Send`] type can be used as a component.  Groups
+//! of components are accessed using [`Query`]s, which can be narrowed down with the [`With`],
+//! [`Without`], and [`Added`] filters.
+//!
+//! There are two ways to use a world:
+//!
+//! - **One per game:**  The [`EcsPlugin`] adds a [`World`] to the [`Context`] as a
+//!   [resource](Context::resource()), and ends its frame automatically.
+//! - **One per state:**  A [`State`] can own a [`World`], which is dropped along
+//!   with the state.  The state should call [`World::end_frame()`] at the end of its update.
+//!   Commands which aren't [`Send`] can be queued with [`LocalCommands`], and applied by the
+//!   state.
+//!
+//! # Systems
+//!
+//! Game logic can be split into [`System`]s, which are ordered into [`Stage`]s by a
+//! [`Schedule`].  Systems which declare the components they access can run in parallel, and
+//! [`RunCriteria`] can limit systems to certain states, or a fixed rate.  The
+//! [`SchedulePlugin`] runs a schedule on the world added by the [`EcsPlugin`].
+//!
+//! # Examples
+//!
+//! ```
+//! use wolf_engine_framework::*;
+//! use wolf_engine_framework::ecs::*;
+//! use wolf_engine_core::prelude::*;
+//!
+//! struct Position(f32);
+//! struct Velocity(f32);
+//!
+//! let framework = FrameworkBuilder::new(())
+//!     .with_plugin(EcsPlugin)
+//!     .build()
+//!     .unwrap();
+//!
+//! framework.run(|event, context, _control_flow| {
+//!     if event == Event::EventsCleared {
+//!         let world = context.resource_mut::<World>().unwrap();
+//!         if world.is_empty() {
+//!             world.spawn((Position(0.0), Velocity(1.0)));
+//!         }
+//!         world
+//!             .query::<(&mut Position, &Velocity)>()
+//!             .for_each(|_entity, (position, velocity)| position.0 += velocity.0);
+//!         let mut has_finished = false;
+//!         world
+//!             .query::<&Position>()
+//!             .for_each(|_entity, position| has_finished |= position.0 >= 3.0);
+//!         if has_finished {
+//!             context.quit();
+//!         }
+//!     }
+//! });
+//! ```
+
+use wolf_engine_core::prelude::*;
+
+use crate::*;
+
+mod commands;
+pub use commands::*;
+mod entities;
+pub(crate) use entities::Entities;
+pub use entities::Entity;
+mod query;
+pub use query::*;
+mod schedule;
+pub use schedule::*;
+mod storage;
+mod world;
+pub use world::*;
+
+/// Adds a [`World`] to the [`Context`] as a [resource](Context::resource()).
+///
+/// The world's [`World::end_frame()`] is called at the start of every frame, so changes queued
+/// during a frame are applied before the next one starts.
+pub struct EcsPlugin;
+
+impl<D: 'static> Plugin<D> for EcsPlugin {
+    fn name(&self) -> &'static str {
+        "ecs"
+    }
+
+    fn build(&mut self, builder: &mut PluginBuilder<D>) {
+        builder.insert_resource(World::new());
+        builder.add_subsystem(WorldSubsystem);
+    }
+}
+
+struct WorldSubsystem;
+
+impl<D> Subsystem<D> for WorldSubsystem {
+    fn name(&self) -> &'static str {
+        "ecs_world"
+    }
+
+    fn on_frame(&mut self, context: &mut Context<D>) {
+        if let Ok(world) = context.resource_mut::<World>() {
+            world.end_frame();
+        }
+    }
+}
+
+#[cfg(test)]
+mod ecs_tests {
+    use ntest::timeout;
+
+    use super::*;
+
+    struct Lifetime(u32);
+
+    struct Level {
+        world: World,
+    }
+
+    impl State<()> for Level {
+        fn on_enter(&mut self, _context: &mut Context<()>) {
+            self.world.spawn((Lifetime(1),));
+            self.world.spawn((Lifetime(3),));
+        }
+
+        fn update(&mut self, _context: &mut Context<()>) -> OptionalTransition<()> {
+            let commands = self.world.commands();
+            self.world
+                .query::<&mut Lifetime>()
+                .for_each(|entity, lifetime| {
+                    lifetime.0 -= 1;
+                    if lifetime.0 == 0 {
+                        commands.despawn(entity);
+                    }
+                });
+            self.world.end_frame();
+            if self.world.is_empty() {
+                Some(Transition::Pop)
+            } else {
+                None
+            }
+        }
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_run_a_world_owned_by_a_state() {
+        let framework = FrameworkBuilder::new(())
+            .with_plugin(StateStack::with_state(Level {
+                world: World::new(),
+            }))
+            .build()
+            .unwrap();
+
+        assert_eq!(framework.run(|_event, _context, _control_flow| ()), 0);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_apply_commands_between_frames_for_the_world_resource() {
+        let framework = FrameworkBuilder::new(0)
+            .with_plugin(EcsPlugin)
+            .build()
+            .unwrap();
+
+        framework.run(|event, context, _control_flow| {
+            if event != Event::EventsCleared {
+                return;
+            }
+            context.data += 1;
+            let frame = context.data;
+            let world = context.resource_mut::<World>().unwrap();
+            match frame {
+                1 => world.commands().spawn((Lifetime(1),)),
+                2 => {
+                    assert_eq!(world.len(), 1);
+                    assert_eq!(world.added::<Lifetime>().len(), 1);
+                }
+                _ => {
+                    assert!(world.added::<Lifetime>().is_empty());
+                    context.quit();
+                }
+            }
+        });
+    }
+}
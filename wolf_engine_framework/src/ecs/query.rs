@@ -0,0 +1,340 @@
+use std::any::{type_name, TypeId};
+use std::marker::PhantomData;
+
+use crate::ecs::storage::{ComponentStorage, Storages};
+use crate::ecs::*;
+
+/// The components accessed by a [`Query`].
+///
+/// Implemented for `&T`, `&mut T`, and tuples of up to 8 of them.
+pub trait QueryData {
+    /// The references returned for each matching entity.
+    type Item<'a>;
+
+    #[doc(hidden)]
+    type Fetch<'w>;
+
+    #[doc(hidden)]
+    fn component_types(component_types: &mut Vec<(TypeId, &'static str)>);
+
+    #[doc(hidden)]
+    fn matches(storages: &Storages, entity: Entity) -> bool;
+
+    #[doc(hidden)]
+    fn borrow<'w>(storages: &mut Storages<'w>) -> Self::Fetch<'w>;
+
+    #[doc(hidden)]
+    fn fetch<'f>(fetch: &'f mut Self::Fetch<'_>, entity: Entity) -> Self::Item<'f>;
+}
+
+impl<T: 'static> QueryData for &T {
+    type Item<'a> = &'a T;
+    type Fetch<'w> = Option<&'w ComponentStorage<T>>;
+
+    fn component_types(component_types: &mut Vec<(TypeId, &'static str)>) {
+        component_types.push((TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    fn matches(storages: &Storages, entity: Entity) -> bool {
+        storages.has::<T>(entity)
+    }
+
+    fn borrow<'w>(storages: &mut Storages<'w>) -> Self::Fetch<'w> {
+        storages.read::<T>()
+    }
+
+    fn fetch<'f>(fetch: &'f mut Self::Fetch<'_>, entity: Entity) -> Self::Item<'f> {
+        fetch
+            .and_then(|storage| storage.get(entity))
+            .expect("Queries should only fetch matching entities")
+    }
+}
+
+impl<T: 'static> QueryData for &mut T {
+    type Item<'a> = &'a mut T;
+    type Fetch<'w> = Option<&'w mut ComponentStorage<T>>;
+
+    fn component_types(component_types: &mut Vec<(TypeId, &'static str)>) {
+        component_types.push((TypeId::of::<T>(), type_name::<T>()));
+    }
+
+    fn matches(storages: &Storages, entity: Entity) -> bool {
+        storages.has::<T>(entity)
+    }
+
+    fn borrow<'w>(storages: &mut Storages<'w>) -> Self::Fetch<'w> {
+        storages.write::<T>()
+    }
+
+    fn fetch<'f>(fetch: &'f mut Self::Fetch<'_>, entity: Entity) -> Self::Item<'f> {
+        fetch
+            .as_mut()
+            .and_then(|storage| storage.get_mut(entity))
+            .expect("Queries should only fetch matching entities")
+    }
+}
+
+macro_rules! impl_query_data {
+    ($($data:ident),*) => {
+        impl<$($data: QueryData),*> QueryData for ($($data,)*) {
+            type Item<'a> = ($($data::Item<'a>,)*);
+            type Fetch<'w> = ($($data::Fetch<'w>,)*);
+
+            fn component_types(component_types: &mut Vec<(TypeId, &'static str)>) {
+                $($data::component_types(component_types);)*
+            }
+
+            fn matches(storages: &Storages, entity: Entity) -> bool {
+                true $(&& $data::matches(storages, entity))*
+            }
+
+            fn borrow<'w>(storages: &mut Storages<'w>) -> Self::Fetch<'w> {
+                ($($data::borrow(storages),)*)
+            }
+
+            #[allow(non_snake_case)]
+            fn fetch<'f>(fetch: &'f mut Self::Fetch<'_>, entity: Entity) -> Self::Item<'f> {
+                let ($($data,)*) = fetch;
+                ($($data::fetch($data, entity),)*)
+            }
+        }
+    };
+}
+
+impl_query_data!(A);
+impl_query_data!(A, B);
+impl_query_data!(A, B, C);
+impl_query_data!(A, B, C, D);
+impl_query_data!(A, B, C, D, E);
+impl_query_data!(A, B, C, D, E, F);
+impl_query_data!(A, B, C, D, E, F, G);
+impl_query_data!(A, B, C, D, E, F, G, H);
+
+/// Narrows down the entities matched by a [`Query`], without accessing their components.
+///
+/// Implemented for [`With`], [`Without`], [`Added`], and tuples of up to 8 filters, which
+/// match only if all of their filters match.
+pub trait QueryFilter {
+    #[doc(hidden)]
+    fn matches(storages: &Storages, entity: Entity) -> bool;
+}
+
+impl QueryFilter for () {
+    fn matches(_storages: &Storages, _entity: Entity) -> bool {
+        true
+    }
+}
+
+/// Matches entities which have a component of type `T`.
+pub struct With<T>(PhantomData<fn() -> T>);
+
+impl<T: 'static> QueryFilter for With<T> {
+    fn matches(storages: &Storages, entity: Entity) -> bool {
+        storages.has::<T>(entity)
+    }
+}
+
+/// Matches entities which don't have a component of type `T`.
+pub struct Without<T>(PhantomData<fn() -> T>);
+
+impl<T: 'static> QueryFilter for Without<T> {
+    fn matches(storages: &Storages, entity: Entity) -> bool {
+        !storages.has::<T>(entity)
+    }
+}
+
+/// Matches entities which had a component of type `T` added since the world's trackers were
+/// last cleared.
+pub struct Added<T>(PhantomData<fn() -> T>);
+
+impl<T: 'static> QueryFilter for Added<T> {
+    fn matches(storages: &Storages, entity: Entity) -> bool {
+        storages.added::<T>(entity)
+    }
+}
+
+macro_rules! impl_query_filter {
+    ($($filter:ident),*) => {
+        impl<$($filter: QueryFilter),*> QueryFilter for ($($filter,)*) {
+            fn matches(storages: &Storages, entity: Entity) -> bool {
+                true $(&& $filter::matches(storages, entity))*
+            }
+        }
+    };
+}
+
+impl_query_filter!(A);
+impl_query_filter!(A, B);
+impl_query_filter!(A, B, C);
+impl_query_filter!(A, B, C, D);
+impl_query_filter!(A, B, C, D, E);
+impl_query_filter!(A, B, C, D, E, F);
+impl_query_filter!(A, B, C, D, E, F, G);
+impl_query_filter!(A, B, C, D, E, F, G, H);
+
+/// Provides access to the components of every entity matching `Q`, and the filter `F`.
+///
+/// Queries are created using [`World::query()`], and [`World::query_filtered()`], or the
+/// matching methods on a [`SystemView`].  A query may only access each component type once, so
+/// `(&mut Position, &Position)` is not allowed.
+pub struct Query<'w, Q: QueryData, F: QueryFilter = ()> {
+    storages: Storages<'w>,
+    _query: PhantomData<fn() -> (Q, F)>,
+}
+
+impl<'w, Q: QueryData, F: QueryFilter> Query<'w, Q, F> {
+    pub(crate) fn new(storages: Storages<'w>) -> Self {
+        let mut component_types = Vec::new();
+        Q::component_types(&mut component_types);
+        for (index, (type_id, name)) in component_types.iter().enumerate() {
+            if component_types[..index]
+                .iter()
+                .any(|(other_type_id, _)| other_type_id == type_id)
+            {
+                panic!("A query may only access `{}` once", name);
+            }
+        }
+        Self {
+            storages,
+            _query: PhantomData,
+        }
+    }
+
+    fn matches(&self, entity: Entity) -> bool {
+        Q::matches(&self.storages, entity) && F::matches(&self.storages, entity)
+    }
+
+    /// Returns the matching entities.
+    pub fn entities(&self) -> Vec<Entity> {
+        self.storages
+            .entities()
+            .iter()
+            .filter(|entity| self.matches(*entity))
+            .collect()
+    }
+
+    /// Returns the number of matching entities.
+    pub fn count(&self) -> usize {
+        self.entities().len()
+    }
+
+    /// Calls the function with the components of each matching entity.
+    pub fn for_each<Function>(mut self, mut function: Function)
+    where
+        Function: for<'a> FnMut(Entity, Q::Item<'a>),
+    {
+        let entities = self.entities();
+        let mut fetch = Q::borrow(&mut self.storages);
+        for entity in entities {
+            function(entity, Q::fetch(&mut fetch, entity));
+        }
+    }
+
+    /// Calls the function with the components of a single entity, if it matches the query.
+    pub fn get<R, Function>(mut self, entity: Entity, function: Function) -> Option<R>
+    where
+        Function: for<'a> FnOnce(Q::Item<'a>) -> R,
+    {
+        if !self.storages.entities().contains(entity) || !self.matches(entity) {
+            return None;
+        }
+        let mut fetch = Q::borrow(&mut self.storages);
+        Some(function(Q::fetch(&mut fetch, entity)))
+    }
+}
+
+#[cfg(test)]
+mod query_tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Velocity(i32);
+
+    struct Frozen;
+
+    #[test]
+    fn should_query_component_tuples() {
+        let mut world = World::new();
+        let moving = world.spawn((Position(0), Velocity(2)));
+        let still = world.spawn((Position(5),));
+
+        world
+            .query::<(&mut Position, &Velocity)>()
+            .for_each(|_entity, (position, velocity)| position.0 += velocity.0);
+
+        assert_eq!(world.get::<Position>(moving), Some(&Position(2)));
+        assert_eq!(world.get::<Position>(still), Some(&Position(5)));
+        assert_eq!(world.query::<(&Position,)>().count(), 2);
+    }
+
+    #[test]
+    fn should_apply_filters() {
+        let mut world = World::new();
+        let frozen = world.spawn((Position(0), Frozen));
+        let free = world.spawn((Position(0),));
+
+        assert_eq!(
+            world
+                .query_filtered::<(&Position,), With<Frozen>>()
+                .entities(),
+            vec![frozen]
+        );
+        assert_eq!(
+            world
+                .query_filtered::<(&Position,), Without<Frozen>>()
+                .entities(),
+            vec![free]
+        );
+    }
+
+    #[test]
+    fn should_filter_added_components() {
+        let mut world = World::new();
+        world.spawn((Position(0),));
+        world.clear_trackers();
+        let new_entity = world.spawn((Position(1),));
+
+        let added = world
+            .query_filtered::<(&Position,), Added<Position>>()
+            .entities();
+
+        assert_eq!(added, vec![new_entity]);
+    }
+
+    #[test]
+    fn should_get_a_single_entity() {
+        let mut world = World::new();
+        let entity = world.spawn((Position(3),));
+        let other = world.spawn((Velocity(1),));
+
+        let position = world
+            .query::<&Position>()
+            .get(entity, |position| position.0);
+
+        assert_eq!(position, Some(3));
+        assert_eq!(world.query::<&Position>().get(other, |_| ()), None);
+    }
+
+    #[test]
+    fn should_keep_components_after_querying_missing_types() {
+        let mut world = World::new();
+        let entity = world.spawn((Position(3),));
+
+        world
+            .query::<(&Position, &Velocity)>()
+            .for_each(|_entity, _components| panic!("No entity should match"));
+
+        assert_eq!(world.get::<Position>(entity), Some(&Position(3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "may only access")]
+    fn should_panic_on_aliased_component_access() {
+        let mut world = World::new();
+
+        world.query::<(&mut Position, &Position)>();
+    }
+}
@@ -0,0 +1,1278 @@
+use std::any::{type_name, TypeId};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use wolf_engine_core::prelude::*;
+
+use crate::ecs::storage::{AnyStorage, Storages};
+use crate::ecs::*;
+
+/// A labelled group of [`System`]s, which runs during one of the engine's
+/// [frame phases](FramePhase).
+///
+/// When frame phases are disabled, every stage runs, in order, on [`Event::EventsCleared`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Stage {
+    /// Runs during [`FramePhase::PreUpdate`].
+    PreUpdate,
+    /// Runs once for each fixed step consumed from the [`Clock`], at the start of
+    /// [`FramePhase::Update`].
+    FixedUpdate,
+    /// Runs during [`FramePhase::Update`], after the fixed updates.
+    Update,
+    /// Runs during [`FramePhase::PostUpdate`].
+    PostUpdate,
+    /// Runs during [`FramePhase::Render`].
+    Render,
+}
+
+impl Stage {
+    /// Every stage, in the order they run.
+    pub const ALL: [Stage; 5] = [
+        Self::PreUpdate,
+        Self::FixedUpdate,
+        Self::Update,
+        Self::PostUpdate,
+        Self::Render,
+    ];
+
+    /// Returns the frame phase the stage runs during.
+    pub fn frame_phase(&self) -> FramePhase {
+        match self {
+            Self::PreUpdate => FramePhase::PreUpdate,
+            Self::FixedUpdate | Self::Update => FramePhase::Update,
+            Self::PostUpdate => FramePhase::PostUpdate,
+            Self::Render => FramePhase::Render,
+        }
+    }
+}
+
+/// Describes the stage currently being run by a [`Schedule`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RunInfo {
+    stage: Stage,
+    delta: Duration,
+    active_state: Option<&'static str>,
+}
+
+impl RunInfo {
+    /// Creates the run info for a stage, which covers `delta` game time.
+    pub fn new(stage: Stage, delta: Duration) -> Self {
+        Self {
+            stage,
+            delta,
+            active_state: None,
+        }
+    }
+
+    /// Sets the [name](State::name()) of the active state.
+    pub fn with_active_state(mut self, name: &'static str) -> Self {
+        self.active_state = Some(name);
+        self
+    }
+
+    /// Returns the stage being run.
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    /// Returns the game time covered by this run of the stage.
+    ///
+    /// For [`Stage::FixedUpdate`], this is the length of a fixed step.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Returns the [name](State::name()) of the active state, if there is one.
+    pub fn active_state(&self) -> Option<&'static str> {
+        self.active_state
+    }
+}
+
+/// Decides whether a [`System`] should run.
+///
+/// A system with more than one run criteria only runs if all of them pass.
+pub struct RunCriteria {
+    kind: RunCriteriaKind,
+}
+
+type CustomRunCriteria = Box<dyn FnMut(&World, &RunInfo) -> bool>;
+
+enum RunCriteriaKind {
+    InState(&'static str),
+    FixedRate {
+        timestep: Option<Duration>,
+        accumulator: Duration,
+    },
+    Custom(CustomRunCriteria),
+}
+
+impl RunCriteria {
+    /// Runs the system only while a state of type `S` is the [`ActiveState`].
+    ///
+    /// This relies on the state using the default [`State::name()`].
+    pub fn in_state<S: ?Sized + 'static>() -> Self {
+        Self::in_state_named(type_name::<S>())
+    }
+
+    /// Runs the system only while the [`ActiveState`] has the provided name.
+    pub fn in_state_named(name: &'static str) -> Self {
+        Self {
+            kind: RunCriteriaKind::InState(name),
+        }
+    }
+
+    /// Runs the system at most `rate` times per second of game time, and at most once per run
+    /// of its stage.
+    ///
+    /// A rate of `0` stops the system from running.
+    pub fn fixed_rate(rate: u32) -> Self {
+        let timestep = match rate {
+            0 => None,
+            rate => Some(Duration::from_secs(1) / rate),
+        };
+        Self {
+            kind: RunCriteriaKind::FixedRate {
+                timestep,
+                accumulator: Duration::ZERO,
+            },
+        }
+    }
+
+    /// Runs the system only when the function returns `true`.
+    pub fn custom<F>(function: F) -> Self
+    where
+        F: FnMut(&World, &RunInfo) -> bool + 'static,
+    {
+        Self {
+            kind: RunCriteriaKind::Custom(Box::new(function)),
+        }
+    }
+
+    fn should_run(&mut self, world: &World, info: &RunInfo) -> bool {
+        match &mut self.kind {
+            RunCriteriaKind::InState(name) => info.active_state() == Some(*name),
+            RunCriteriaKind::FixedRate {
+                timestep: Some(timestep),
+                accumulator,
+            } => {
+                *accumulator += info.delta();
+                if *accumulator >= *timestep {
+                    *accumulator = (*accumulator - *timestep).min(*timestep);
+                    true
+                } else {
+                    false
+                }
+            }
+            RunCriteriaKind::FixedRate { timestep: None, .. } => false,
+            RunCriteriaKind::Custom(function) => function(world, info),
+        }
+    }
+}
+
+type ExclusiveSystemFunction = Box<dyn FnMut(&mut World, &RunInfo)>;
+type ParallelSystemFunction = Box<dyn for<'a> FnMut(&mut SystemView<'a>) + Send>;
+
+enum SystemFunction {
+    Exclusive(ExclusiveSystemFunction),
+    Parallel(ParallelSystemFunction),
+}
+
+/// The components a parallel [`System`] has declared it will access.
+#[derive(Default)]
+struct Access {
+    reads: Vec<TypeId>,
+    writes: Vec<TypeId>,
+    shares: Vec<fn(&mut World)>,
+}
+
+impl Access {
+    fn conflicts_with(&self, other: &Access) -> bool {
+        self.writes
+            .iter()
+            .any(|type_id| other.reads.contains(type_id) || other.writes.contains(type_id))
+            || other
+                .writes
+                .iter()
+                .any(|type_id| self.reads.contains(type_id))
+    }
+}
+
+/// A named unit of game logic, which is run by a [`Schedule`].
+///
+/// There are two kinds of system:
+///
+/// - **Exclusive systems** get full access to the [`World`], so they never run alongside other
+///   systems.
+/// - **Parallel systems** get a [`SystemView`], which can only access the components the system
+///   declares using [`System::reads()`], and [`System::writes()`].  Parallel systems whose
+///   access doesn't conflict may run at the same time, on different threads.
+///
+/// Systems are ordered relative to other systems in the same stage using [`System::before()`],
+/// and [`System::after()`].  Otherwise, they run in the order they were added.
+pub struct System {
+    name: &'static str,
+    stage: Stage,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+    run_criteria: Vec<RunCriteria>,
+    access: Access,
+    function: SystemFunction,
+}
+
+impl System {
+    /// Creates a system with full access to the [`World`].
+    pub fn exclusive<F>(name: &'static str, stage: Stage, function: F) -> Self
+    where
+        F: FnMut(&mut World, &RunInfo) + 'static,
+    {
+        Self::new(name, stage, SystemFunction::Exclusive(Box::new(function)))
+    }
+
+    /// Creates a system which may run in parallel with other systems.
+    ///
+    /// The system may only access the components it declares using [`System::reads()`], and
+    /// [`System::writes()`].  Accessing anything else panics.
+    pub fn parallel<F>(name: &'static str, stage: Stage, function: F) -> Self
+    where
+        F: for<'a> FnMut(&mut SystemView<'a>) + Send + 'static,
+    {
+        Self::new(name, stage, SystemFunction::Parallel(Box::new(function)))
+    }
+
+    fn new(name: &'static str, stage: Stage, function: SystemFunction) -> Self {
+        Self {
+            name,
+            stage,
+            before: Vec::new(),
+            after: Vec::new(),
+            run_criteria: Vec::new(),
+            access: Access::default(),
+            function,
+        }
+    }
+
+    /// Declares that the system reads components of type `T`.
+    pub fn reads<T: Send + Sync + 'static>(mut self) -> Self {
+        self.access.reads.push(TypeId::of::<T>());
+        self.access.shares.push(World::share_storage::<T>);
+        self
+    }
+
+    /// Declares that the system reads, and writes, components of type `T`.
+    pub fn writes<T: Send + Sync + 'static>(mut self) -> Self {
+        self.access.writes.push(TypeId::of::<T>());
+        self.access.shares.push(World::share_storage::<T>);
+        self
+    }
+
+    /// Runs the system before the named system, which must be in the same stage.
+    pub fn before(mut self, name: &'static str) -> Self {
+        self.before.push(name);
+        self
+    }
+
+    /// Runs the system after the named system, which must be in the same stage.
+    pub fn after(mut self, name: &'static str) -> Self {
+        self.after.push(name);
+        self
+    }
+
+    /// Adds a [`RunCriteria`], which must pass for the system to run.
+    pub fn with_run_criteria(mut self, run_criteria: RunCriteria) -> Self {
+        self.run_criteria.push(run_criteria);
+        self
+    }
+
+    /// Returns the name of the system.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the stage the system runs in.
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    fn is_exclusive(&self) -> bool {
+        matches!(self.function, SystemFunction::Exclusive(_))
+    }
+
+    fn should_run(&mut self, world: &World, info: &RunInfo) -> bool {
+        // Every criteria is checked, so fixed rates keep accumulating time.
+        let mut should_run = true;
+        for criteria in self.run_criteria.iter_mut() {
+            should_run &= criteria.should_run(world, info);
+        }
+        should_run
+    }
+}
+
+/// Provides a parallel [`System`] with access to the components it declared.
+pub struct SystemView<'a> {
+    name: &'static str,
+    entities: &'a Entities,
+    writable: Vec<(TypeId, &'a mut (dyn AnyStorage + Send + Sync))>,
+    readable: Vec<(TypeId, &'a (dyn AnyStorage + Send + Sync))>,
+    commands: Commands,
+    info: &'a RunInfo,
+}
+
+impl<'a> SystemView<'a> {
+    /// Returns the name of the system.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns information about the stage being run.
+    pub fn info(&self) -> &RunInfo {
+        self.info
+    }
+
+    /// Returns a handle to the world's command queue.
+    pub fn commands(&self) -> Commands {
+        self.commands.clone()
+    }
+
+    /// Returns `true` if the entity exists in the world.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(entity)
+    }
+
+    /// Creates a [`Query`] over every entity with the components in `Q`.
+    pub fn query<Q: QueryData>(&mut self) -> Query<'_, Q> {
+        Query::new(self.storages())
+    }
+
+    /// Creates a [`Query`] over every entity with the components in `Q`, which also matches
+    /// the filter `F`.
+    pub fn query_filtered<Q: QueryData, F: QueryFilter>(&mut self) -> Query<'_, Q, F> {
+        Query::new(self.storages())
+    }
+
+    fn storages(&mut self) -> Storages<'_> {
+        let mut storages = Storages::restricted(self.entities, self.name);
+        for (type_id, storage) in self.writable.iter_mut() {
+            storages.add_writable(*type_id, &mut **storage);
+        }
+        for (type_id, storage) in self.readable.iter() {
+            storages.add_readable(*type_id, *storage);
+        }
+        storages
+    }
+}
+
+/// Indicates a problem ordering the [`System`]s in a [`Schedule`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ScheduleError {
+    /// A system with the same name was already added.
+    Duplicate {
+        /// The name of the system.
+        name: &'static str,
+    },
+    /// A system is ordered relative to a system which is not in the same stage.
+    MissingDependency {
+        /// The name of the system.
+        name: &'static str,
+        /// The name of the missing system.
+        dependency: &'static str,
+    },
+    /// The ordering constraints of some systems form a cycle, so they can't be ordered.
+    DependencyCycle {
+        /// The names of the systems which could not be ordered, in the order they were added.
+        names: Vec<&'static str>,
+    },
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Duplicate { name } => {
+                write!(f, "A system named `{}` was already added", name)
+            }
+            Self::MissingDependency { name, dependency } => write!(
+                f,
+                "System `{}` is ordered relative to `{}`, which is not in the same stage",
+                name, dependency
+            ),
+            Self::DependencyCycle { names } => write!(
+                f,
+                "The ordering constraints of these systems form a cycle: {}",
+                names.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for ScheduleError {}
+
+impl From<DependencyError> for ScheduleError {
+    fn from(error: DependencyError) -> Self {
+        match error {
+            DependencyError::Missing { name, dependency } => {
+                Self::MissingDependency { name, dependency }
+            }
+            DependencyError::Cycle { names } => Self::DependencyCycle { names },
+        }
+    }
+}
+
+/// Builds a [`Schedule`].
+pub struct ScheduleBuilder {
+    systems: Vec<System>,
+    threads: usize,
+}
+
+impl Default for ScheduleBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScheduleBuilder {
+    /// Creates a builder for an empty, single-threaded, schedule.
+    pub fn new() -> Self {
+        Self {
+            systems: Vec::new(),
+            threads: 1,
+        }
+    }
+
+    /// Adds a system to the schedule.
+    pub fn with_system(mut self, system: System) -> Self {
+        self.systems.push(system);
+        self
+    }
+
+    /// Sets the number of threads used to run parallel systems.
+    ///
+    /// With `1` thread, the default, every system runs on the calling thread.
+    /// [`std::thread::available_parallelism()`] is a good choice for using every core.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Orders the systems, and groups them into batches which can run in parallel.
+    pub fn build(self) -> Result<Schedule, ScheduleError> {
+        for (index, system) in self.systems.iter().enumerate() {
+            if self.systems[..index]
+                .iter()
+                .any(|other_system| other_system.name == system.name)
+            {
+                return Err(ScheduleError::Duplicate { name: system.name });
+            }
+        }
+        let mut unsorted = self.systems;
+        let mut systems = Vec::with_capacity(unsorted.len());
+        let mut batches = Vec::new();
+        for stage in Stage::ALL {
+            let (stage_systems, other_systems) = unsorted
+                .into_iter()
+                .partition(|system| system.stage == stage);
+            unsorted = other_systems;
+            let first_index = systems.len();
+            systems.extend(Self::sort_stage(stage_systems)?);
+            Self::batch_stage(&systems, first_index, &mut batches);
+        }
+        Ok(Schedule {
+            systems,
+            batches,
+            workers: Workers::new(self.threads - 1),
+        })
+    }
+
+    /// Returns the names of the systems which must run before the system.
+    fn dependencies(system: &System, stage_systems: &[System]) -> Vec<&'static str> {
+        let mut dependencies = system.after.clone();
+        dependencies.extend(
+            stage_systems
+                .iter()
+                .filter(|other_system| other_system.before.contains(&system.name))
+                .map(|other_system| other_system.name),
+        );
+        dependencies
+    }
+
+    /// Sorts the systems so each one runs after its dependencies, keeping the order they were
+    /// added wherever possible.
+    fn sort_stage(stage_systems: Vec<System>) -> Result<Vec<System>, ScheduleError> {
+        for system in stage_systems.iter() {
+            if let Some(dependency) =
+                system
+                    .before
+                    .iter()
+                    .chain(system.after.iter())
+                    .find(|dependency| {
+                        !stage_systems
+                            .iter()
+                            .any(|other_system| other_system.name == **dependency)
+                    })
+            {
+                return Err(ScheduleError::MissingDependency {
+                    name: system.name,
+                    dependency,
+                });
+            }
+        }
+        let dependencies: Vec<Vec<&'static str>> = stage_systems
+            .iter()
+            .map(|system| Self::dependencies(system, &stage_systems))
+            .collect();
+        let mut systems: Vec<(System, Vec<&'static str>)> =
+            stage_systems.into_iter().zip(dependencies).collect();
+        sort_by_dependencies(
+            &mut systems,
+            |(system, _)| system.name,
+            |(_, dependencies)| dependencies.clone(),
+        )?;
+        Ok(systems.into_iter().map(|(system, _)| system).collect())
+    }
+
+    /// Groups consecutive parallel systems into batches, as long as they don't conflict with,
+    /// or depend on, any other system in the batch.
+    fn batch_stage(systems: &[System], first_index: usize, batches: &mut Vec<Vec<usize>>) {
+        let stage_systems = &systems[first_index..];
+        let mut batch: Vec<usize> = Vec::new();
+        for (index, system) in stage_systems.iter().enumerate() {
+            let dependencies = Self::dependencies(system, stage_systems);
+            let can_join = !system.is_exclusive()
+                && batch.iter().all(|batch_index| {
+                    let batch_system = &stage_systems[*batch_index];
+                    !batch_system.is_exclusive()
+                        && !batch_system.access.conflicts_with(&system.access)
+                        && !dependencies.contains(&batch_system.name)
+                });
+            if !can_join && !batch.is_empty() {
+                batches.push(batch.drain(..).map(|index| index + first_index).collect());
+            }
+            batch.push(index);
+        }
+        if !batch.is_empty() {
+            batches.push(batch.into_iter().map(|index| index + first_index).collect());
+        }
+    }
+}
+
+/// Runs [`System`]s in order, stage by stage.
+///
+/// Within a stage, systems run in the order they were added, unless they are reordered by
+/// their ordering constraints.  Consecutive parallel systems whose access doesn't conflict are
+/// grouped into batches.  When the schedule has more than one
+/// [thread](ScheduleBuilder::with_threads()), each batch is split between the calling thread,
+/// and worker threads which are started when the schedule is built, and kept until it is
+/// dropped.
+///
+/// The [`SchedulePlugin`] runs a schedule on the world added by the [`EcsPlugin`], but a
+/// schedule can also be run by hand, using [`Schedule::run_stage()`].
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// use wolf_engine_framework::ecs::*;
+///
+/// struct Position(f32);
+/// struct Velocity(f32);
+///
+/// let mut schedule = ScheduleBuilder::new()
+///     .with_system(
+///         System::parallel("movement", Stage::Update, |view| {
+///             let delta = view.info().delta().as_secs_f32();
+///             view.query::<(&mut Position, &Velocity)>()
+///                 .for_each(|_entity, (position, velocity)| position.0 += velocity.0 * delta);
+///         })
+///         .writes::<Position>()
+///         .reads::<Velocity>()
+///         .after("spawn"),
+///     )
+///     .with_system(System::exclusive("spawn", Stage::Update, |world, _info| {
+///         if world.is_empty() {
+///             world.spawn((Position(0.0), Velocity(2.0)));
+///         }
+///     }))
+///     .build()
+///     .unwrap();
+/// assert_eq!(schedule.system_names(Stage::Update), vec!["spawn", "movement"]);
+///
+/// let mut world = World::new();
+/// schedule.run_stage(&mut world, &RunInfo::new(Stage::Update, Duration::from_secs(1)));
+///
+/// world
+///     .query::<&Position>()
+///     .for_each(|_entity, position| assert_eq!(position.0, 2.0));
+/// ```
+pub struct Schedule {
+    systems: Vec<System>,
+    batches: Vec<Vec<usize>>,
+    workers: Workers,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            systems: Vec::new(),
+            batches: Vec::new(),
+            workers: Workers::new(0),
+        }
+    }
+}
+
+impl Schedule {
+    /// Returns the names of the systems in the stage, in the order they run.
+    pub fn system_names(&self, stage: Stage) -> Vec<&'static str> {
+        self.systems
+            .iter()
+            .filter(|system| system.stage == stage)
+            .map(|system| system.name)
+            .collect()
+    }
+
+    /// Returns the names of the systems in each batch of the stage.
+    ///
+    /// Systems in the same batch may run at the same time.
+    pub fn batches(&self, stage: Stage) -> Vec<Vec<&'static str>> {
+        self.batches
+            .iter()
+            .filter(|batch| self.systems[batch[0]].stage == stage)
+            .map(|batch| {
+                batch
+                    .iter()
+                    .map(|index| self.systems[*index].name)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the number of threads used to run parallel systems.
+    pub fn threads(&self) -> usize {
+        self.workers.len() + 1
+    }
+
+    /// Runs the systems in the [stage](RunInfo::stage()) whose run criteria pass.
+    pub fn run_stage(&mut self, world: &mut World, info: &RunInfo) {
+        let Self {
+            systems,
+            batches,
+            workers,
+        } = self;
+        for batch in batches.iter() {
+            if systems[batch[0]].stage != info.stage() {
+                continue;
+            }
+            let mut runnable: Vec<&mut System> = systems
+                .iter_mut()
+                .enumerate()
+                .filter(|(index, _)| batch.contains(index))
+                .map(|(_, system)| system)
+                .filter_map(|system| system.should_run(world, info).then_some(system))
+                .collect();
+            Self::run_batch(&mut runnable, world, info, workers);
+        }
+    }
+
+    fn run_batch(
+        systems: &mut [&mut System],
+        world: &mut World,
+        info: &RunInfo,
+        workers: &Workers,
+    ) {
+        if systems.is_empty() {
+            return;
+        }
+        if let [system] = systems {
+            if let SystemFunction::Exclusive(function) = &mut system.function {
+                function(world, info);
+                return;
+            }
+        }
+        for system in systems.iter() {
+            for share in system.access.shares.iter() {
+                share(world);
+            }
+        }
+        let accesses: Vec<(Vec<TypeId>, Vec<TypeId>)> = systems
+            .iter()
+            .map(|system| (system.access.reads.clone(), system.access.writes.clone()))
+            .collect();
+        let commands = world.commands();
+        let (entities, storages) = world.shared_storages_mut();
+        let mut jobs: Vec<(&mut ParallelSystemFunction, SystemView)> = systems
+            .iter_mut()
+            .filter_map(|system| match &mut system.function {
+                SystemFunction::Parallel(function) => Some((
+                    function,
+                    SystemView {
+                        name: system.name,
+                        entities,
+                        writable: Vec::new(),
+                        readable: Vec::new(),
+                        commands: commands.clone(),
+                        info,
+                    },
+                )),
+                SystemFunction::Exclusive(_) => None,
+            })
+            .collect();
+        for (type_id, storage) in storages {
+            let writer = accesses
+                .iter()
+                .position(|(_, writes)| writes.contains(&type_id));
+            match writer {
+                Some(writer) => jobs[writer].1.writable.push((type_id, storage)),
+                None => {
+                    let storage = &*storage;
+                    for ((reads, _), (_, view)) in accesses.iter().zip(jobs.iter_mut()) {
+                        if reads.contains(&type_id) {
+                            view.readable.push((type_id, storage));
+                        }
+                    }
+                }
+            }
+        }
+        let thread_count = (workers.len() + 1).min(jobs.len());
+        let mut groups: Vec<Vec<_>> = (0..thread_count).map(|_| Vec::new()).collect();
+        for (index, job) in jobs.into_iter().enumerate() {
+            groups[index % thread_count].push(job);
+        }
+        let mut groups = groups.into_iter();
+        let local_group = groups.next().unwrap_or_default();
+        let tasks = groups
+            .map(|group| Box::new(move || Self::run_jobs(group)) as ScopedTask<'_>)
+            .collect();
+        workers.run_scoped(tasks, move || Self::run_jobs(local_group));
+    }
+
+    fn run_jobs(jobs: Vec<(&mut ParallelSystemFunction, SystemView)>) {
+        for (function, mut view) in jobs {
+            function(&mut view);
+        }
+    }
+}
+
+type ScopedTask<'a> = Box<dyn FnOnce() + Send + 'a>;
+type WorkerTask = ScopedTask<'static>;
+
+/// The worker threads used to run a [`Schedule`]'s parallel batches.
+///
+/// The threads are kept for the life of the schedule, so running a batch doesn't need to spawn
+/// any.
+struct Workers {
+    task_sender: Option<Sender<WorkerTask>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Workers {
+    fn new(count: usize) -> Self {
+        if count == 0 {
+            return Self {
+                task_sender: None,
+                threads: Vec::new(),
+            };
+        }
+        let (task_sender, task_receiver) = mpsc::channel::<WorkerTask>();
+        let task_receiver = Arc::new(Mutex::new(task_receiver));
+        let threads = (0..count)
+            .map(|index| {
+                let task_receiver = task_receiver.clone();
+                thread::Builder::new()
+                    .name(format!("wolf_engine_schedule_worker_{}", index))
+                    .spawn(move || Self::run_worker(task_receiver))
+                    .expect("Failed to spawn a schedule worker thread")
+            })
+            .collect();
+        Self {
+            task_sender: Some(task_sender),
+            threads,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.threads.len()
+    }
+
+    fn run_worker(task_receiver: Arc<Mutex<Receiver<WorkerTask>>>) {
+        loop {
+            let task = task_receiver.lock().unwrap().recv();
+            match task {
+                Ok(task) => task(),
+                Err(_) => break,
+            }
+        }
+    }
+
+    /// Runs each task on a worker thread, and the local task on the calling thread, then waits
+    /// for every task to finish.
+    ///
+    /// The tasks may borrow from the caller, because they are finished before this returns.  If
+    /// any task panics, the panic is resumed once the others have finished.
+    fn run_scoped(&self, tasks: Vec<ScopedTask<'_>>, local_task: impl FnOnce()) {
+        let (done_sender, done_receiver) = mpsc::channel::<thread::Result<()>>();
+        let mut task_count = 0;
+        for task in tasks {
+            let done_sender = done_sender.clone();
+            let task: ScopedTask<'_> = Box::new(move || {
+                let result = panic::catch_unwind(AssertUnwindSafe(task));
+                done_sender.send(result).ok();
+            });
+            // SAFETY: The borrows held by the task outlive it, because this function doesn't
+            // return until every sent task has run, or been dropped without running, which is
+            // detected when its `done_sender` is dropped.  The local task's panics are caught, so
+            // waiting can't be skipped by unwinding.
+            let task: WorkerTask =
+                unsafe { std::mem::transmute::<ScopedTask<'_>, WorkerTask>(task) };
+            let task_sender = self
+                .task_sender
+                .as_ref()
+                .expect("Tasks should only be sent to running workers");
+            if task_sender.send(task).is_ok() {
+                task_count += 1;
+            }
+        }
+        drop(done_sender);
+        let mut panic = panic::catch_unwind(AssertUnwindSafe(local_task)).err();
+        for _ in 0..task_count {
+            match done_receiver.recv() {
+                Ok(Ok(())) => (),
+                Ok(Err(task_panic)) => {
+                    panic.get_or_insert(task_panic);
+                }
+                Err(_) => break,
+            }
+        }
+        if let Some(panic) = panic {
+            panic::resume_unwind(panic);
+        }
+    }
+}
+
+impl Drop for Workers {
+    fn drop(&mut self) {
+        self.task_sender.take();
+        for thread in self.threads.drain(..) {
+            thread.join().ok();
+        }
+    }
+}
+
+/// Runs a [`Schedule`] on the [`World`] added by the [`EcsPlugin`].
+///
+/// Each [`Stage`] runs during its [frame phase](Stage::frame_phase()), or every stage runs on
+/// [`Event::EventsCleared`] when frame phases are disabled.  [`Stage::FixedUpdate`] consumes
+/// the [`Clock`]'s fixed steps, so it shouldn't be combined with anything else which consumes
+/// them, such as [`Game::fixed_update()`].
+///
+/// The name of the [`ActiveState`] is passed to the systems, so [`RunCriteria::in_state()`]
+/// works with a [`StateStack`] added before this plugin.
+pub struct SchedulePlugin {
+    schedule: Schedule,
+}
+
+impl SchedulePlugin {
+    /// Creates a plugin which runs the schedule.
+    pub fn new(schedule: Schedule) -> Self {
+        Self { schedule }
+    }
+
+    fn run_stage<D>(schedule: &mut Schedule, stage: Stage, context: &mut Context<D>) {
+        let active_state = context
+            .resource::<ActiveState>()
+            .ok()
+            .map(|active_state| active_state.name());
+        let run_info = |delta| {
+            let run_info = RunInfo::new(stage, delta);
+            match active_state {
+                Some(name) => run_info.with_active_state(name),
+                None => run_info,
+            }
+        };
+        if stage == Stage::FixedUpdate {
+            let Some(timestep) = context.clock().fixed_timestep() else {
+                return;
+            };
+            while context.clock_mut().consume_fixed_step() {
+                if let Ok(world) = context.resource_mut::<World>() {
+                    schedule.run_stage(world, &run_info(timestep));
+                }
+            }
+        } else {
+            let delta = context.clock().delta();
+            if let Ok(world) = context.resource_mut::<World>() {
+                schedule.run_stage(world, &run_info(delta));
+            }
+        }
+    }
+}
+
+impl<D: 'static> Plugin<D> for SchedulePlugin {
+    fn name(&self) -> &'static str {
+        "ecs_schedule"
+    }
+
+    fn dependencies(&self) -> Vec<&'static str> {
+        vec!["ecs"]
+    }
+
+    fn build(&mut self, builder: &mut PluginBuilder<D>) {
+        let mut schedule = std::mem::take(&mut self.schedule);
+        builder.add_event_handler(move |event, context, _control_flow| match event {
+            Event::EventsCleared => {
+                for stage in Stage::ALL {
+                    Self::run_stage(&mut schedule, stage, context);
+                }
+            }
+            Event::FramePhase(phase) => {
+                for stage in Stage::ALL {
+                    if stage.frame_phase() == phase {
+                        Self::run_stage(&mut schedule, stage, context);
+                    }
+                }
+            }
+            _ => (),
+        });
+    }
+}
+
+#[cfg(test)]
+mod schedule_tests {
+    use std::sync::{Arc, Mutex};
+    use std::thread::ThreadId;
+
+    use ntest::timeout;
+    use test_case::test_case;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Velocity(i32);
+
+    fn empty_system(name: &'static str) -> System {
+        System::exclusive(name, Stage::Update, |_world, _info| ())
+    }
+
+    fn update(delta: Duration) -> RunInfo {
+        RunInfo::new(Stage::Update, delta)
+    }
+
+    #[test]
+    fn should_order_systems_by_their_constraints() {
+        let schedule = ScheduleBuilder::new()
+            .with_system(empty_system("c").after("b"))
+            .with_system(empty_system("b"))
+            .with_system(empty_system("a").before("b"))
+            .with_system(System::exclusive(
+                "render",
+                Stage::Render,
+                |_world, _info| (),
+            ))
+            .build()
+            .unwrap();
+
+        assert_eq!(schedule.system_names(Stage::Update), vec!["a", "b", "c"]);
+        assert_eq!(schedule.system_names(Stage::Render), vec!["render"]);
+    }
+
+    #[test_case(
+        vec![empty_system("a"), empty_system("a")],
+        ScheduleError::Duplicate { name: "a" };
+        "duplicate systems"
+    )]
+    #[test_case(
+        vec![
+            empty_system("a").after("render"),
+            System::exclusive("render", Stage::Render, |_world, _info| ()),
+        ],
+        ScheduleError::MissingDependency { name: "a", dependency: "render" };
+        "missing dependency"
+    )]
+    #[test_case(
+        vec![empty_system("a").after("b"), empty_system("b").after("a"), empty_system("c")],
+        ScheduleError::DependencyCycle { names: vec!["a", "b"] };
+        "dependency cycle"
+    )]
+    fn should_report_schedule_errors(systems: Vec<System>, expected_error: ScheduleError) {
+        let schedule_builder = systems
+            .into_iter()
+            .fold(ScheduleBuilder::new(), ScheduleBuilder::with_system);
+
+        let error = schedule_builder.build().err().unwrap();
+
+        assert_eq!(error, expected_error);
+    }
+
+    #[test]
+    fn should_batch_systems_without_conflicting_access() {
+        let schedule = ScheduleBuilder::new()
+            .with_system(System::parallel("move", Stage::Update, |_view| ()).writes::<Position>())
+            .with_system(
+                System::parallel("accelerate", Stage::Update, |_view| ()).writes::<Velocity>(),
+            )
+            .with_system(System::parallel("draw", Stage::Update, |_view| ()).reads::<Position>())
+            .with_system(System::parallel("debug", Stage::Update, |_view| ()).reads::<Position>())
+            .with_system(empty_system("exclusive"))
+            .with_system(
+                System::parallel("follow", Stage::Update, |_view| ())
+                    .reads::<Velocity>()
+                    .after("exclusive"),
+            )
+            .with_system(
+                System::parallel("log", Stage::Update, |_view| ())
+                    .reads::<Velocity>()
+                    .after("follow"),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            schedule.batches(Stage::Update),
+            vec![
+                vec!["move", "accelerate"],
+                vec!["draw", "debug"],
+                vec!["exclusive"],
+                vec!["follow"],
+                vec!["log"],
+            ]
+        );
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_run_parallel_systems_on_multiple_threads() {
+        let thread_ids: Arc<Mutex<Vec<ThreadId>>> = Arc::default();
+        let move_thread_ids = thread_ids.clone();
+        let accelerate_thread_ids = thread_ids.clone();
+        let mut schedule = ScheduleBuilder::new()
+            .with_threads(2)
+            .with_system(
+                System::parallel("move", Stage::Update, move |view| {
+                    move_thread_ids
+                        .lock()
+                        .unwrap()
+                        .push(std::thread::current().id());
+                    view.query::<&mut Position>()
+                        .for_each(|_entity, position| position.0 += 1);
+                })
+                .writes::<Position>(),
+            )
+            .with_system(
+                System::parallel("accelerate", Stage::Update, move |view| {
+                    accelerate_thread_ids
+                        .lock()
+                        .unwrap()
+                        .push(std::thread::current().id());
+                    view.query::<&mut Velocity>()
+                        .for_each(|_entity, velocity| velocity.0 += 2);
+                })
+                .writes::<Velocity>(),
+            )
+            .build()
+            .unwrap();
+        let mut world = World::new();
+        let entity = world.spawn((Position(0), Velocity(0)));
+
+        schedule.run_stage(&mut world, &update(Duration::ZERO));
+
+        let thread_ids = thread_ids.lock().unwrap();
+        assert_eq!(thread_ids.len(), 2);
+        assert_ne!(thread_ids[0], thread_ids[1]);
+        assert_eq!(world.get::<Position>(entity), Some(&Position(1)));
+        assert_eq!(world.get::<Velocity>(entity), Some(&Velocity(2)));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_reuse_worker_threads_between_runs() {
+        let thread_ids: Arc<Mutex<Vec<ThreadId>>> = Arc::default();
+        let worker_thread_ids = thread_ids.clone();
+        let mut schedule = ScheduleBuilder::new()
+            .with_threads(2)
+            .with_system(
+                System::parallel("move", Stage::Update, |view| {
+                    view.query::<&mut Position>()
+                        .for_each(|_entity, position| position.0 += 1);
+                })
+                .writes::<Position>(),
+            )
+            .with_system(
+                System::parallel("accelerate", Stage::Update, move |_view| {
+                    worker_thread_ids
+                        .lock()
+                        .unwrap()
+                        .push(std::thread::current().id());
+                })
+                .writes::<Velocity>(),
+            )
+            .build()
+            .unwrap();
+        let mut world = World::new();
+
+        for _ in 0..3 {
+            schedule.run_stage(&mut world, &update(Duration::ZERO));
+        }
+
+        let thread_ids = thread_ids.lock().unwrap();
+        assert_eq!(thread_ids.len(), 3);
+        assert!(thread_ids.iter().all(|id| *id == thread_ids[0]));
+        assert_ne!(thread_ids[0], std::thread::current().id());
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_resume_panics_from_worker_threads() {
+        let mut schedule = ScheduleBuilder::new()
+            .with_threads(2)
+            .with_system(System::parallel("move", Stage::Update, |_view| ()).writes::<Position>())
+            .with_system(
+                System::parallel("broken", Stage::Update, |_view| panic!("System failed"))
+                    .writes::<Velocity>(),
+            )
+            .build()
+            .unwrap();
+        let mut world = World::new();
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            schedule.run_stage(&mut world, &update(Duration::ZERO))
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(schedule.threads(), 2);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            schedule.run_stage(&mut world, &update(Duration::ZERO))
+        }));
+        assert!(result.is_err(), "The worker should survive the panic.");
+    }
+
+    #[test]
+    fn should_share_read_only_components_between_systems() {
+        let mut schedule = ScheduleBuilder::new()
+            .with_threads(2)
+            .with_system(
+                System::parallel("spawn_faster", Stage::Update, |view| {
+                    let commands = view.commands();
+                    view.query::<&Velocity>().for_each(|_entity, velocity| {
+                        commands.spawn((Velocity(velocity.0 * 2),));
+                    });
+                })
+                .reads::<Velocity>(),
+            )
+            .with_system(
+                System::parallel("move", Stage::Update, |view| {
+                    view.query::<(&mut Position, &Velocity)>()
+                        .for_each(|_entity, (position, velocity)| position.0 += velocity.0);
+                })
+                .writes::<Position>()
+                .reads::<Velocity>(),
+            )
+            .build()
+            .unwrap();
+        let mut world = World::new();
+        let entity = world.spawn((Position(0), Velocity(3)));
+
+        schedule.run_stage(&mut world, &update(Duration::ZERO));
+        world.apply_commands();
+
+        assert_eq!(schedule.batches(Stage::Update).len(), 1);
+        assert_eq!(world.get::<Position>(entity), Some(&Position(3)));
+        assert_eq!(world.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "did not declare access")]
+    fn should_panic_on_undeclared_access() {
+        let mut schedule = ScheduleBuilder::new()
+            .with_system(
+                System::parallel("sneaky", Stage::Update, |view| {
+                    view.query::<(&mut Position, &Velocity)>()
+                        .for_each(|_entity, _components| ());
+                })
+                .writes::<Position>(),
+            )
+            .build()
+            .unwrap();
+        let mut world = World::new();
+        world.spawn((Position(0), Velocity(0)));
+
+        schedule.run_stage(&mut world, &update(Duration::ZERO));
+    }
+
+    #[test]
+    fn should_only_run_systems_whose_run_criteria_pass() {
+        let mut schedule = ScheduleBuilder::new()
+            .with_system(
+                System::exclusive("menu", Stage::Update, |world, _info| {
+                    world.spawn((Position(0),));
+                })
+                .with_run_criteria(RunCriteria::in_state_named("menu")),
+            )
+            .with_system(
+                System::exclusive("ticker", Stage::Update, |world, _info| {
+                    world.spawn((Velocity(0),));
+                })
+                .with_run_criteria(RunCriteria::fixed_rate(10)),
+            )
+            .build()
+            .unwrap();
+        let mut world = World::new();
+        let frame = update(Duration::from_millis(50));
+
+        for _ in 0..4 {
+            schedule.run_stage(&mut world, &frame);
+        }
+        schedule.run_stage(&mut world, &frame.with_active_state("menu"));
+
+        assert_eq!(world.query::<&Position>().count(), 1);
+        assert_eq!(world.query::<&Velocity>().count(), 2);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_run_the_schedule_as_a_plugin() {
+        let schedule = ScheduleBuilder::new()
+            .with_system(System::exclusive(
+                "spawn",
+                Stage::PreUpdate,
+                |world, _info| {
+                    if world.is_empty() {
+                        world.spawn((Position(0),));
+                    }
+                },
+            ))
+            .with_system(
+                System::parallel("move", Stage::Update, |view| {
+                    view.query::<&mut Position>()
+                        .for_each(|_entity, position| position.0 += 1);
+                })
+                .writes::<Position>(),
+            )
+            .build()
+            .unwrap();
+        let framework = FrameworkBuilder::new(())
+            .with_plugin(EcsPlugin)
+            .with_plugin(SchedulePlugin::new(schedule))
+            .build()
+            .unwrap();
+
+        framework.run(|event, context, _control_flow| {
+            if event != Event::EventsCleared {
+                return;
+            }
+            let mut has_finished = false;
+            context
+                .resource_mut::<World>()
+                .unwrap()
+                .query::<&Position>()
+                .for_each(|_entity, position| has_finished = position.0 == 3);
+            if has_finished {
+                context.quit();
+            }
+        });
+    }
+}
@@ -0,0 +1,267 @@
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+
+use crate::ecs::{Entities, Entity};
+
+/// A type-erased [`ComponentStorage`].
+pub(crate) trait AnyStorage: Any + Send {
+    fn remove_entity(&mut self, entity: Entity) -> bool;
+    fn contains(&self, entity: Entity) -> bool;
+    fn clear_trackers(&mut self);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+/// A component storage owned by a [`World`](crate::ecs::World).
+///
+/// Storages start out `Local`, and are converted to `Shared` when a system declares access to
+/// them, so they can be shared between threads by the [`Schedule`](crate::ecs::Schedule).
+pub(crate) enum StorageEntry {
+    Local(Box<dyn AnyStorage>),
+    Shared(Box<dyn AnyStorage + Send + Sync>),
+}
+
+impl StorageEntry {
+    pub fn get(&self) -> &dyn AnyStorage {
+        match self {
+            Self::Local(storage) => storage.as_ref(),
+            Self::Shared(storage) => storage.as_ref(),
+        }
+    }
+
+    pub fn get_mut(&mut self) -> &mut dyn AnyStorage {
+        match self {
+            Self::Local(storage) => storage.as_mut(),
+            Self::Shared(storage) => storage.as_mut(),
+        }
+    }
+}
+
+/// The component storages a [`Query`](crate::ecs::Query) can access.
+///
+/// Storages are removed as they are borrowed, so each one can only be borrowed mutably once.
+/// Restricted sets of storages are given to systems, and panic when a storage the system did
+/// not declare is accessed.
+///
+/// This is only `pub` so it can be used by the [`QueryData`](crate::ecs::QueryData) impls.  It
+/// can't be named outside of the crate.
+pub struct Storages<'w> {
+    entities: &'w Entities,
+    writable: HashMap<TypeId, &'w mut dyn AnyStorage>,
+    readable: HashMap<TypeId, &'w dyn AnyStorage>,
+    system: Option<&'static str>,
+}
+
+impl<'w> Storages<'w> {
+    /// Creates an unrestricted set of storages.
+    pub(crate) fn new(entities: &'w Entities) -> Self {
+        Self {
+            entities,
+            writable: HashMap::new(),
+            readable: HashMap::new(),
+            system: None,
+        }
+    }
+
+    /// Creates a set of storages restricted to the ones declared by a system.
+    pub(crate) fn restricted(entities: &'w Entities, system: &'static str) -> Self {
+        Self {
+            system: Some(system),
+            ..Self::new(entities)
+        }
+    }
+
+    pub(crate) fn add_writable(&mut self, type_id: TypeId, storage: &'w mut dyn AnyStorage) {
+        self.writable.insert(type_id, storage);
+    }
+
+    pub(crate) fn add_readable(&mut self, type_id: TypeId, storage: &'w dyn AnyStorage) {
+        self.readable.insert(type_id, storage);
+    }
+
+    pub(crate) fn entities(&self) -> &'w Entities {
+        self.entities
+    }
+
+    fn find<T: 'static>(&self) -> Option<&dyn AnyStorage> {
+        let type_id = TypeId::of::<T>();
+        let storage = match self.writable.get(&type_id) {
+            Some(storage) => Some(&**storage),
+            None => self.readable.get(&type_id).copied(),
+        };
+        if storage.is_none() {
+            self.check_declared::<T>();
+        }
+        storage
+    }
+
+    fn check_declared<T: 'static>(&self) {
+        if let Some(system) = self.system {
+            panic!(
+                "System `{}` did not declare access to `{}`",
+                system,
+                type_name::<T>()
+            );
+        }
+    }
+
+    pub(crate) fn has<T: 'static>(&self, entity: Entity) -> bool {
+        self.find::<T>()
+            .is_some_and(|storage| storage.contains(entity))
+    }
+
+    pub(crate) fn added<T: 'static>(&self, entity: Entity) -> bool {
+        self.find::<T>()
+            .and_then(|storage| storage.as_any().downcast_ref::<ComponentStorage<T>>())
+            .is_some_and(|storage| storage.added().contains(&entity))
+    }
+
+    /// Borrows the storage for `T`, returning `None` if there isn't one.
+    pub(crate) fn read<T: 'static>(&mut self) -> Option<&'w ComponentStorage<T>> {
+        let type_id = TypeId::of::<T>();
+        if let Some(storage) = self.writable.remove(&type_id) {
+            self.readable.insert(type_id, storage);
+        }
+        let storage = self.readable.get(&type_id).copied();
+        if storage.is_none() {
+            self.check_declared::<T>();
+        }
+        storage.map(|storage| {
+            storage
+                .as_any()
+                .downcast_ref()
+                .expect("Component storages should always match their type id")
+        })
+    }
+
+    /// Mutably borrows the storage for `T`, returning `None` if there isn't one.
+    pub(crate) fn write<T: 'static>(&mut self) -> Option<&'w mut ComponentStorage<T>> {
+        let type_id = TypeId::of::<T>();
+        match self.writable.remove(&type_id) {
+            Some(storage) => Some(
+                storage
+                    .as_any_mut()
+                    .downcast_mut()
+                    .expect("Component storages should always match their type id"),
+            ),
+            None if self.readable.contains_key(&type_id) => panic!(
+                "`{}` can't be borrowed mutably, because it is only readable",
+                type_name::<T>()
+            ),
+            None => {
+                self.check_declared::<T>();
+                None
+            }
+        }
+    }
+}
+
+/// Stores every component of one type, using a sparse set.
+///
+/// Components are packed together in a dense array, and the sparse array maps entity indices
+/// into it, so lookups, insertions, and removals are all `O(1)`.
+///
+/// This is only `pub` so it can be used by the [`QueryData`](crate::ecs::QueryData) impls.  It
+/// can't be named outside of the crate.
+pub struct ComponentStorage<T> {
+    sparse: Vec<Option<usize>>,
+    entities: Vec<Entity>,
+    components: Vec<T>,
+    added: Vec<Entity>,
+    removed: Vec<Entity>,
+}
+
+impl<T: 'static> ComponentStorage<T> {
+    pub fn new() -> Self {
+        Self {
+            sparse: Vec::new(),
+            entities: Vec::new(),
+            components: Vec::new(),
+            added: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+
+    fn dense_index(&self, entity: Entity) -> Option<usize> {
+        let dense_index = (*self.sparse.get(entity.index() as usize)?)?;
+        (self.entities[dense_index] == entity).then_some(dense_index)
+    }
+
+    /// Inserts the component, returning the old one, if there was one.
+    pub fn insert(&mut self, entity: Entity, component: T) -> Option<T> {
+        if let Some(dense_index) = self.dense_index(entity) {
+            return Some(std::mem::replace(
+                &mut self.components[dense_index],
+                component,
+            ));
+        }
+        let index = entity.index() as usize;
+        if index >= self.sparse.len() {
+            self.sparse.resize(index + 1, None);
+        }
+        self.sparse[index] = Some(self.entities.len());
+        self.entities.push(entity);
+        self.components.push(component);
+        self.added.push(entity);
+        None
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let dense_index = self.dense_index(entity)?;
+        self.sparse[entity.index() as usize] = None;
+        self.entities.swap_remove(dense_index);
+        let component = self.components.swap_remove(dense_index);
+        if let Some(moved_entity) = self.entities.get(dense_index) {
+            self.sparse[moved_entity.index() as usize] = Some(dense_index);
+        }
+        self.added.retain(|added| *added != entity);
+        self.removed.push(entity);
+        Some(component)
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        self.dense_index(entity)
+            .map(|dense_index| &self.components[dense_index])
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        self.dense_index(entity)
+            .map(|dense_index| &mut self.components[dense_index])
+    }
+
+    pub fn added(&self) -> &[Entity] {
+        &self.added
+    }
+
+    pub fn removed(&self) -> &[Entity] {
+        &self.removed
+    }
+}
+
+impl<T: Send + 'static> AnyStorage for ComponentStorage<T> {
+    fn remove_entity(&mut self, entity: Entity) -> bool {
+        self.remove(entity).is_some()
+    }
+
+    fn contains(&self, entity: Entity) -> bool {
+        self.dense_index(entity).is_some()
+    }
+
+    fn clear_trackers(&mut self) {
+        self.added.clear();
+        self.removed.clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
@@ -0,0 +1,398 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::ecs::storage::{AnyStorage, ComponentStorage, StorageEntry, Storages};
+use crate::ecs::*;
+
+/// Indicates a problem accessing an entity in a [`World`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum WorldError {
+    /// The entity has been despawned, or belongs to another world.
+    NoSuchEntity(Entity),
+}
+
+impl Display for WorldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSuchEntity(entity) => write!(
+                f,
+                "Entity {}v{} does not exist in this world",
+                entity.index(),
+                entity.generation()
+            ),
+        }
+    }
+}
+
+impl Error for WorldError {}
+
+/// A set of components, which can be added to an entity all at once.
+///
+/// Bundles are implemented for tuples of up to 8 components.
+pub trait Bundle: Send + 'static {
+    /// Inserts each component in the bundle into the entity.
+    #[doc(hidden)]
+    fn insert_into(self, world: &mut World, entity: Entity);
+}
+
+macro_rules! impl_bundle {
+    ($($component:ident),*) => {
+        impl<$($component: Send + 'static),*> Bundle for ($($component,)*) {
+            #[allow(non_snake_case, unused_variables)]
+            fn insert_into(self, world: &mut World, entity: Entity) {
+                let ($($component,)*) = self;
+                $(world.storage_mut_or_insert::<$component>().insert(entity, $component);)*
+            }
+        }
+    };
+}
+
+impl_bundle!();
+impl_bundle!(A);
+impl_bundle!(A, B);
+impl_bundle!(A, B, C);
+impl_bundle!(A, B, C, D);
+impl_bundle!(A, B, C, D, E);
+impl_bundle!(A, B, C, D, E, F);
+impl_bundle!(A, B, C, D, E, F, G);
+impl_bundle!(A, B, C, D, E, F, G, H);
+
+/// Stores entities, and their components.
+///
+/// Any `Send + 'static` type can be used as a component, so a world can be stored as a
+/// [resource](wolf_engine_core::Context::resource()).  Components are stored by type, so an
+/// entity can only have one component of each type.  Components accessed by
+/// [parallel systems](System::parallel()) must also be [`Sync`].
+///
+/// # Change Detection
+///
+/// The world keeps track of which components were added, and removed, since
+/// [`World::clear_trackers()`] was last called.  These can be read with [`World::added()`], and
+/// [`World::removed()`], or queried with the [`Added`] filter.
+///
+/// # Commands
+///
+/// Entities can't be spawned, or despawned, while a [`Query`] is running, so changes can be
+/// queued up using [`Commands`], instead.  Queued commands are applied, in order, by
+/// [`World::apply_commands()`].
+///
+/// [`World::end_frame()`] clears the trackers, then applies the queued commands, so it should be
+/// called once at the end of each frame.  The [`EcsPlugin`] does this automatically for the
+/// world it adds as a resource.  States with their own world should call it themselves.
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_framework::ecs::*;
+///
+/// struct Position(f32);
+/// struct Velocity(f32);
+/// struct Frozen;
+///
+/// let mut world = World::new();
+/// let moving = world.spawn((Position(0.0), Velocity(1.0)));
+/// let frozen = world.spawn((Position(0.0), Velocity(1.0), Frozen));
+///
+/// world
+///     .query_filtered::<(&mut Position, &Velocity), Without<Frozen>>()
+///     .for_each(|_entity, (position, velocity)| position.0 += velocity.0);
+///
+/// assert_eq!(world.get::<Position>(moving).unwrap().0, 1.0);
+/// assert_eq!(world.get::<Position>(frozen).unwrap().0, 0.0);
+/// ```
+pub struct World {
+    entities: Entities,
+    storages: HashMap<TypeId, StorageEntry>,
+    commands: Commands,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl World {
+    /// Creates an empty world.
+    pub fn new() -> Self {
+        Self {
+            entities: Entities::new(),
+            storages: HashMap::new(),
+            commands: Commands::default(),
+        }
+    }
+
+    /// Creates a new entity with the provided components.
+    pub fn spawn<B: Bundle>(&mut self, bundle: B) -> Entity {
+        let entity = self.entities.allocate();
+        bundle.insert_into(self, entity);
+        entity
+    }
+
+    /// Removes the entity, and all of its components.
+    ///
+    /// Returns `false` if the entity did not exist.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.entities.free(entity) {
+            return false;
+        }
+        for storage in self.storages.values_mut() {
+            storage.get_mut().remove_entity(entity);
+        }
+        true
+    }
+
+    /// Returns `true` if the entity exists in this world.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(entity)
+    }
+
+    /// Returns the number of entities in the world.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if the world has no entities.
+    pub fn is_empty(&self) -> bool {
+        self.entities.len() == 0
+    }
+
+    /// Returns every entity in the world.
+    pub fn entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter()
+    }
+
+    /// Adds a component to the entity, and returns the old component, if there was one.
+    pub fn insert<T: Send + 'static>(
+        &mut self,
+        entity: Entity,
+        component: T,
+    ) -> Result<Option<T>, WorldError> {
+        if !self.contains(entity) {
+            return Err(WorldError::NoSuchEntity(entity));
+        }
+        Ok(self.storage_mut_or_insert::<T>().insert(entity, component))
+    }
+
+    /// Removes a component from the entity, and returns it, if there was one.
+    pub fn remove<T: 'static>(&mut self, entity: Entity) -> Option<T> {
+        self.storage_mut::<T>()?.remove(entity)
+    }
+
+    /// Returns a reference to the entity's component, if it has one.
+    pub fn get<T: 'static>(&self, entity: Entity) -> Option<&T> {
+        self.storage::<T>()?.get(entity)
+    }
+
+    /// Returns a mutable reference to the entity's component, if it has one.
+    pub fn get_mut<T: 'static>(&mut self, entity: Entity) -> Option<&mut T> {
+        self.storage_mut::<T>()?.get_mut(entity)
+    }
+
+    /// Returns `true` if the entity has a component of type `T`.
+    pub fn has<T: 'static>(&self, entity: Entity) -> bool {
+        self.storages
+            .get(&TypeId::of::<T>())
+            .is_some_and(|storage| storage.get().contains(entity))
+    }
+
+    /// Returns the entities which had a component of type `T` added since the trackers were
+    /// last cleared.
+    pub fn added<T: 'static>(&self) -> &[Entity] {
+        self.storage::<T>().map_or(&[], |storage| storage.added())
+    }
+
+    /// Returns the entities which had a component of type `T` removed since the trackers were
+    /// last cleared, including entities which have been despawned.
+    pub fn removed<T: 'static>(&self) -> &[Entity] {
+        self.storage::<T>().map_or(&[], |storage| storage.removed())
+    }
+
+    /// Forgets which components were added, and removed.
+    pub fn clear_trackers(&mut self) {
+        for storage in self.storages.values_mut() {
+            storage.get_mut().clear_trackers();
+        }
+    }
+
+    /// Creates a [`Query`] over every entity with the components in `Q`.
+    pub fn query<Q: QueryData>(&mut self) -> Query<'_, Q> {
+        Query::new(self.all_storages())
+    }
+
+    /// Creates a [`Query`] over every entity with the components in `Q`, which also matches
+    /// the filter `F`.
+    pub fn query_filtered<Q: QueryData, F: QueryFilter>(&mut self) -> Query<'_, Q, F> {
+        Query::new(self.all_storages())
+    }
+
+    /// Returns a handle to the world's command queue.
+    ///
+    /// Handles are cheap to clone, and can be used while the world is being queried.
+    pub fn commands(&self) -> Commands {
+        self.commands.clone()
+    }
+
+    /// Applies the queued commands, in the order they were queued.
+    ///
+    /// Commands queued by other commands are also applied.
+    pub fn apply_commands(&mut self) {
+        loop {
+            let commands = self.commands.take();
+            if commands.is_empty() {
+                break;
+            }
+            for command in commands {
+                command(self);
+            }
+        }
+    }
+
+    /// Clears the trackers, then applies the queued commands.
+    ///
+    /// This should be called once at the end of each frame, so changes made by the commands
+    /// can be seen by the following frame.
+    pub fn end_frame(&mut self) {
+        self.clear_trackers();
+        self.apply_commands();
+    }
+
+    fn all_storages(&mut self) -> Storages<'_> {
+        let mut storages = Storages::new(&self.entities);
+        for (type_id, storage) in self.storages.iter_mut() {
+            storages.add_writable(*type_id, storage.get_mut());
+        }
+        storages
+    }
+
+    pub(crate) fn storage<T: 'static>(&self) -> Option<&ComponentStorage<T>> {
+        self.storages
+            .get(&TypeId::of::<T>())
+            .and_then(|storage| storage.get().as_any().downcast_ref())
+    }
+
+    pub(crate) fn storage_mut<T: 'static>(&mut self) -> Option<&mut ComponentStorage<T>> {
+        self.storages
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|storage| storage.get_mut().as_any_mut().downcast_mut())
+    }
+
+    pub(crate) fn storage_mut_or_insert<T: Send + 'static>(&mut self) -> &mut ComponentStorage<T> {
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| StorageEntry::Local(Box::new(ComponentStorage::<T>::new())))
+            .get_mut()
+            .as_any_mut()
+            .downcast_mut()
+            .expect("Component storages should always match their type id")
+    }
+
+    /// Makes sure the storage for `T` can be sent to other threads.
+    pub(crate) fn share_storage<T: Send + Sync + 'static>(&mut self) {
+        let type_id = TypeId::of::<T>();
+        let storage: Box<dyn AnyStorage + Send + Sync> = match self.storages.remove(&type_id) {
+            Some(StorageEntry::Shared(storage)) => storage,
+            Some(StorageEntry::Local(storage)) => storage
+                .into_any()
+                .downcast::<ComponentStorage<T>>()
+                .expect("Component storages should always match their type id"),
+            None => Box::new(ComponentStorage::<T>::new()),
+        };
+        self.storages.insert(type_id, StorageEntry::Shared(storage));
+    }
+
+    /// Returns the entities, along with every storage which can be sent to other threads.
+    pub(crate) fn shared_storages_mut(
+        &mut self,
+    ) -> (
+        &Entities,
+        Vec<(TypeId, &mut (dyn AnyStorage + Send + Sync))>,
+    ) {
+        let storages = self
+            .storages
+            .iter_mut()
+            .filter_map(|(type_id, storage)| match storage {
+                StorageEntry::Shared(storage) => Some((*type_id, storage.as_mut())),
+                StorageEntry::Local(_) => None,
+            })
+            .collect();
+        (&self.entities, storages)
+    }
+}
+
+#[cfg(test)]
+mod world_tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Name(&'static str);
+
+    #[test]
+    fn should_spawn_and_despawn_entities() {
+        let mut world = World::new();
+        let entity = world.spawn((Position(1), Name("player")));
+
+        assert_eq!(world.get::<Position>(entity), Some(&Position(1)));
+        assert!(world.despawn(entity));
+
+        assert!(!world.contains(entity));
+        assert_eq!(world.get::<Position>(entity), None);
+        assert!(world.is_empty());
+    }
+
+    #[test]
+    fn should_not_affect_new_entities_with_stale_handles() {
+        let mut world = World::new();
+        let old_entity = world.spawn((Position(1),));
+        world.despawn(old_entity);
+        let new_entity = world.spawn((Position(2),));
+
+        assert_eq!(old_entity.index(), new_entity.index());
+        assert_eq!(world.get::<Position>(old_entity), None);
+        assert!(!world.despawn(old_entity));
+        assert_eq!(
+            world.insert(old_entity, Name("ghost")),
+            Err(WorldError::NoSuchEntity(old_entity))
+        );
+        assert_eq!(world.get::<Position>(new_entity), Some(&Position(2)));
+    }
+
+    #[test]
+    fn should_insert_and_remove_components() {
+        let mut world = World::new();
+        let entity = world.spawn(());
+
+        assert_eq!(world.insert(entity, Position(1)), Ok(None));
+        assert_eq!(world.insert(entity, Position(2)), Ok(Some(Position(1))));
+        world.get_mut::<Position>(entity).unwrap().0 += 1;
+
+        assert_eq!(world.remove::<Position>(entity), Some(Position(3)));
+        assert!(!world.has::<Position>(entity));
+    }
+
+    #[test]
+    fn should_track_added_and_removed_components() {
+        let mut world = World::new();
+        let first = world.spawn((Position(1),));
+        let second = world.spawn((Position(2), Name("second")));
+        world.clear_trackers();
+
+        let third = world.spawn((Position(3),));
+        world.remove::<Position>(first);
+        world.despawn(second);
+
+        assert_eq!(world.added::<Position>(), &[third]);
+        assert_eq!(world.removed::<Position>(), &[first, second]);
+        assert_eq!(world.removed::<Name>(), &[second]);
+
+        world.clear_trackers();
+
+        assert!(world.added::<Position>().is_empty());
+        assert!(world.removed::<Position>().is_empty());
+    }
+}
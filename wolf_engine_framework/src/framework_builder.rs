@@ -0,0 +1,340 @@
+use wolf_engine_core::prelude::*;
+
+use crate::plugins::PluginRegistry;
+use crate::*;
+
+/// Provides a way to add [`Plugin`]s to the engine, and build a ready-to-run [`Framework`].
+///
+/// Errors, such as duplicate plugins, or missing dependencies, are reported by
+/// [`FrameworkBuilder::build()`].
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_framework::*;
+/// use wolf_engine_core::prelude::*;
+///
+/// struct QuitPlugin;
+///
+/// impl Plugin<u32> for QuitPlugin {
+///     fn build(&mut self, builder: &mut PluginBuilder<u32>) {
+///         builder.add_event_handler(|event, context, _control_flow| {
+///             if event == Event::EventsCleared {
+///                 context.data += 1;
+///                 if context.data == 3 {
+///                     context.quit();
+///                 }
+///             }
+///         });
+///     }
+/// }
+///
+/// let framework = FrameworkBuilder::new(0)
+///     .with_plugin(QuitPlugin)
+///     .build()
+///     .expect("Failed to build the framework");
+///
+/// let exit_code = framework.run(|_event, _context, _control_flow| ());
+///
+/// assert_eq!(exit_code, 0);
+/// ```
+pub struct FrameworkBuilder<D> {
+    engine_builder: EngineBuilder<D>,
+    plugins: PluginRegistry<D>,
+    plugin_error: Option<PluginError>,
+    #[cfg(feature = "config")]
+    config: Option<wolf_engine_core::config::Config>,
+}
+
+impl<D> FrameworkBuilder<D> {
+    /// Creates a new builder, with the default engine options, and the provided data.
+    pub fn new(data: D) -> Self {
+        Self::from_engine_builder(EngineBuilder::new(data))
+    }
+
+    /// Creates a new builder, which uses the provided [`EngineBuilder`]'s options.
+    pub fn from_engine_builder(engine_builder: EngineBuilder<D>) -> Self {
+        Self {
+            engine_builder,
+            plugins: PluginRegistry::new(),
+            plugin_error: None,
+            #[cfg(feature = "config")]
+            config: None,
+        }
+    }
+
+    /// Adds a [`Plugin`].
+    ///
+    /// Adding more than one plugin with the same name is reported by
+    /// [`FrameworkBuilder::build()`].
+    pub fn with_plugin<P: Plugin<D> + 'static>(mut self, plugin: P) -> Self {
+        if let Err(error) = self.plugins.add(Box::new(plugin)) {
+            self.plugin_error.get_or_insert(error);
+        }
+        self
+    }
+
+    /// Provides a [`Config`](wolf_engine_core::config::Config), which plugins can read from
+    /// while they are being built.
+    ///
+    /// The engine settings are applied using
+    /// [`EngineBuilder::with_config()`](wolf_engine_core::EngineBuilder::with_config()).
+    #[cfg(feature = "config")]
+    pub fn with_config(mut self, config: wolf_engine_core::config::Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Builds the plugins in dependency order, then builds the engine.
+    pub fn build(self) -> Result<Framework<D>, PluginError> {
+        if let Some(error) = self.plugin_error {
+            return Err(error);
+        }
+        let mut plugins = self.plugins.into_sorted()?;
+        let plugin_names = plugins.iter().map(|plugin| plugin.name()).collect();
+        let mut plugin_builder = PluginBuilder::new(self.engine_builder);
+        #[cfg(feature = "config")]
+        if let Some(config) = self.config {
+            plugin_builder.set_config(config);
+        }
+        for plugin in plugins.iter_mut() {
+            plugin.build(&mut plugin_builder);
+        }
+        let (engine_builder, event_handlers) = plugin_builder.finish();
+        let (event_loop, context) = engine_builder.build()?;
+        Ok(Framework {
+            event_loop,
+            context,
+            event_handlers,
+            plugin_names,
+        })
+    }
+}
+
+/// A built engine, with its [`Plugin`]s, which is ready to run.
+pub struct Framework<D> {
+    event_loop: EventLoop,
+    context: Context<D>,
+    event_handlers: Vec<PluginEventHandler<D>>,
+    plugin_names: Vec<&'static str>,
+}
+
+impl<D> Framework<D> {
+    /// Returns the names of the plugins, in the order they were built.
+    pub fn plugin_names(&self) -> Vec<&'static str> {
+        self.plugin_names.clone()
+    }
+
+    /// Returns the engine's [`Context`].
+    pub fn context(&self) -> &Context<D> {
+        &self.context
+    }
+
+    /// Returns the engine's [`Context`] mutably.
+    pub fn context_mut(&mut self) -> &mut Context<D> {
+        &mut self.context
+    }
+
+    /// Runs the engine using [`EventLoop::run()`], and returns the exit code.
+    ///
+    /// Each event is passed to the plugins' event handlers, in order, and then to the provided
+    /// event handler.
+    pub fn run<F>(self, mut event_handler: F) -> i32
+    where
+        F: FnMut(Event, &mut Context<D>, &mut ControlFlow),
+    {
+        let mut event_handlers = self.event_handlers;
+        self.event_loop
+            .run(self.context, move |event, context, control_flow| {
+                for plugin_event_handler in event_handlers.iter_mut() {
+                    plugin_event_handler(event, context, control_flow);
+                }
+                event_handler(event, context, control_flow);
+            })
+    }
+}
+
+#[cfg(test)]
+mod framework_builder_tests {
+    use ntest::timeout;
+    use test_case::test_case;
+    use wolf_engine_core::testing::CallLog;
+
+    use super::*;
+
+    struct TestPlugin {
+        name: &'static str,
+        dependencies: Vec<&'static str>,
+        log: CallLog,
+    }
+
+    impl TestPlugin {
+        fn new(name: &'static str, dependencies: Vec<&'static str>, log: &CallLog) -> Self {
+            Self {
+                name,
+                dependencies,
+                log: log.clone(),
+            }
+        }
+    }
+
+    impl Plugin<()> for TestPlugin {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn dependencies(&self) -> Vec<&'static str> {
+            self.dependencies.clone()
+        }
+
+        fn build(&mut self, builder: &mut PluginBuilder<()>) {
+            self.log.push(format!("{} build", self.name));
+            let name = self.name;
+            let log = self.log.clone();
+            builder.add_event_handler(move |event, context, _control_flow| {
+                if event == Event::EventsCleared {
+                    log.push(format!("{} event", name));
+                    context.quit();
+                }
+            });
+        }
+    }
+
+    struct ResourcePlugin;
+
+    impl Plugin<()> for ResourcePlugin {
+        fn build(&mut self, builder: &mut PluginBuilder<()>) {
+            builder.insert_resource(7_u32);
+        }
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_build_and_run_plugins_in_dependency_order() {
+        let log = CallLog::new();
+        let framework = FrameworkBuilder::new(())
+            .with_plugin(TestPlugin::new("renderer", vec!["window"], &log))
+            .with_plugin(TestPlugin::new("window", vec![], &log))
+            .with_plugin(TestPlugin::new("audio", vec![], &log))
+            .build()
+            .unwrap();
+        let game_log = log.clone();
+
+        framework.run(move |event, _context, _control_flow| {
+            if event == Event::EventsCleared {
+                game_log.push("game event");
+            }
+        });
+
+        assert_eq!(
+            log.messages(),
+            vec![
+                "window build",
+                "renderer build",
+                "audio build",
+                "window event",
+                "renderer event",
+                "audio event",
+                "game event",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_insert_plugin_resources() {
+        let framework = FrameworkBuilder::new(())
+            .with_plugin(ResourcePlugin)
+            .build()
+            .unwrap();
+
+        assert_eq!(*framework.context().resource::<u32>().unwrap(), 7);
+    }
+
+    #[test_case(
+        vec![("a", vec![]), ("a", vec![])],
+        PluginError::Duplicate { name: "a" } ;
+        "duplicate plugins"
+    )]
+    #[test_case(
+        vec![("a", vec!["b"])],
+        PluginError::MissingDependency { name: "a", dependency: "b" } ;
+        "missing dependency"
+    )]
+    #[test_case(
+        vec![("a", vec!["b"]), ("b", vec!["a"]), ("c", vec![])],
+        PluginError::DependencyCycle { names: vec!["a", "b"] } ;
+        "dependency cycle"
+    )]
+    fn should_report_plugin_errors(
+        plugins: Vec<(&'static str, Vec<&'static str>)>,
+        expected_error: PluginError,
+    ) {
+        let log = CallLog::new();
+        let builder = plugins.into_iter().fold(
+            FrameworkBuilder::new(()),
+            |builder, (name, dependencies)| {
+                builder.with_plugin(TestPlugin::new(name, dependencies, &log))
+            },
+        );
+
+        let error = builder.build().err();
+
+        assert_eq!(error, Some(expected_error));
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn should_report_engine_errors() {
+        let error = FrameworkBuilder::new(())
+            .with_plugin(ResourcePlugin)
+            .with_plugin(DuplicateResourcePlugin)
+            .build()
+            .err();
+
+        assert_eq!(
+            error,
+            Some(PluginError::Engine(EngineBuilderError::DuplicateResource {
+                type_name: "u32"
+            }))
+        );
+    }
+
+    struct DuplicateResourcePlugin;
+
+    impl Plugin<()> for DuplicateResourcePlugin {
+        fn build(&mut self, builder: &mut PluginBuilder<()>) {
+            builder.insert_resource(8_u32);
+        }
+    }
+
+    #[cfg(feature = "config")]
+    struct ConfigPlugin;
+
+    #[cfg(feature = "config")]
+    impl Plugin<()> for ConfigPlugin {
+        fn build(&mut self, builder: &mut PluginBuilder<()>) {
+            let has_audio_section = builder.config().unwrap().has_section("audio");
+            builder.insert_resource(has_audio_section);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "config")]
+    fn should_provide_the_config_to_plugins() {
+        let config = wolf_engine_core::config::ConfigLoader::new()
+            .with_defaults("[audio]\nvolume = 0.5\n[engine]\nfixed_rate = 30")
+            .load()
+            .unwrap();
+
+        let framework = FrameworkBuilder::new(())
+            .with_config(config)
+            .with_plugin(ConfigPlugin)
+            .build()
+            .unwrap();
+
+        assert!(*framework.context().resource::<bool>().unwrap());
+        assert!(framework
+            .context()
+            .has_resource::<wolf_engine_core::config::Config>());
+    }
+}
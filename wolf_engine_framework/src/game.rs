@@ -0,0 +1,322 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use wolf_engine_core::prelude::*;
+
+use crate::*;
+
+/// The number of fixed updates per second used by [`run()`].
+pub const DEFAULT_FIXED_RATE: u32 = 60;
+
+/// Indicates why [`run()`] did not finish successfully.
+#[derive(Debug, PartialEq, Clone)]
+pub enum RunError {
+    /// The framework could not be built.
+    Build(PluginError),
+    /// The game exited with a non-zero exit code.
+    Exit(i32),
+}
+
+impl Display for RunError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Build(error) => write!(f, "{}", error),
+            Self::Exit(exit_code) => write!(f, "The game exited with code {}", exit_code),
+        }
+    }
+}
+
+impl Error for RunError {}
+
+impl From<PluginError> for RunError {
+    fn from(error: PluginError) -> Self {
+        Self::Build(error)
+    }
+}
+
+/// Provides a simple, callback-based interface for writing a game, which is run by [`run()`].
+///
+/// Every frame, the game is given any number of fixed updates, to catch up to the
+/// [fixed rate](DEFAULT_FIXED_RATE), followed by a single variable update, and a render.  Every
+/// other event is passed to [`Game::on_event()`].
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_framework::*;
+/// use wolf_engine_core::prelude::*;
+/// use std::time::Duration;
+///
+/// struct MyGame {
+///     frames: u32,
+/// }
+///
+/// impl Game for MyGame {
+///     fn update(&mut self, context: &mut Context<()>, _delta: Duration) {
+///         self.frames += 1;
+///         if self.frames == 3 {
+///             context.quit();
+///         }
+///     }
+///
+///     fn render(&mut self, _context: &mut Context<()>, _alpha: f32) {
+///         println!("Frame {}", self.frames);
+///     }
+/// }
+///
+/// wolf_engine_framework::run(MyGame { frames: 0 }).expect("The game failed");
+/// ```
+pub trait Game: 'static {
+    /// Adds plugins, or changes other options, before the framework is built.
+    ///
+    /// The [default plugins](add_default_plugins()) have already been added.
+    fn configure(&mut self, builder: FrameworkBuilder<()>) -> FrameworkBuilder<()> {
+        builder
+    }
+
+    /// Called once, after the engine has been built, and before the first frame.
+    fn setup(&mut self, _context: &mut Context<()>) {}
+
+    /// Called once per frame, with the time since the last frame.
+    fn update(&mut self, _context: &mut Context<()>, _delta: Duration) {}
+
+    /// Called at a fixed rate, zero, or more, times per frame, before [`Game::update()`].
+    fn fixed_update(&mut self, _context: &mut Context<()>) {}
+
+    /// Called once per frame, after [`Game::update()`].
+    ///
+    /// `alpha` is the fraction of a fixed step left over after the fixed updates, which can be
+    /// used to interpolate between fixed states.
+    fn render(&mut self, _context: &mut Context<()>, _alpha: f32) {}
+
+    /// Called for every event, other than [`Event::EventsCleared`].
+    fn on_event(&mut self, _event: Event, _context: &mut Context<()>) {}
+
+    /// Returns the exit code reported once the game has quit.
+    fn exit_code(&self) -> i32 {
+        0
+    }
+}
+
+/// Adds the plugins enabled by the crate's features:
+///
+/// - `logging`: [`LoggingPlugin`].
+pub fn add_default_plugins<D: 'static>(builder: FrameworkBuilder<D>) -> FrameworkBuilder<D> {
+    #[cfg(feature = "logging")]
+    let builder = builder.with_plugin(LoggingPlugin::default());
+    builder
+}
+
+/// Builds the engine, with the [default plugins](add_default_plugins()), then runs the game
+/// until it quits.
+///
+/// Returns [`RunError::Exit`] if the game exits with a non-zero exit code.
+pub fn run<G: Game>(mut game: G) -> Result<(), RunError> {
+    let builder = FrameworkBuilder::from_engine_builder(
+        EngineBuilder::new(()).with_fixed_rate(DEFAULT_FIXED_RATE),
+    );
+    let mut framework = game.configure(add_default_plugins(builder)).build()?;
+    game.setup(framework.context_mut());
+    let exit_code = framework.run(|event, context, _control_flow| match event {
+        Event::EventsCleared => {
+            while context.clock_mut().consume_fixed_step() {
+                game.fixed_update(context);
+            }
+            let delta = context.clock().delta();
+            game.update(context, delta);
+            let alpha = context.clock().fixed_alpha();
+            game.render(context, alpha);
+        }
+        event => game.on_event(event, context),
+    });
+    let exit_code = match exit_code {
+        0 => game.exit_code(),
+        exit_code => exit_code,
+    };
+    match exit_code {
+        0 => Ok(()),
+        exit_code => Err(RunError::Exit(exit_code)),
+    }
+}
+
+/// Initializes the built-in logger, using
+/// [`initialize_logging()`](wolf_engine_core::logging::initialize_logging()).
+///
+/// Logging is only initialized once per process, so adding the plugin more than once is
+/// harmless.  When the `config` feature is enabled, and a config was provided to the
+/// [`FrameworkBuilder`], the engine's log settings are read from it.
+#[cfg(feature = "logging")]
+pub struct LoggingPlugin {
+    log_level: wolf_engine_core::logging::LogLevel,
+}
+
+#[cfg(feature = "logging")]
+impl Default for LoggingPlugin {
+    fn default() -> Self {
+        Self::new(wolf_engine_core::logging::LogLevel::Info)
+    }
+}
+
+#[cfg(feature = "logging")]
+impl LoggingPlugin {
+    /// Creates a logging plugin, which uses the provided default level.
+    pub fn new(log_level: wolf_engine_core::logging::LogLevel) -> Self {
+        Self { log_level }
+    }
+}
+
+#[cfg(feature = "logging")]
+impl<D> Plugin<D> for LoggingPlugin {
+    fn name(&self) -> &'static str {
+        "logging"
+    }
+
+    fn build(&mut self, _builder: &mut PluginBuilder<D>) {
+        use wolf_engine_core::logging::*;
+        static INITIALIZE_LOGGING: std::sync::Once = std::sync::Once::new();
+
+        let log_level = self.log_level;
+        #[cfg(feature = "config")]
+        let filter = _builder
+            .config()
+            .and_then(|config| config.engine().ok())
+            .and_then(|settings| LogFilter::from_engine_settings(&settings).ok());
+        INITIALIZE_LOGGING.call_once(|| {
+            #[cfg(feature = "config")]
+            if let Some(filter) = filter {
+                let filter = filter.clone().with_env_var(LOG_ENV_VAR).unwrap_or(filter);
+                return initialize_logging_with_filter(filter);
+            }
+            initialize_logging(log_level);
+        });
+    }
+}
+
+#[cfg(test)]
+mod game_tests {
+    use ntest::timeout;
+    use wolf_engine_core::testing::CallLog;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct TestGame {
+        log: CallLog,
+        updates: u32,
+        exit_code: i32,
+    }
+
+    impl Game for TestGame {
+        fn configure(&mut self, builder: FrameworkBuilder<()>) -> FrameworkBuilder<()> {
+            self.log.push("configure");
+            builder
+        }
+
+        fn setup(&mut self, _context: &mut Context<()>) {
+            self.log.push("setup");
+        }
+
+        fn update(&mut self, context: &mut Context<()>, _delta: Duration) {
+            self.log.push("update");
+            self.updates += 1;
+            if self.updates == 2 {
+                context.quit();
+            }
+        }
+
+        fn render(&mut self, _context: &mut Context<()>, alpha: f32) {
+            assert!((0.0..=1.0).contains(&alpha));
+            self.log.push("render");
+        }
+
+        fn on_event(&mut self, event: Event, _context: &mut Context<()>) {
+            if event == Event::Quit {
+                self.log.push("quit");
+            }
+        }
+
+        fn exit_code(&self) -> i32 {
+            self.exit_code
+        }
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_run_the_game_callbacks_in_order() {
+        let game = TestGame::default();
+        let log = game.log.clone();
+
+        let result = run(game);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            log.messages(),
+            vec![
+                "configure",
+                "setup",
+                "update",
+                "render",
+                "update",
+                "render",
+                "quit"
+            ]
+        );
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_report_non_zero_exit_codes() {
+        let game = TestGame {
+            exit_code: 3,
+            ..Default::default()
+        };
+
+        let result = run(game);
+
+        assert_eq!(result, Err(RunError::Exit(3)));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_run_fixed_updates() {
+        struct FixedGame(u32);
+
+        impl Game for FixedGame {
+            fn update(&mut self, context: &mut Context<()>, _delta: Duration) {
+                context
+                    .clock_mut()
+                    .tick_by(Duration::from_secs(1) / DEFAULT_FIXED_RATE * 2);
+            }
+
+            fn fixed_update(&mut self, context: &mut Context<()>) {
+                self.0 += 1;
+                if self.0 >= 4 {
+                    context.quit();
+                }
+            }
+        }
+
+        assert_eq!(run(FixedGame(0)), Ok(()));
+    }
+
+    #[test]
+    fn should_report_build_errors() {
+        struct BrokenGame;
+
+        impl Game for BrokenGame {
+            fn configure(&mut self, builder: FrameworkBuilder<()>) -> FrameworkBuilder<()> {
+                builder
+                    .with_plugin(StateStack::<()>::new())
+                    .with_plugin(StateStack::<()>::new())
+            }
+        }
+
+        assert_eq!(
+            run(BrokenGame),
+            Err(RunError::Build(PluginError::Duplicate {
+                name: "state_stack"
+            }))
+        );
+    }
+}
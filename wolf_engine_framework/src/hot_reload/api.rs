@@ -0,0 +1,324 @@
+use std::any::type_name;
+use std::ffi::c_void;
+use std::mem::{align_of, size_of};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use wolf_engine_core::prelude::*;
+
+/// The version of the [`GameLogicApi`] layout.
+///
+/// Libraries built against a different ABI version are refused.
+pub const ABI_VERSION: u32 = 2;
+
+/// The name of the function exported by [`export_game_logic!`](crate::export_game_logic!).
+pub const ENTRY_POINT: &str = "wolf_engine_game_logic";
+
+/// Game logic which can be compiled into a dynamic library, and hot-reloaded by a
+/// [`GameLibrary`](crate::hot_reload::GameLibrary).
+///
+/// Game logic is stateless: everything which needs to survive a reload must be stored in the
+/// [`Context`], which is owned by the host.  Logic is exported from the library using
+/// [`export_game_logic!`](crate::export_game_logic!).
+///
+/// # State Migration
+///
+/// The [state version](GameLogic::STATE_VERSION) describes how the logic interprets the
+/// context's data.  When a reloaded library reports a different state version,
+/// [`GameLogic::migrate_state()`] is called on the new logic before it is used.  If migration
+/// fails, the old library is kept running, so migrations should not leave the data
+/// half-changed.
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_core::prelude::*;
+/// use wolf_engine_framework::hot_reload::*;
+///
+/// pub struct GameData {
+///     pub score: u32,
+/// }
+///
+/// pub struct MyGameLogic;
+///
+/// impl GameLogic<GameData> for MyGameLogic {
+///     const STATE_VERSION: u32 = 2;
+///
+///     fn handle_event(event: Event, context: &mut Context<GameData>) {
+///         if event == Event::EventsCleared {
+///             context.data.score += 1;
+///         }
+///     }
+///
+///     fn migrate_state(data: &mut GameData, from_version: u32) -> Result<(), String> {
+///         match from_version {
+///             // Version 1 counted score in tens.
+///             1 => data.score *= 10,
+///             _ => return Err(format!("Unknown state version: {}", from_version)),
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// wolf_engine_framework::export_game_logic!(GameData, MyGameLogic);
+/// ```
+pub trait GameLogic<D> {
+    /// The version of the state layout used by this logic.
+    const STATE_VERSION: u32 = 0;
+
+    /// Called after the library is loaded, or reloaded.
+    fn on_load(_context: &mut Context<D>) {}
+
+    /// Called before the library is unloaded.
+    ///
+    /// Anything the library put into the context which refers to its code, or static data,
+    /// must be removed here, because it dangles once the library is unloaded.  That includes
+    /// boxed trait objects, closures, function pointers, and `&'static str`s, or other
+    /// `'static` references, created by the library, such as string literals.  Store owned
+    /// values, such as a [`String`], instead.
+    fn on_unload(_context: &mut Context<D>) {}
+
+    /// Handles an event.
+    fn handle_event(event: Event, context: &mut Context<D>);
+
+    /// Converts the data from an older, or newer, state version to
+    /// [`GameLogic::STATE_VERSION`].
+    ///
+    /// By default, migration always fails.
+    fn migrate_state(_data: &mut D, from_version: u32) -> Result<(), String> {
+        Err(format!(
+            "No migration from state version {} to {}",
+            from_version,
+            Self::STATE_VERSION
+        ))
+    }
+}
+
+/// A string which is passed across the library boundary.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RawStr {
+    pointer: *const u8,
+    len: usize,
+}
+
+// Safety: A `RawStr` is only created from a `&'static str`, which can be read from any thread.
+unsafe impl Send for RawStr {}
+
+impl RawStr {
+    fn new(string: &'static str) -> Self {
+        Self {
+            pointer: string.as_ptr(),
+            len: string.len(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The string must still be valid, which means the library it came from must still be
+    /// loaded.
+    unsafe fn to_string(self) -> String {
+        let bytes = std::slice::from_raw_parts(self.pointer, self.len);
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+/// The table of functions, and metadata, exported by a game library.
+///
+/// This is the ABI boundary between the host, and the library.  It is `#[repr(C)]`, and
+/// starts with the [`ABI_VERSION`], which must stay the first field in future versions, so
+/// incompatible libraries can be detected.
+///
+/// The context, data, and events are passed by pointer, so the host and library must be built
+/// with the same compiler, the same version, and features, of Wolf Engine, and the same global
+/// allocator.
+/// Every call into the library catches panics, so a panicking library can't unwind into the
+/// host.
+///
+/// Use [`export_game_logic!`](crate::export_game_logic!) instead of creating this directly.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct GameLogicApi {
+    abi_version: u32,
+    state_version: u32,
+    engine_version: RawStr,
+    data_type: RawStr,
+    data_size: usize,
+    data_align: usize,
+    context_size: usize,
+    context_align: usize,
+    on_load: unsafe extern "C" fn(*mut c_void) -> bool,
+    on_unload: unsafe extern "C" fn(*mut c_void) -> bool,
+    handle_event: unsafe extern "C" fn(*const c_void, *mut c_void) -> bool,
+    migrate_state: unsafe extern "C" fn(*mut c_void, u32, *mut c_void) -> bool,
+}
+
+impl GameLogicApi {
+    /// Creates the function table for some [`GameLogic`].
+    pub fn new<D, L: GameLogic<D>>() -> Self {
+        Self {
+            abi_version: ABI_VERSION,
+            state_version: L::STATE_VERSION,
+            engine_version: RawStr::new(env!("CARGO_PKG_VERSION")),
+            data_type: RawStr::new(type_name::<D>()),
+            data_size: size_of::<D>(),
+            data_align: align_of::<D>(),
+            context_size: size_of::<Context<D>>(),
+            context_align: align_of::<Context<D>>(),
+            on_load: on_load::<D, L>,
+            on_unload: on_unload::<D, L>,
+            handle_event: handle_event::<D, L>,
+            migrate_state: migrate_state::<D, L>,
+        }
+    }
+
+    pub(crate) fn abi_version(&self) -> u32 {
+        self.abi_version
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_abi_version(mut self, abi_version: u32) -> Self {
+        self.abi_version = abi_version;
+        self
+    }
+
+    pub(crate) fn state_version(&self) -> u32 {
+        self.state_version
+    }
+
+    /// Returns the engine version, and data type name, the library was built with.
+    ///
+    /// # Safety
+    ///
+    /// The library the table came from must still be loaded.
+    pub(crate) unsafe fn build_info(&self) -> (String, String) {
+        (self.engine_version.to_string(), self.data_type.to_string())
+    }
+
+    pub(crate) fn data_layout(&self) -> (usize, usize) {
+        (self.data_size, self.data_align)
+    }
+
+    pub(crate) fn context_layout(&self) -> (usize, usize) {
+        (self.context_size, self.context_align)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_context_layout(mut self, size: usize, align: usize) -> Self {
+        self.context_size = size;
+        self.context_align = align;
+        self
+    }
+
+    /// # Safety
+    ///
+    /// The library must still be loaded, and be compatible with `D`.
+    pub(crate) unsafe fn on_load<D>(&self, context: &mut Context<D>) -> bool {
+        (self.on_load)(context as *mut Context<D> as *mut c_void)
+    }
+
+    /// # Safety
+    ///
+    /// The library must still be loaded, and be compatible with `D`.
+    pub(crate) unsafe fn on_unload<D>(&self, context: &mut Context<D>) -> bool {
+        (self.on_unload)(context as *mut Context<D> as *mut c_void)
+    }
+
+    /// # Safety
+    ///
+    /// The library must still be loaded, and be compatible with `D`.
+    pub(crate) unsafe fn handle_event<D>(&self, event: Event, context: &mut Context<D>) -> bool {
+        (self.handle_event)(
+            &event as *const Event as *const c_void,
+            context as *mut Context<D> as *mut c_void,
+        )
+    }
+
+    /// # Safety
+    ///
+    /// The library must still be loaded, and be compatible with `D`.
+    pub(crate) unsafe fn migrate_state<D>(
+        &self,
+        data: &mut D,
+        from_version: u32,
+    ) -> Result<(), String> {
+        let mut error = String::new();
+        let is_migrated = (self.migrate_state)(
+            data as *mut D as *mut c_void,
+            from_version,
+            &mut error as *mut String as *mut c_void,
+        );
+        match is_migrated {
+            true => Ok(()),
+            false => Err(error),
+        }
+    }
+}
+
+unsafe extern "C" fn on_load<D, L: GameLogic<D>>(context: *mut c_void) -> bool {
+    let context = &mut *(context as *mut Context<D>);
+    catch_unwind(AssertUnwindSafe(|| L::on_load(context))).is_ok()
+}
+
+unsafe extern "C" fn on_unload<D, L: GameLogic<D>>(context: *mut c_void) -> bool {
+    let context = &mut *(context as *mut Context<D>);
+    catch_unwind(AssertUnwindSafe(|| L::on_unload(context))).is_ok()
+}
+
+unsafe extern "C" fn handle_event<D, L: GameLogic<D>>(
+    event: *const c_void,
+    context: *mut c_void,
+) -> bool {
+    let event = *(event as *const Event);
+    let context = &mut *(context as *mut Context<D>);
+    catch_unwind(AssertUnwindSafe(|| L::handle_event(event, context))).is_ok()
+}
+
+unsafe extern "C" fn migrate_state<D, L: GameLogic<D>>(
+    data: *mut c_void,
+    from_version: u32,
+    error: *mut c_void,
+) -> bool {
+    let data = &mut *(data as *mut D);
+    let error = &mut *(error as *mut String);
+    match catch_unwind(AssertUnwindSafe(|| L::migrate_state(data, from_version))) {
+        Ok(Ok(())) => true,
+        Ok(Err(message)) => {
+            error.push_str(&message);
+            false
+        }
+        Err(_) => {
+            error.push_str("The migration panicked");
+            false
+        }
+    }
+}
+
+/// Exports [`GameLogic`] from a dynamic library, so it can be loaded by a
+/// [`GameLibrary`](crate::hot_reload::GameLibrary).
+///
+/// The library's crate must be built as a `cdylib`, and only one set of logic can be exported
+/// per library.
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_core::prelude::*;
+/// use wolf_engine_framework::hot_reload::*;
+///
+/// pub struct MyGameLogic;
+///
+/// impl GameLogic<()> for MyGameLogic {
+///     fn handle_event(event: Event, context: &mut Context<()>) {}
+/// }
+///
+/// wolf_engine_framework::export_game_logic!((), MyGameLogic);
+/// ```
+#[macro_export]
+macro_rules! export_game_logic {
+    ($data:ty, $logic:ty) => {
+        #[no_mangle]
+        pub extern "C" fn wolf_engine_game_logic() -> $crate::hot_reload::GameLogicApi {
+            $crate::hot_reload::GameLogicApi::new::<$data, $logic>()
+        }
+    };
+}
@@ -0,0 +1,654 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::mem::{align_of, size_of};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use libloading::Library;
+use wolf_engine_core::prelude::*;
+
+use crate::assets::FileWatcher;
+use crate::hot_reload::*;
+
+static NEXT_COPY: AtomicU64 = AtomicU64::new(0);
+
+/// Indicates why game logic could not be loaded, or reloaded.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum HotReloadError {
+    /// The library could not be copied before loading it.
+    Io {
+        /// The path of the library.
+        path: PathBuf,
+        /// A description of the error.
+        message: String,
+    },
+    /// The library could not be loaded, or doesn't export any game logic.
+    Load {
+        /// The path of the library.
+        path: PathBuf,
+        /// A description of the error.
+        message: String,
+    },
+    /// The library was built against a different ABI, engine version, or data type.
+    Incompatible {
+        /// The path of the library.
+        path: PathBuf,
+        /// A description of the difference.
+        reason: String,
+    },
+    /// The new logic could not migrate the state from the old logic's state version.
+    Migration {
+        /// The path of the library.
+        path: PathBuf,
+        /// The state version of the old logic.
+        from_version: u32,
+        /// The state version of the new logic.
+        to_version: u32,
+        /// A description of the error.
+        message: String,
+    },
+    /// The library panicked while it was being loaded.
+    Panicked {
+        /// The path of the library.
+        path: PathBuf,
+    },
+}
+
+impl Display for HotReloadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { path, message } => write!(f, "Failed to copy {:?}: {}", path, message),
+            Self::Load { path, message } => write!(f, "Failed to load {:?}: {}", path, message),
+            Self::Incompatible { path, reason } => {
+                write!(f, "{:?} is incompatible with the host: {}", path, reason)
+            }
+            Self::Migration {
+                path,
+                from_version,
+                to_version,
+                message,
+            } => write!(
+                f,
+                "{:?} failed to migrate the state from version {} to {}: {}",
+                path, from_version, to_version, message
+            ),
+            Self::Panicked { path } => write!(f, "{:?} panicked while loading", path),
+        }
+    }
+}
+
+impl Error for HotReloadError {}
+
+/// A loaded copy of a game library.
+struct LoadedLogic {
+    api: GameLogicApi,
+    library: Option<Library>,
+    copy_path: Option<PathBuf>,
+}
+
+impl LoadedLogic {
+    /// Checks the library is compatible with the host, before anything else is called.
+    fn new<D>(
+        path: &Path,
+        api: GameLogicApi,
+        library: Option<Library>,
+        copy_path: Option<PathBuf>,
+    ) -> Result<Self, HotReloadError> {
+        let logic = Self {
+            api,
+            library,
+            copy_path,
+        };
+        let incompatible = |reason: String| HotReloadError::Incompatible {
+            path: path.to_path_buf(),
+            reason,
+        };
+        if api.abi_version() != ABI_VERSION {
+            return Err(incompatible(format!(
+                "The library uses ABI version {}, but the host uses {}",
+                api.abi_version(),
+                ABI_VERSION
+            )));
+        }
+        // Safety: The library is still loaded, because it is owned by `logic`.
+        let (engine_version, data_type) = unsafe { api.build_info() };
+        if engine_version != env!("CARGO_PKG_VERSION") {
+            return Err(incompatible(format!(
+                "The library uses Wolf Engine {}, but the host uses {}",
+                engine_version,
+                env!("CARGO_PKG_VERSION")
+            )));
+        }
+        let host_layout = (size_of::<D>(), align_of::<D>());
+        if data_type != std::any::type_name::<D>() || api.data_layout() != host_layout {
+            return Err(incompatible(format!(
+                "The library uses `{}` as its data, but the host uses `{}`",
+                data_type,
+                std::any::type_name::<D>()
+            )));
+        }
+        let host_context_layout = (size_of::<Context<D>>(), align_of::<Context<D>>());
+        if api.context_layout() != host_context_layout {
+            return Err(incompatible(format!(
+                "The library's context has a size, and alignment, of {:?}, but the host's has \
+                 {:?}, so they were built with different Wolf Engine features",
+                api.context_layout(),
+                host_context_layout
+            )));
+        }
+        Ok(logic)
+    }
+}
+
+impl Drop for LoadedLogic {
+    fn drop(&mut self) {
+        self.library.take();
+        if let Some(copy_path) = self.copy_path.take() {
+            std::fs::remove_file(copy_path).ok();
+        }
+    }
+}
+
+/// Loads [`GameLogic`] from a dynamic library, and reloads it when the library is rebuilt.
+///
+/// The library is copied before it is loaded, so the original file can be replaced by the
+/// compiler while the game is running.  The file is watched using a [`FileWatcher`], and when
+/// it changes, the new library is loaded, checked, and swapped in, while the [`Context`], and
+/// everything in it, stays with the host.
+///
+/// Swapping libraries works like this:
+///
+/// 1. The new library is loaded, and checked for compatibility.
+/// 2. The old logic's [`GameLogic::on_unload()`] is called.
+/// 3. If the state versions differ, the new logic's [`GameLogic::migrate_state()`] is called.
+/// 4. The old library is unloaded, and the new logic's [`GameLogic::on_load()`] is called.
+///
+/// If the new library can't be loaded, isn't compatible, or fails to migrate the state, the
+/// error is logged, and the old library keeps running.
+///
+/// The easiest way to run the library is to add it to the engine as a [`Subsystem`], which
+/// starts it, passes it every event, and checks for rebuilds at the start of every frame.  It
+/// can also be run by hand, by passing it every event with [`GameLibrary::handle_event()`], and
+/// calling [`GameLibrary::reload_if_changed()`] once per frame.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wolf_engine_core::EngineBuilder;
+/// use wolf_engine_framework::*;
+/// use wolf_engine_framework::hot_reload::*;
+///
+/// pub struct GameData {
+///     pub score: u32,
+/// }
+///
+/// let game_library = unsafe { GameLibrary::open("target/debug/libmy_game.so") }.unwrap();
+/// let engine_builder = EngineBuilder::new(GameData { score: 0 }).with_subsystem(game_library);
+/// let framework = FrameworkBuilder::from_engine_builder(engine_builder)
+///     .build()
+///     .unwrap();
+///
+/// framework.run(|_event, _context, _control_flow| {});
+/// ```
+pub struct GameLibrary<D> {
+    path: PathBuf,
+    logic: LoadedLogic,
+    file_watcher: FileWatcher,
+    is_started: bool,
+    _data: PhantomData<fn(&mut D)>,
+}
+
+impl<D> GameLibrary<D> {
+    /// Loads game logic from the dynamic library at `path`.
+    ///
+    /// The library isn't started until [`GameLibrary::start()`] is called, or the first event
+    /// is handled.
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs arbitrary code.  The library, and every version of it which is
+    /// reloaded, must export its logic using
+    /// [`export_game_logic!`](crate::export_game_logic!), and must be built with the same
+    /// compiler, and global allocator, as the host.  It must also enable the same
+    /// `wolf_engine_core` features, such as `serde`, because they change the layout of the
+    /// [`Context`] which is passed to it.  A library whose context has a different size, or
+    /// alignment, is refused, but that can't catch every mismatch.
+    ///
+    /// The library's code, and static data, are unloaded when it is replaced, or the
+    /// `GameLibrary` is dropped.  Any `&'static str`, or other `'static` reference, created by
+    /// the library, and any function pointer, or trait object, pointing into it, dangles after
+    /// that.  The library must remove all of them from the [`Context`] in
+    /// [`GameLogic::on_unload()`], and store owned values, such as a [`String`], instead.
+    pub unsafe fn open(path: impl Into<PathBuf>) -> Result<Self, HotReloadError> {
+        let path = path.into();
+        let logic = load_library::<D>(&path)?;
+        let mut file_watcher = FileWatcher::new().with_debounce(Duration::from_millis(500));
+        file_watcher.watch(&path);
+        Ok(Self::from_logic(path, logic, file_watcher))
+    }
+
+    fn from_logic(path: PathBuf, logic: LoadedLogic, file_watcher: FileWatcher) -> Self {
+        Self {
+            path,
+            logic,
+            file_watcher,
+            is_started: false,
+            _data: PhantomData,
+        }
+    }
+
+    /// Replaces the [`FileWatcher`] used to detect rebuilds.
+    ///
+    /// By default, the library is checked every 250 ms, and is reloaded once it has gone
+    /// unchanged for 500 ms, so it isn't loaded while the compiler is still writing it.
+    pub fn with_file_watcher(mut self, mut file_watcher: FileWatcher) -> Self {
+        file_watcher.watch(&self.path);
+        self.file_watcher = file_watcher;
+        self
+    }
+
+    /// Returns the path of the library.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the state version of the loaded logic.
+    pub fn state_version(&self) -> u32 {
+        self.logic.api.state_version()
+    }
+
+    /// Returns `true` if the logic has been started.
+    pub fn is_started(&self) -> bool {
+        self.is_started
+    }
+
+    /// Calls the logic's [`GameLogic::on_load()`], if it hasn't been started already.
+    pub fn start(&mut self, context: &mut Context<D>) {
+        if !self.is_started {
+            self.is_started = true;
+            self.call_on_load(context);
+        }
+    }
+
+    /// Calls the logic's [`GameLogic::on_unload()`], if it has been started.
+    pub fn stop(&mut self, context: &mut Context<D>) {
+        if self.is_started {
+            self.is_started = false;
+            self.call_on_unload(context);
+        }
+    }
+
+    /// Loads the library again, and swaps it in for the current one.
+    ///
+    /// If an error is returned, the current library is still loaded, and running, unless the
+    /// new library panicked in [`GameLogic::on_load()`].
+    pub fn reload(&mut self, context: &mut Context<D>) -> Result<(), HotReloadError> {
+        // Safety: The caller of `open()` promised every version of the library is safe to load.
+        let logic = unsafe { load_library::<D>(&self.path)? };
+        self.swap(logic, context)
+    }
+
+    /// Reloads the library if its file has changed, as reported by the [`FileWatcher`].
+    ///
+    /// This should be called once per frame.  When the library is added to the engine as a
+    /// [`Subsystem`], it is called at the start of every frame, so it works with, or without,
+    /// [frame phases](EventLoop::frame_phases()).  Errors are logged, and the old library keeps
+    /// running.
+    pub fn reload_if_changed(&mut self, context: &mut Context<D>) {
+        if self.file_watcher.poll(Instant::now()).is_empty() {
+            return;
+        }
+        log::info!("Reloading game logic from {:?}", self.path);
+        if let Err(error) = self.reload(context) {
+            log::error!("{}", error);
+        }
+    }
+
+    /// Passes an event to the logic, starting it first, if needed.
+    pub fn handle_event(&mut self, event: Event, context: &mut Context<D>) {
+        self.start(context);
+        // Safety: The library is loaded, and was checked against `D`.
+        if !unsafe { self.logic.api.handle_event(event, context) } {
+            log::error!(
+                "Game logic from {:?} panicked handling {:?}",
+                self.path,
+                event
+            );
+        }
+    }
+
+    fn swap(&mut self, logic: LoadedLogic, context: &mut Context<D>) -> Result<(), HotReloadError> {
+        if self.is_started {
+            self.call_on_unload(context);
+        }
+        let from_version = self.logic.api.state_version();
+        let to_version = logic.api.state_version();
+        if from_version != to_version {
+            // Safety: The new library is loaded, and was checked against `D`.
+            let migration = unsafe { logic.api.migrate_state(&mut context.data, from_version) };
+            if let Err(message) = migration {
+                if self.is_started {
+                    self.call_on_load(context);
+                }
+                return Err(HotReloadError::Migration {
+                    path: self.path.clone(),
+                    from_version,
+                    to_version,
+                    message,
+                });
+            }
+        }
+        self.logic = logic;
+        // Safety: The library is loaded, and was checked against `D`.
+        if self.is_started && !unsafe { self.logic.api.on_load(context) } {
+            return Err(HotReloadError::Panicked {
+                path: self.path.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn call_on_load(&mut self, context: &mut Context<D>) {
+        // Safety: The library is loaded, and was checked against `D`.
+        if !unsafe { self.logic.api.on_load(context) } {
+            log::error!("Game logic from {:?} panicked while loading", self.path);
+        }
+    }
+
+    fn call_on_unload(&mut self, context: &mut Context<D>) {
+        // Safety: The library is loaded, and was checked against `D`.
+        if !unsafe { self.logic.api.on_unload(context) } {
+            log::error!("Game logic from {:?} panicked while unloading", self.path);
+        }
+    }
+}
+
+impl<D> Subsystem<D> for GameLibrary<D> {
+    fn name(&self) -> &'static str {
+        "game_library"
+    }
+
+    fn on_start(&mut self, context: &mut Context<D>) {
+        self.start(context);
+    }
+
+    fn on_event(&mut self, event: Event, context: &mut Context<D>) {
+        self.handle_event(event, context);
+    }
+
+    fn on_frame(&mut self, context: &mut Context<D>) {
+        self.reload_if_changed(context);
+    }
+
+    fn on_shutdown(&mut self, context: &mut Context<D>) {
+        self.stop(context);
+    }
+}
+
+/// Copies the library to a unique path, then loads the copy.
+///
+/// Loading a copy lets the original be overwritten, and stops the platform from handing back
+/// the already-loaded library.
+///
+/// # Safety
+///
+/// See [`GameLibrary::open()`].
+unsafe fn load_library<D>(path: &Path) -> Result<LoadedLogic, HotReloadError> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let copy_path = std::env::temp_dir().join(format!(
+        "wolf_engine_{}_{}_{}",
+        std::process::id(),
+        NEXT_COPY.fetch_add(1, Ordering::Relaxed),
+        file_name
+    ));
+    std::fs::copy(path, &copy_path).map_err(|error| HotReloadError::Io {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    })?;
+    let load_error = |error: libloading::Error| HotReloadError::Load {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    };
+    let library = match Library::new(&copy_path) {
+        Ok(library) => library,
+        Err(error) => {
+            std::fs::remove_file(&copy_path).ok();
+            return Err(load_error(error));
+        }
+    };
+    let entry_point = library
+        .get::<unsafe extern "C" fn() -> GameLogicApi>(ENTRY_POINT.as_bytes())
+        .map(|entry_point| *entry_point);
+    match entry_point {
+        Ok(entry_point) => {
+            LoadedLogic::new::<D>(path, entry_point(), Some(library), Some(copy_path))
+        }
+        Err(error) => {
+            drop(library);
+            std::fs::remove_file(&copy_path).ok();
+            Err(load_error(error))
+        }
+    }
+}
+
+#[cfg(test)]
+mod game_library_tests {
+    use ntest::timeout;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct TestData {
+        calls: Vec<String>,
+        score: u32,
+    }
+
+    struct LogicV1;
+
+    impl GameLogic<TestData> for LogicV1 {
+        const STATE_VERSION: u32 = 1;
+
+        fn on_load(context: &mut Context<TestData>) {
+            context.data.calls.push("v1 load".to_string());
+        }
+
+        fn on_unload(context: &mut Context<TestData>) {
+            context.data.calls.push("v1 unload".to_string());
+        }
+
+        fn handle_event(event: Event, context: &mut Context<TestData>) {
+            if event == Event::EventsCleared {
+                context.data.calls.push("v1 event".to_string());
+                context.data.score += 1;
+            }
+        }
+    }
+
+    struct LogicV2;
+
+    impl GameLogic<TestData> for LogicV2 {
+        const STATE_VERSION: u32 = 2;
+
+        fn on_load(context: &mut Context<TestData>) {
+            context.data.calls.push("v2 load".to_string());
+        }
+
+        fn handle_event(event: Event, context: &mut Context<TestData>) {
+            if event == Event::EventsCleared {
+                context.data.calls.push("v2 event".to_string());
+            }
+        }
+
+        fn migrate_state(data: &mut TestData, from_version: u32) -> Result<(), String> {
+            assert_eq!(from_version, 1);
+            data.calls.push("v2 migrate".to_string());
+            data.score *= 10;
+            Ok(())
+        }
+    }
+
+    struct UnmigratableLogic;
+
+    impl GameLogic<TestData> for UnmigratableLogic {
+        const STATE_VERSION: u32 = 3;
+
+        fn handle_event(_event: Event, _context: &mut Context<TestData>) {}
+    }
+
+    struct PanickingLogic;
+
+    impl GameLogic<TestData> for PanickingLogic {
+        const STATE_VERSION: u32 = 1;
+
+        fn handle_event(_event: Event, _context: &mut Context<TestData>) {
+            panic!("The game logic panicked");
+        }
+    }
+
+    fn logic<L: GameLogic<TestData>>() -> LoadedLogic {
+        LoadedLogic::new::<TestData>(
+            Path::new("test"),
+            GameLogicApi::new::<TestData, L>(),
+            None,
+            None,
+        )
+        .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    fn game_library<L: GameLogic<TestData>>() -> (Context<TestData>, GameLibrary<TestData>) {
+        let (_event_loop, context) = wolf_engine_core::init(TestData::default());
+        let game_library = GameLibrary::from_logic("test".into(), logic::<L>(), FileWatcher::new());
+        (context, game_library)
+    }
+
+    #[test]
+    fn should_start_the_logic_and_pass_it_events() {
+        let (mut context, mut game_library) = game_library::<LogicV1>();
+
+        game_library.handle_event(Event::EventsCleared, &mut context);
+        game_library.handle_event(Event::EventsCleared, &mut context);
+        game_library.stop(&mut context);
+
+        assert!(!game_library.is_started());
+        assert_eq!(
+            context.data.calls,
+            vec!["v1 load", "v1 event", "v1 event", "v1 unload"]
+        );
+        assert_eq!(context.data.score, 2);
+    }
+
+    #[test]
+    fn should_swap_logic_and_migrate_the_state() {
+        let (mut context, mut game_library) = game_library::<LogicV1>();
+        game_library.handle_event(Event::EventsCleared, &mut context);
+
+        game_library.swap(logic::<LogicV2>(), &mut context).unwrap();
+        game_library.handle_event(Event::EventsCleared, &mut context);
+
+        assert_eq!(game_library.state_version(), 2);
+        assert_eq!(
+            context.data.calls,
+            vec![
+                "v1 load",
+                "v1 event",
+                "v1 unload",
+                "v2 migrate",
+                "v2 load",
+                "v2 event"
+            ]
+        );
+        assert_eq!(context.data.score, 10);
+    }
+
+    #[test]
+    fn should_keep_the_old_logic_if_migration_fails() {
+        let (mut context, mut game_library) = game_library::<LogicV1>();
+        game_library.start(&mut context);
+
+        let error = game_library
+            .swap(logic::<UnmigratableLogic>(), &mut context)
+            .unwrap_err();
+        game_library.handle_event(Event::EventsCleared, &mut context);
+
+        assert_eq!(
+            error,
+            HotReloadError::Migration {
+                path: "test".into(),
+                from_version: 1,
+                to_version: 3,
+                message: "No migration from state version 1 to 3".to_string(),
+            }
+        );
+        assert_eq!(game_library.state_version(), 1);
+        assert_eq!(
+            context.data.calls,
+            vec!["v1 load", "v1 unload", "v1 load", "v1 event"]
+        );
+    }
+
+    #[test]
+    fn should_survive_panicking_logic() {
+        let (mut context, mut game_library) = game_library::<PanickingLogic>();
+
+        game_library.handle_event(Event::EventsCleared, &mut context);
+        game_library.swap(logic::<LogicV1>(), &mut context).unwrap();
+        game_library.handle_event(Event::EventsCleared, &mut context);
+
+        assert_eq!(context.data.calls, vec!["v1 load", "v1 event"]);
+    }
+
+    #[test]
+    fn should_refuse_incompatible_logic() {
+        let api = GameLogicApi::new::<TestData, LogicV1>().with_abi_version(ABI_VERSION + 1);
+
+        let abi_error = LoadedLogic::new::<TestData>(Path::new("test"), api, None, None);
+        let data_error = LoadedLogic::new::<u32>(
+            Path::new("test"),
+            GameLogicApi::new::<TestData, LogicV1>(),
+            None,
+            None,
+        );
+        let context_layout = (
+            size_of::<Context<TestData>>(),
+            align_of::<Context<TestData>>(),
+        );
+        let context_error = LoadedLogic::new::<TestData>(
+            Path::new("test"),
+            GameLogicApi::new::<TestData, LogicV1>()
+                .with_context_layout(context_layout.0 + 8, context_layout.1),
+            None,
+            None,
+        );
+
+        assert!(matches!(
+            abi_error,
+            Err(HotReloadError::Incompatible { .. })
+        ));
+        assert!(matches!(
+            data_error,
+            Err(HotReloadError::Incompatible { .. })
+        ));
+        assert!(matches!(
+            context_error,
+            Err(HotReloadError::Incompatible { .. })
+        ));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_fail_to_open_missing_or_invalid_libraries() {
+        let invalid_library = std::env::temp_dir().join(format!(
+            "wolf_engine_game_library_tests_{}_invalid.so",
+            std::process::id()
+        ));
+        std::fs::write(&invalid_library, "Not a library").unwrap();
+
+        let missing_error = unsafe { GameLibrary::<TestData>::open("missing.so") };
+        let invalid_error = unsafe { GameLibrary::<TestData>::open(&invalid_library) };
+
+        assert!(matches!(missing_error, Err(HotReloadError::Io { .. })));
+        assert!(matches!(invalid_error, Err(HotReloadError::Load { .. })));
+    }
+}
@@ -0,0 +1,63 @@
+//! Provides hot-reloading of game logic from a dynamic library.
+//!
+//! The game is split into two crates: a host executable, which owns the engine, and the
+//! [`Context`](wolf_engine_core::Context), and a `cdylib` containing the game's
+//! [`GameLogic`].  The library exports its logic with [`export_game_logic!`](crate::export_game_logic!),
+//! and the host loads it with a [`GameLibrary`], which reloads the logic whenever the library
+//! is rebuilt, while all of the game's data stays in the host.
+//!
+//! The boundary between the host, and the library, is the versioned, `#[repr(C)]`,
+//! [`GameLogicApi`].  Libraries built with a different ABI version, engine version, or data
+//! type are refused, and panics never cross the boundary.
+//!
+//! This module is only available with the `hot_reload` feature.
+//!
+//! # Examples
+//!
+//! In the library, which has `crate-type = ["cdylib"]`:
+//!
+//! ```
+//! use wolf_engine_core::prelude::*;
+//! use wolf_engine_framework::hot_reload::*;
+//!
+//! pub struct GameData {
+//!     pub frames: u64,
+//! }
+//!
+//! pub struct MyGameLogic;
+//!
+//! impl GameLogic<GameData> for MyGameLogic {
+//!     fn handle_event(event: Event, context: &mut Context<GameData>) {
+//!         if event == Event::EventsCleared {
+//!             context.data.frames += 1;
+//!         }
+//!     }
+//! }
+//!
+//! wolf_engine_framework::export_game_logic!(GameData, MyGameLogic);
+//! ```
+//!
+//! In the host.  The data type is checked by name, and layout, so `GameData` should be defined
+//! in a crate shared by the host, and the library:
+//!
+//! ```no_run
+//! # pub struct GameData {
+//! #     pub frames: u64,
+//! # }
+//! use wolf_engine_core::EngineBuilder;
+//! use wolf_engine_framework::*;
+//! use wolf_engine_framework::hot_reload::*;
+//!
+//! let game_library = unsafe { GameLibrary::open("target/debug/libmy_game.so") }.unwrap();
+//! let engine_builder = EngineBuilder::new(GameData { frames: 0 }).with_subsystem(game_library);
+//! let framework = FrameworkBuilder::from_engine_builder(engine_builder)
+//!     .build()
+//!     .unwrap();
+//!
+//! framework.run(|_event, _context, _control_flow| {});
+//! ```
+
+mod api;
+pub use api::*;
+mod library;
+pub use library::*;
@@ -5,3 +5,80 @@
 //! more on building your game.  The framework includes a plugin system, and game state /
 //! state-stack architecture.
 //!
+//! # Getting Started
+//!
+//! The quickest way to get a game running is to implement the [`Game`] trait, and pass the game
+//! to [`run()`].
+//!
+//! ```
+//! use wolf_engine_framework::*;
+//! use wolf_engine_core::prelude::*;
+//! use std::time::Duration;
+//!
+//! struct MyGame;
+//!
+//! impl Game for MyGame {
+//!     fn update(&mut self, context: &mut Context<()>, _delta: Duration) {
+//!         context.quit();
+//!     }
+//! }
+//!
+//! wolf_engine_framework::run(MyGame).unwrap();
+//! ```
+//!
+//! # Plugins
+//!
+//! Reusable functionality is packaged up as a [`Plugin`], which registers its subsystems,
+//! resources, and event handlers when the [`Framework`] is built.  Plugins are added using the
+//! [`FrameworkBuilder`], and the resulting [`Framework`] is run on a normal core
+//! [`EventLoop`](wolf_engine_core::EventLoop).
+//!
+//! # Game States
+//!
+//! Games are split into [`State`]s, such as menus, levels, and pause screens, which are kept on a
+//! [`StateStack`].  The top state is the active one, and it changes the stack by returning
+//! [`Transition`]s.  The stack is a [`Plugin`], so it can be added directly to the
+//! [`FrameworkBuilder`].
+//!
+//! A [`LoadingState`] can be used to run background load jobs, and show their progress, before
+//! switching to a state which needs the loaded data.
+//!
+//! # Assets
+//!
+//! The [`assets`] module provides an [`assets::AssetServer`], which loads files in the
+//! background using loaders registered by file extension, and hands out reference-counted
+//! [`assets::Handle`]s to them.  With an [`assets::FileWatcher`], assets are hot-reloaded when
+//! their files change.
+//!
+//! # Hot-Reloading
+//!
+//! With the `hot_reload` feature, the `hot_reload` module can load the game's logic from a
+//! dynamic library, and reload it when the library is rebuilt, while the game's data stays in
+//! the host.
+//!
+//! # Entity-Component-System
+//!
+//! The [`ecs`] module provides a lightweight ECS, which can be used with one [`ecs::World`] per
+//! game, using the [`ecs::EcsPlugin`], or one per state.  Systems can be organized into an
+//! [`ecs::Schedule`], and run each frame by the [`ecs::SchedulePlugin`].
+//!
+//! # Scene Graph
+//!
+//! The [`scene`] module provides a 2D [`scene::SceneGraph`], which propagates transforms from
+//! parents to their children, and can be used with, or without, the ECS.
+
+pub mod assets;
+pub mod ecs;
+mod framework_builder;
+pub use framework_builder::*;
+mod game;
+pub use game::*;
+#[cfg(feature = "hot_reload")]
+pub mod hot_reload;
+mod loading;
+pub use loading::*;
+mod plugins;
+pub use plugins::*;
+pub mod scene;
+mod states;
+pub use states::*;
@@ -0,0 +1,488 @@
+use std::any::{type_name, TypeId};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use wolf_engine_core::prelude::*;
+
+use crate::*;
+
+type SpawnLoadJob<D> = Box<dyn FnOnce(&Context<D>) -> Box<dyn LoadJob<D>>>;
+type ProgressDisplay<D> = Box<dyn FnMut(&LoadingProgress, &mut Context<D>)>;
+type ErrorStateFactory<D> = Box<dyn FnOnce(LoadError) -> Box<dyn State<D>>>;
+
+/// Indicates a load job, run by a [`LoadingState`], failed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LoadError {
+    /// The name of the job which failed.
+    pub job: &'static str,
+    /// A description of the failure.
+    pub message: String,
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to load `{}`: {}", self.job, self.message)
+    }
+}
+
+impl Error for LoadError {}
+
+/// Reports the combined progress of the jobs run by a [`LoadingState`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct LoadingProgress {
+    completed: usize,
+    total: usize,
+    fraction: f32,
+}
+
+impl LoadingProgress {
+    /// Returns the number of jobs which have finished.
+    pub fn completed(&self) -> usize {
+        self.completed
+    }
+
+    /// Returns the total number of jobs.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the overall progress, from `0.0` to `1.0`.
+    ///
+    /// Each job contributes equally, using the progress it reports with
+    /// [`JobContext::report_progress()`] until it finishes.
+    pub fn fraction(&self) -> f32 {
+        self.fraction
+    }
+
+    /// Returns `true` once every job has finished.
+    pub fn is_complete(&self) -> bool {
+        self.completed == self.total
+    }
+}
+
+/// A type-erased [`JobHandle`] to a running load job.
+trait LoadJob<D> {
+    fn status(&self) -> JobStatus;
+
+    fn cancel(&self);
+
+    /// Inserts the job's result into the context.
+    fn finish(&self, context: &mut Context<D>) -> Result<(), String>;
+}
+
+impl<D, T: Send + 'static> LoadJob<D> for JobHandle<Result<T, String>> {
+    fn status(&self) -> JobStatus {
+        JobHandle::status(self)
+    }
+
+    fn cancel(&self) {
+        JobHandle::cancel(self);
+    }
+
+    fn finish(&self, context: &mut Context<D>) -> Result<(), String> {
+        match self.take_result() {
+            Some(Ok(resource)) => {
+                context.insert_resource(resource);
+                Ok(())
+            }
+            Some(Err(message)) => Err(message),
+            None => Err("The job did not return a result".to_string()),
+        }
+    }
+}
+
+struct RunningLoadJob<D> {
+    name: &'static str,
+    job: Box<dyn LoadJob<D>>,
+    percent: u8,
+    is_finished: bool,
+}
+
+/// A [`State`] which runs a set of background load jobs, then switches to the target state.
+///
+/// The jobs are spawned on the engine's [`JobPool`] when the state is entered, and polled every
+/// time the state is updated.  Each job's result is inserted into the [`Context`] as a
+/// [resource](Context::resource()), so the target state can use it.  While the jobs run, the
+/// display callback is called with the combined [`LoadingProgress`] every time the state is
+/// rendered.
+///
+/// If a job fails, or returns an error, the remaining jobs are cancelled, and the state switches
+/// to the error state, if one was provided.  Otherwise, the error is logged, and the game quits.
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_framework::*;
+/// use wolf_engine_core::prelude::*;
+///
+/// struct Level(Vec<u32>);
+///
+/// struct Playing;
+///
+/// impl State<()> for Playing {
+///     fn update(&mut self, context: &mut Context<()>) -> OptionalTransition<()> {
+///         assert_eq!(context.resource::<Level>().unwrap().0.len(), 3);
+///         Some(Transition::Quit)
+///     }
+/// }
+///
+/// let loading_state = LoadingState::new(Playing)
+///     .with_job("level", |job| {
+///         job.report_progress(50);
+///         Ok(Level(vec![1, 2, 3]))
+///     })
+///     .with_display(|progress, _context| {
+///         println!("Loading: {:.0}%", progress.fraction() * 100.0);
+///     });
+///
+/// let framework = FrameworkBuilder::new(())
+///     .with_plugin(StateStack::with_state(loading_state))
+///     .build()
+///     .unwrap();
+///
+/// framework.run(|_event, _context, _control_flow| ());
+/// ```
+pub struct LoadingState<D> {
+    target_state: Option<Box<dyn State<D>>>,
+    jobs_to_spawn: Vec<(&'static str, SpawnLoadJob<D>)>,
+    jobs: Vec<RunningLoadJob<D>>,
+    result_types: Vec<TypeId>,
+    display: Option<ProgressDisplay<D>>,
+    error_state: Option<ErrorStateFactory<D>>,
+    error: Option<LoadError>,
+}
+
+impl<D: 'static> LoadingState<D> {
+    /// Creates a loading state which switches to the target state once every job has finished.
+    pub fn new<S: State<D> + 'static>(target_state: S) -> Self {
+        Self {
+            target_state: Some(Box::new(target_state)),
+            jobs_to_spawn: Vec::new(),
+            jobs: Vec::new(),
+            result_types: Vec::new(),
+            display: None,
+            error_state: None,
+            error: None,
+        }
+    }
+
+    /// Adds a load job, which inserts its result into the [`Context`] as a resource.
+    ///
+    /// Returning an error, or panicking, fails the load.  Each job must return a different
+    /// resource type, so results can't overwrite each other.  Adding a job which returns the
+    /// same type as an earlier job fails the load, without running any jobs.
+    pub fn with_job<T, F>(mut self, name: &'static str, job: F) -> Self
+    where
+        T: Send + 'static,
+        F: FnOnce(&JobContext) -> Result<T, String> + Send + 'static,
+    {
+        let result_type = TypeId::of::<T>();
+        if self.result_types.contains(&result_type) {
+            let message = format!(
+                "Another job already loads a resource of type `{}`",
+                type_name::<T>()
+            );
+            self.fail(name, message);
+            return self;
+        }
+        self.result_types.push(result_type);
+        self.jobs_to_spawn.push((
+            name,
+            Box::new(move |context| Box::new(context.spawn_job(job))),
+        ));
+        self
+    }
+
+    /// Sets the callback used to display the loading progress.
+    pub fn with_display<F>(mut self, display: F) -> Self
+    where
+        F: FnMut(&LoadingProgress, &mut Context<D>) + 'static,
+    {
+        self.display = Some(Box::new(display));
+        self
+    }
+
+    /// Sets the function used to create the state shown when loading fails.
+    pub fn with_error_state<F>(mut self, error_state: F) -> Self
+    where
+        F: FnOnce(LoadError) -> Box<dyn State<D>> + 'static,
+    {
+        self.error_state = Some(Box::new(error_state));
+        self
+    }
+
+    /// Returns the combined progress of the load jobs.
+    pub fn progress(&self) -> LoadingProgress {
+        let total = self.jobs.len() + self.jobs_to_spawn.len();
+        let completed = self.jobs.iter().filter(|job| job.is_finished).count();
+        let fraction = if total == 0 {
+            1.0
+        } else {
+            let percent_sum: u32 = self
+                .jobs
+                .iter()
+                .map(|job| {
+                    if job.is_finished {
+                        100
+                    } else {
+                        job.percent as u32
+                    }
+                })
+                .sum();
+            percent_sum as f32 / (total as f32 * 100.0)
+        };
+        LoadingProgress {
+            completed,
+            total,
+            fraction,
+        }
+    }
+
+    fn fail(&mut self, job: &'static str, message: String) {
+        self.error.get_or_insert(LoadError { job, message });
+    }
+
+    fn poll_jobs(&mut self, context: &mut Context<D>) {
+        for job in self.jobs.iter_mut().filter(|job| !job.is_finished) {
+            let result = match job.job.status() {
+                JobStatus::Running(percent) => {
+                    job.percent = percent;
+                    continue;
+                }
+                JobStatus::Finished => job.job.finish(context),
+                JobStatus::Failed => Err("The job panicked".to_string()),
+                JobStatus::Cancelled => Err("The job was cancelled".to_string()),
+            };
+            job.is_finished = true;
+            if let Err(message) = result {
+                self.error.get_or_insert(LoadError {
+                    job: job.name,
+                    message,
+                });
+            }
+        }
+    }
+
+    fn cancel_jobs(&mut self) {
+        for job in self.jobs.iter().filter(|job| !job.is_finished) {
+            job.job.cancel();
+        }
+    }
+}
+
+impl<D: 'static> State<D> for LoadingState<D> {
+    fn on_enter(&mut self, context: &mut Context<D>) {
+        if self.error.is_some() {
+            return;
+        }
+        for (name, spawn_job) in std::mem::take(&mut self.jobs_to_spawn) {
+            self.jobs.push(RunningLoadJob {
+                name,
+                job: spawn_job(context),
+                percent: 0,
+                is_finished: false,
+            });
+        }
+    }
+
+    fn on_exit(&mut self, _context: &mut Context<D>) {
+        self.cancel_jobs();
+    }
+
+    fn update(&mut self, context: &mut Context<D>) -> OptionalTransition<D> {
+        self.poll_jobs(context);
+        if let Some(error) = self.error.take() {
+            self.cancel_jobs();
+            return match self.error_state.take() {
+                Some(error_state) => Some(Transition::Switch(error_state(error))),
+                None => {
+                    log::error!("{}", error);
+                    Some(Transition::Quit)
+                }
+            };
+        }
+        if self.progress().is_complete() {
+            self.target_state.take().map(Transition::Switch)
+        } else {
+            None
+        }
+    }
+
+    fn render(&mut self, context: &mut Context<D>) {
+        let progress = self.progress();
+        if let Some(display) = self.display.as_mut() {
+            display(&progress, context);
+        }
+    }
+}
+
+#[cfg(test)]
+mod loading_state_tests {
+    use std::sync::{Arc, Mutex};
+
+    use ntest::timeout;
+
+    use super::*;
+
+    struct Loaded(&'static str);
+
+    struct TargetState {
+        entered: Arc<Mutex<Option<String>>>,
+    }
+
+    impl State<()> for TargetState {
+        fn on_enter(&mut self, context: &mut Context<()>) {
+            *self.entered.lock().unwrap() = context
+                .resource::<Loaded>()
+                .ok()
+                .map(|loaded| loaded.0.to_string());
+        }
+
+        fn update(&mut self, _context: &mut Context<()>) -> OptionalTransition<()> {
+            Some(Transition::Quit)
+        }
+    }
+
+    struct QuitState;
+
+    impl State<()> for QuitState {
+        fn update(&mut self, _context: &mut Context<()>) -> OptionalTransition<()> {
+            Some(Transition::Quit)
+        }
+    }
+
+    fn run(loading_state: LoadingState<()>) {
+        FrameworkBuilder::new(())
+            .with_plugin(StateStack::with_state(loading_state))
+            .build()
+            .unwrap()
+            .run(|_event, _context, _control_flow| ());
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_switch_to_the_target_state_once_loaded() {
+        let entered = Arc::new(Mutex::new(None));
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let display_progress = progress.clone();
+        let loading_state = LoadingState::new(TargetState {
+            entered: entered.clone(),
+        })
+        .with_job("loaded", |job| {
+            job.report_progress(50);
+            Ok(Loaded("level 1"))
+        })
+        .with_job("empty", |_job| Ok(()))
+        .with_display(move |progress, _context| display_progress.lock().unwrap().push(*progress));
+
+        run(loading_state);
+
+        assert_eq!(*entered.lock().unwrap(), Some("level 1".to_string()));
+        let progress = progress.lock().unwrap();
+        assert!(progress.iter().all(|progress| progress.total() == 2));
+        assert!(progress
+            .windows(2)
+            .all(|pair| pair[0].fraction() <= pair[1].fraction()));
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_switch_to_the_error_state_on_failure() {
+        let entered = Arc::new(Mutex::new(None));
+        let error = Arc::new(Mutex::new(None));
+        let reported_error = error.clone();
+        let loading_state = LoadingState::new(TargetState {
+            entered: entered.clone(),
+        })
+        .with_job("broken", |_job| -> Result<(), String> {
+            Err("Missing file".to_string())
+        })
+        .with_error_state(move |error| {
+            *reported_error.lock().unwrap() = Some(error);
+            Box::new(QuitState)
+        });
+
+        run(loading_state);
+
+        assert_eq!(*entered.lock().unwrap(), None);
+        assert_eq!(
+            *error.lock().unwrap(),
+            Some(LoadError {
+                job: "broken",
+                message: "Missing file".to_string()
+            })
+        );
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_quit_on_failure_without_an_error_state() {
+        let entered = Arc::new(Mutex::new(None));
+        let loading_state = LoadingState::new(TargetState {
+            entered: entered.clone(),
+        })
+        .with_job("panicking", |_job| -> Result<(), String> {
+            panic!("Failed to load")
+        });
+
+        run(loading_state);
+
+        assert_eq!(*entered.lock().unwrap(), None);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_poll_jobs_without_receiving_events() {
+        let (_event_loop, mut context) = wolf_engine_core::init(());
+        let (finish_sender, finish_receiver) = std::sync::mpsc::channel::<()>();
+        let mut loading_state = LoadingState::new(QuitState).with_job("loaded", move |job| {
+            job.report_progress(50);
+            finish_receiver.recv().unwrap();
+            Ok(Loaded("level 1"))
+        });
+        loading_state.on_enter(&mut context);
+
+        while loading_state.progress().fraction() < 0.5 {
+            assert!(loading_state.update(&mut context).is_none());
+        }
+        finish_sender.send(()).unwrap();
+        while loading_state.update(&mut context).is_none() {}
+
+        assert!(loading_state.progress().is_complete());
+        assert_eq!(context.resource::<Loaded>().unwrap().0, "level 1");
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_reject_jobs_with_the_same_result_type() {
+        let entered = Arc::new(Mutex::new(None));
+        let error = Arc::new(Mutex::new(None));
+        let reported_error = error.clone();
+        let loading_state = LoadingState::new(TargetState {
+            entered: entered.clone(),
+        })
+        .with_job("first", |_job| Ok(Loaded("first")))
+        .with_job("second", |_job| Ok(Loaded("second")))
+        .with_error_state(move |error| {
+            *reported_error.lock().unwrap() = Some(error);
+            Box::new(QuitState)
+        });
+
+        run(loading_state);
+
+        assert_eq!(*entered.lock().unwrap(), None);
+        let error = error.lock().unwrap().clone().unwrap();
+        assert_eq!(error.job, "second");
+        assert!(error.message.contains("Loaded"));
+    }
+
+    #[test]
+    fn should_report_progress_with_no_jobs() {
+        let loading_state = LoadingState::<()>::new(TargetState {
+            entered: Arc::default(),
+        });
+
+        assert!(loading_state.progress().is_complete());
+        assert_eq!(loading_state.progress().fraction(), 1.0);
+    }
+}
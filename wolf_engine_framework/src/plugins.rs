@@ -0,0 +1,261 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use wolf_engine_core::prelude::*;
+
+/// A boxed event handler, added by a [`Plugin`].
+pub type PluginEventHandler<D> = Box<dyn FnMut(Event, &mut Context<D>, &mut ControlFlow)>;
+
+/// Indicates a problem registering, ordering, or building, [`Plugin`]s.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PluginError {
+    /// A plugin with the same name was already added.
+    Duplicate {
+        /// The name of the plugin.
+        name: &'static str,
+    },
+    /// A plugin depends on a plugin which was never added.
+    MissingDependency {
+        /// The name of the plugin.
+        name: &'static str,
+        /// The name of the missing dependency.
+        dependency: &'static str,
+    },
+    /// The dependencies of some plugins form a cycle, so they can't be ordered.
+    DependencyCycle {
+        /// The names of the plugins which could not be ordered, in the order they were added.
+        names: Vec<&'static str>,
+    },
+    /// The engine could not be built from the options set by the plugins.
+    Engine(EngineBuilderError),
+}
+
+impl Display for PluginError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Duplicate { name } => {
+                write!(f, "A plugin named `{}` was already added", name)
+            }
+            Self::MissingDependency { name, dependency } => write!(
+                f,
+                "Plugin `{}` depends on `{}`, which was not added",
+                name, dependency
+            ),
+            Self::DependencyCycle { names } => write!(
+                f,
+                "The dependencies of these plugins form a cycle: {}",
+                names.join(", ")
+            ),
+            Self::Engine(error) => write!(f, "Failed to build the engine: {}", error),
+        }
+    }
+}
+
+impl Error for PluginError {}
+
+impl From<DependencyError> for PluginError {
+    fn from(error: DependencyError) -> Self {
+        match error {
+            DependencyError::Missing { name, dependency } => {
+                Self::MissingDependency { name, dependency }
+            }
+            DependencyError::Cycle { names } => Self::DependencyCycle { names },
+        }
+    }
+}
+
+impl From<EngineBuilderError> for PluginError {
+    fn from(error: EngineBuilderError) -> Self {
+        Self::Engine(error)
+    }
+}
+
+/// Provides a way to package up a reusable piece of functionality, so it can be added to a game
+/// in one step.
+///
+/// Plugins are added to a [`FrameworkBuilder`](crate::FrameworkBuilder).  When the framework is
+/// built, each plugin's [`Plugin::build()`] method is called, in order, so it can register
+/// its subsystems, resources, event handlers, and so on, using the provided [`PluginBuilder`].
+///
+/// A plugin is always built after the plugins listed in its [`Plugin::dependencies()`], and
+/// otherwise in the order they were added.
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_framework::*;
+/// use wolf_engine_core::prelude::*;
+///
+/// struct Score(u32);
+///
+/// struct ScorePlugin;
+///
+/// impl Plugin<()> for ScorePlugin {
+///     fn name(&self) -> &'static str {
+///         "score"
+///     }
+///
+///     fn build(&mut self, builder: &mut PluginBuilder<()>) {
+///         builder.insert_resource(Score(0));
+///         builder.add_event_handler(|event, context, _control_flow| {
+///             if event == Event::EventsCleared {
+///                 context.resource_mut::<Score>().unwrap().0 += 1;
+///             }
+///         });
+///     }
+/// }
+///
+/// let framework = FrameworkBuilder::new(())
+///     .with_plugin(ScorePlugin)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(framework.plugin_names(), vec!["score"]);
+/// ```
+pub trait Plugin<D> {
+    /// Returns the plugin's name, which must be unique.
+    ///
+    /// Other plugins use this name to depend on this one.  Defaults to the name of the type.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Returns the names of the plugins this one depends on.
+    fn dependencies(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Registers the plugin's functionality with the engine.
+    fn build(&mut self, builder: &mut PluginBuilder<D>);
+}
+
+/// Stores the plugins added to a [`FrameworkBuilder`](crate::FrameworkBuilder).
+pub(crate) struct PluginRegistry<D> {
+    plugins: Vec<Box<dyn Plugin<D>>>,
+    names: Vec<&'static str>,
+}
+
+impl<D> PluginRegistry<D> {
+    pub fn new() -> Self {
+        Self {
+            plugins: Vec::new(),
+            names: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, plugin: Box<dyn Plugin<D>>) -> Result<(), PluginError> {
+        let name = plugin.name();
+        if self.names.contains(&name) {
+            return Err(PluginError::Duplicate { name });
+        }
+        self.names.push(name);
+        self.plugins.push(plugin);
+        Ok(())
+    }
+
+    /// Returns the plugins sorted so each one comes after its dependencies, keeping the order
+    /// they were added wherever possible.
+    pub fn into_sorted(mut self) -> Result<Vec<Box<dyn Plugin<D>>>, PluginError> {
+        sort_by_dependencies(
+            &mut self.plugins,
+            |plugin| plugin.name(),
+            |plugin| plugin.dependencies(),
+        )?;
+        Ok(self.plugins)
+    }
+}
+
+/// Provides access to the engine's options while a [`Plugin`] is being built.
+pub struct PluginBuilder<D> {
+    engine_builder: Option<EngineBuilder<D>>,
+    event_handlers: Vec<PluginEventHandler<D>>,
+    #[cfg(feature = "config")]
+    config: Option<wolf_engine_core::config::Config>,
+}
+
+impl<D> PluginBuilder<D> {
+    pub(crate) fn new(engine_builder: EngineBuilder<D>) -> Self {
+        Self {
+            engine_builder: Some(engine_builder),
+            event_handlers: Vec::new(),
+            #[cfg(feature = "config")]
+            config: None,
+        }
+    }
+
+    /// Registers a [`Subsystem`].
+    ///
+    /// Duplicate subsystems, and missing dependencies, are reported when the framework is
+    /// built.
+    pub fn add_subsystem<S: Subsystem<D> + 'static>(&mut self, subsystem: S) -> &mut Self {
+        self.configure_engine(|engine_builder| engine_builder.with_subsystem(subsystem))
+    }
+
+    /// Inserts a [resource](Context::resource()).
+    ///
+    /// Adding more than one resource of the same type is reported when the framework is built.
+    pub fn insert_resource<T: Send + 'static>(&mut self, resource: T) -> &mut Self {
+        self.configure_engine(|engine_builder| engine_builder.with_resource(resource))
+    }
+
+    /// Adds an event handler, which is called for every event, before the game's own event
+    /// handler.
+    ///
+    /// Event handlers are called in the order they were added.
+    pub fn add_event_handler<F>(&mut self, event_handler: F) -> &mut Self
+    where
+        F: FnMut(Event, &mut Context<D>, &mut ControlFlow) + 'static,
+    {
+        self.event_handlers.push(Box::new(event_handler));
+        self
+    }
+
+    /// Adds a function which is called once the engine has quit.
+    pub fn add_shutdown_hook<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: FnOnce(&mut Context<D>) + Send + 'static,
+        D: 'static,
+    {
+        self.configure_engine(|engine_builder| engine_builder.with_shutdown_hook(hook))
+    }
+
+    /// Changes any other engine options, using the underlying [`EngineBuilder`].
+    pub fn configure_engine<F>(&mut self, function: F) -> &mut Self
+    where
+        F: FnOnce(EngineBuilder<D>) -> EngineBuilder<D>,
+    {
+        let engine_builder = self
+            .engine_builder
+            .take()
+            .expect("The engine builder should always be present");
+        self.engine_builder = Some(function(engine_builder));
+        self
+    }
+
+    /// Returns the [`Config`](wolf_engine_core::config::Config) provided to the framework, if
+    /// there is one.
+    ///
+    /// Plugins can use this to read their own config sections.
+    #[cfg(feature = "config")]
+    pub fn config(&self) -> Option<&wolf_engine_core::config::Config> {
+        self.config.as_ref()
+    }
+
+    #[cfg(feature = "config")]
+    pub(crate) fn set_config(&mut self, config: wolf_engine_core::config::Config) {
+        self.config = Some(config);
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn finish(self) -> (EngineBuilder<D>, Vec<PluginEventHandler<D>>) {
+        let engine_builder = self
+            .engine_builder
+            .expect("The engine builder should always be present");
+        #[cfg(feature = "config")]
+        let engine_builder = match self.config {
+            Some(config) => engine_builder.with_config(config),
+            None => engine_builder,
+        };
+        (engine_builder, self.event_handlers)
+    }
+}
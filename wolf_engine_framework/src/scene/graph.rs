@@ -0,0 +1,766 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::ecs::{Entity, World};
+use crate::scene::*;
+
+/// A handle to a node in a [`SceneGraph`].
+///
+/// Node handles are generational, so handles to removed nodes won't refer to new nodes which
+/// reuse their index.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct NodeId {
+    index: u32,
+    generation: u32,
+}
+
+impl NodeId {
+    /// Returns the node's index, which may be reused once the node is removed.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the node's generation, which is incremented every time its index is reused.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// Indicates a problem changing the hierarchy of a [`SceneGraph`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SceneGraphError {
+    /// The node has been removed, or belongs to another scene graph.
+    NoSuchNode(NodeId),
+    /// The new parent is the node itself, or one of its descendants.
+    Cycle {
+        /// The node being reparented.
+        node: NodeId,
+        /// The requested parent.
+        parent: NodeId,
+    },
+    /// The new parent's world transform can't be inverted, such as when it has a scale of `0`,
+    /// so the node's world transform can't be kept.
+    NonInvertibleParent(NodeId),
+    /// The node's world transform, relative to the new parent, includes shearing, which its
+    /// local [`Transform2d`] can't store, so the node's world transform can't be kept.
+    ///
+    /// This happens when a rotated node is moved under a parent with a non-uniform scale, or a
+    /// sheared node is made a root node.
+    Shear {
+        /// The node being reparented.
+        node: NodeId,
+        /// The requested parent.
+        parent: Option<NodeId>,
+    },
+}
+
+impl Display for SceneGraphError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSuchNode(node) => write!(
+                f,
+                "Node {}v{} does not exist in this scene graph",
+                node.index(),
+                node.generation()
+            ),
+            Self::Cycle { node, parent } => write!(
+                f,
+                "Node {}v{} can't be a child of its descendant, {}v{}",
+                node.index(),
+                node.generation(),
+                parent.index(),
+                parent.generation()
+            ),
+            Self::NonInvertibleParent(parent) => write!(
+                f,
+                "The world transform of node {}v{} can't be inverted",
+                parent.index(),
+                parent.generation()
+            ),
+            Self::Shear { node, parent } => {
+                write!(
+                    f,
+                    "Node {}v{} would be sheared relative to ",
+                    node.index(),
+                    node.generation()
+                )?;
+                match parent {
+                    Some(parent) => write!(f, "node {}v{}", parent.index(), parent.generation()),
+                    None => write!(f, "the scene's root"),
+                }
+            }
+        }
+    }
+}
+
+impl Error for SceneGraphError {}
+
+struct Node<T> {
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    local: Transform2d,
+    world: Affine2d,
+    is_dirty: bool,
+    data: T,
+}
+
+struct Slot<T> {
+    generation: u32,
+    node: Option<Node<T>>,
+}
+
+/// Stores a hierarchy of nodes, each with a [`Transform2d`] relative to its parent.
+///
+/// Each node holds some data of type `T`, such as a sprite, or an [`Entity`] when used
+/// alongside the [ECS](crate::ecs).
+///
+/// # Transform Propagation
+///
+/// Changing a node's local transform marks it as dirty.  World transforms are recalculated when
+/// they are next read, but only for dirty nodes, and their descendants.  Recalculation can
+/// also be done up front using [`SceneGraph::update_transforms()`].
+///
+/// # Hierarchy Order
+///
+/// [`SceneGraph::iter()`] visits every node in hierarchy order: parents come before their
+/// children, and siblings are visited in the order they were added.  The order is cached
+/// between changes to the hierarchy, so it is cheap to iterate every frame for rendering.
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_framework::scene::*;
+///
+/// let mut scene = SceneGraph::new();
+/// let ship = scene.insert(Transform2d::from_translation(10.0, 0.0), "ship");
+/// let turret = scene
+///     .insert_child(ship, Transform2d::from_translation(0.0, 2.0), "turret")
+///     .unwrap();
+///
+/// scene.local_mut(ship).unwrap().translation[0] = 20.0;
+///
+/// let turret_position = scene.world_transform(turret).unwrap().translation();
+/// assert_eq!(turret_position, [20.0, 2.0]);
+///
+/// let names: Vec<&str> = scene.iter().map(|(_node, name, _world)| *name).collect();
+/// assert_eq!(names, vec!["ship", "turret"]);
+/// ```
+///
+/// Using the scene graph with the ECS, by storing each entity's [`NodeId`] as a component:
+///
+/// ```
+/// use wolf_engine_framework::ecs::*;
+/// use wolf_engine_framework::scene::*;
+///
+/// struct Sprite(&'static str);
+///
+/// let mut world = World::new();
+/// let mut scene = SceneGraph::new();
+///
+/// let ship = world.spawn((Sprite("ship"),));
+/// let ship_node = scene.insert(Transform2d::from_translation(10.0, 0.0), ship);
+/// world.insert(ship, ship_node).unwrap();
+///
+/// let turret = world.spawn((Sprite("turret"),));
+/// let turret_node = scene.insert_child(ship_node, Transform2d::IDENTITY, turret).unwrap();
+/// world.insert(turret, turret_node).unwrap();
+///
+/// scene.despawn_recursive(ship_node, &mut world);
+/// assert!(world.is_empty());
+/// ```
+pub struct SceneGraph<T> {
+    slots: Vec<Slot<T>>,
+    free_indices: Vec<u32>,
+    roots: Vec<NodeId>,
+    order: Vec<NodeId>,
+    is_order_dirty: bool,
+    has_dirty_transforms: bool,
+    len: usize,
+}
+
+impl<T> Default for SceneGraph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SceneGraph<T> {
+    /// Creates an empty scene graph.
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free_indices: Vec::new(),
+            roots: Vec::new(),
+            order: Vec::new(),
+            is_order_dirty: false,
+            has_dirty_transforms: false,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the node exists in this scene graph.
+    pub fn contains(&self, node: NodeId) -> bool {
+        self.node(node).is_some()
+    }
+
+    /// Adds a node with no parent.
+    pub fn insert(&mut self, local: Transform2d, data: T) -> NodeId {
+        let node = self.allocate(None, local, data);
+        self.roots.push(node);
+        node
+    }
+
+    /// Adds a node as the last child of the parent.
+    pub fn insert_child(
+        &mut self,
+        parent: NodeId,
+        local: Transform2d,
+        data: T,
+    ) -> Result<NodeId, SceneGraphError> {
+        if !self.contains(parent) {
+            return Err(SceneGraphError::NoSuchNode(parent));
+        }
+        let node = self.allocate(Some(parent), local, data);
+        self.node_mut(parent)
+            .expect("The parent should exist")
+            .children
+            .push(node);
+        Ok(node)
+    }
+
+    /// Removes the node, along with all of its descendants.
+    ///
+    /// Returns the data of every removed node, in hierarchy order, or `None` if the node did
+    /// not exist.
+    pub fn remove(&mut self, node: NodeId) -> Option<Vec<T>> {
+        let parent = self.node(node)?.parent;
+        self.detach(node, parent);
+        let mut removed = Vec::new();
+        self.collect_subtree(node, &mut removed);
+        self.is_order_dirty = true;
+        let data = removed
+            .into_iter()
+            .map(|removed_node| {
+                let slot = &mut self.slots[removed_node.index as usize];
+                let node = slot.node.take().expect("Removed nodes should exist");
+                slot.generation += 1;
+                self.free_indices.push(removed_node.index);
+                self.len -= 1;
+                node.data
+            })
+            .collect();
+        Some(data)
+    }
+
+    /// Moves the node to a new parent, or makes it a root node if `parent` is `None`.
+    ///
+    /// The node's local transform is changed so its world transform stays the same.  If that
+    /// would need a shear, which a [`Transform2d`] can't store, [`SceneGraphError::Shear`] is
+    /// returned, and the hierarchy is left unchanged.
+    pub fn set_parent(
+        &mut self,
+        node: NodeId,
+        parent: Option<NodeId>,
+    ) -> Result<(), SceneGraphError> {
+        let old_parent = self
+            .node(node)
+            .ok_or(SceneGraphError::NoSuchNode(node))?
+            .parent;
+        if let Some(parent) = parent {
+            if !self.contains(parent) {
+                return Err(SceneGraphError::NoSuchNode(parent));
+            }
+            if self.is_ancestor_or_self(node, parent) {
+                return Err(SceneGraphError::Cycle { node, parent });
+            }
+        }
+        self.update_transforms();
+        let parent_world = match parent {
+            Some(parent) => self
+                .node(parent)
+                .expect("The parent should exist")
+                .world
+                .inverse()
+                .ok_or(SceneGraphError::NonInvertibleParent(parent))?,
+            None => Affine2d::IDENTITY,
+        };
+        let local = parent_world * self.node(node).expect("The node should exist").world;
+        if local.has_shear() {
+            return Err(SceneGraphError::Shear { node, parent });
+        }
+        self.detach(node, old_parent);
+        match parent {
+            Some(parent) => self
+                .node_mut(parent)
+                .expect("The parent should exist")
+                .children
+                .push(node),
+            None => self.roots.push(node),
+        }
+        let node = self.node_mut(node).expect("The node should exist");
+        node.parent = parent;
+        node.local = local.to_transform();
+        node.is_dirty = true;
+        self.has_dirty_transforms = true;
+        self.is_order_dirty = true;
+        Ok(())
+    }
+
+    /// Returns the node's parent, if it has one.
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.node(node)?.parent
+    }
+
+    /// Returns the node's children, in the order they were added.
+    pub fn children(&self, node: NodeId) -> &[NodeId] {
+        self.node(node).map_or(&[], |node| node.children.as_slice())
+    }
+
+    /// Returns the nodes with no parent, in the order they were added.
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+
+    /// Returns the node's data.
+    pub fn get(&self, node: NodeId) -> Option<&T> {
+        self.node(node).map(|node| &node.data)
+    }
+
+    /// Returns the node's data, mutably.
+    pub fn get_mut(&mut self, node: NodeId) -> Option<&mut T> {
+        self.node_mut(node).map(|node| &mut node.data)
+    }
+
+    /// Returns the node's transform, relative to its parent.
+    pub fn local(&self, node: NodeId) -> Option<&Transform2d> {
+        self.node(node).map(|node| &node.local)
+    }
+
+    /// Returns the node's transform, relative to its parent, and marks it as dirty.
+    pub fn local_mut(&mut self, node: NodeId) -> Option<&mut Transform2d> {
+        if !self.contains(node) {
+            return None;
+        }
+        self.has_dirty_transforms = true;
+        let node = self.node_mut(node)?;
+        node.is_dirty = true;
+        Some(&mut node.local)
+    }
+
+    /// Returns the node's world transform, recalculating dirty transforms first.
+    pub fn world_transform(&mut self, node: NodeId) -> Option<Affine2d> {
+        self.update_transforms();
+        self.node(node).map(|node| node.world)
+    }
+
+    /// Recalculates the world transforms of dirty nodes, and their descendants.
+    pub fn update_transforms(&mut self) {
+        self.update_order();
+        if !self.has_dirty_transforms {
+            return;
+        }
+        for index in 0..self.order.len() {
+            let node_id = self.order[index];
+            let node = self.node(node_id).expect("Ordered nodes should exist");
+            let parent = node.parent.map(|parent| {
+                let parent = self.node(parent).expect("Parents should exist");
+                (parent.world, parent.is_dirty)
+            });
+            let is_parent_dirty = parent.is_some_and(|(_, is_dirty)| is_dirty);
+            if !node.is_dirty && !is_parent_dirty {
+                continue;
+            }
+            let parent_world = parent.map_or(Affine2d::IDENTITY, |(world, _)| world);
+            let node = self.node_mut(node_id).expect("Ordered nodes should exist");
+            node.world = parent_world * node.local.to_affine();
+            // Stays dirty until the pass ends, so the children are recalculated too.
+            node.is_dirty = true;
+        }
+        for node_id in self.order.iter() {
+            if let Some(node) = self.slots[node_id.index as usize].node.as_mut() {
+                node.is_dirty = false;
+            }
+        }
+        self.has_dirty_transforms = false;
+    }
+
+    /// Returns every node, along with its data, and world transform, in hierarchy order.
+    ///
+    /// Dirty transforms are recalculated first.
+    pub fn iter(&mut self) -> impl Iterator<Item = (NodeId, &T, &Affine2d)> + '_ {
+        self.update_transforms();
+        let slots = &self.slots;
+        self.order.iter().map(move |node_id| {
+            let node = slots[node_id.index as usize]
+                .node
+                .as_ref()
+                .expect("Ordered nodes should exist");
+            (*node_id, &node.data, &node.world)
+        })
+    }
+
+    fn allocate(&mut self, parent: Option<NodeId>, local: Transform2d, data: T) -> NodeId {
+        let node = Node {
+            parent,
+            children: Vec::new(),
+            local,
+            world: Affine2d::IDENTITY,
+            is_dirty: true,
+            data,
+        };
+        let index = match self.free_indices.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node: None,
+                });
+                self.slots.len() as u32 - 1
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.node = Some(node);
+        self.len += 1;
+        self.is_order_dirty = true;
+        self.has_dirty_transforms = true;
+        NodeId {
+            index,
+            generation: slot.generation,
+        }
+    }
+
+    fn node(&self, node: NodeId) -> Option<&Node<T>> {
+        self.slots
+            .get(node.index as usize)
+            .filter(|slot| slot.generation == node.generation)
+            .and_then(|slot| slot.node.as_ref())
+    }
+
+    fn node_mut(&mut self, node: NodeId) -> Option<&mut Node<T>> {
+        self.slots
+            .get_mut(node.index as usize)
+            .filter(|slot| slot.generation == node.generation)
+            .and_then(|slot| slot.node.as_mut())
+    }
+
+    /// Removes the node from its parent's children, or from the roots.
+    fn detach(&mut self, node: NodeId, parent: Option<NodeId>) {
+        let siblings = match parent {
+            Some(parent) => {
+                &mut self
+                    .node_mut(parent)
+                    .expect("Parents should exist")
+                    .children
+            }
+            None => &mut self.roots,
+        };
+        siblings.retain(|sibling| *sibling != node);
+    }
+
+    fn is_ancestor_or_self(&self, ancestor: NodeId, mut node: NodeId) -> bool {
+        loop {
+            if node == ancestor {
+                return true;
+            }
+            match self.parent(node) {
+                Some(parent) => node = parent,
+                None => return false,
+            }
+        }
+    }
+
+    fn collect_subtree(&self, node: NodeId, nodes: &mut Vec<NodeId>) {
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            nodes.push(node);
+            stack.extend(self.children(node).iter().rev());
+        }
+    }
+
+    fn update_order(&mut self) {
+        if !self.is_order_dirty {
+            return;
+        }
+        let mut order = Vec::with_capacity(self.len);
+        for root in self.roots.iter() {
+            self.collect_subtree(*root, &mut order);
+        }
+        self.order = order;
+        self.is_order_dirty = false;
+    }
+}
+
+impl SceneGraph<Entity> {
+    /// Removes the node, and its descendants, then despawns their entities.
+    ///
+    /// Returns `false` if the node did not exist.
+    pub fn despawn_recursive(&mut self, node: NodeId, world: &mut World) -> bool {
+        match self.remove(node) {
+            Some(entities) => {
+                for entity in entities {
+                    world.despawn(entity);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod scene_graph_tests {
+    use super::*;
+
+    fn assert_close(actual: [f32; 2], expected: [f32; 2]) {
+        assert!(
+            (actual[0] - expected[0]).abs() < 1e-4 && (actual[1] - expected[1]).abs() < 1e-4,
+            "{:?} is not close to {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn should_propagate_transforms_to_descendants() {
+        let mut scene = SceneGraph::new();
+        let root = scene.insert(
+            Transform2d::from_translation(1.0, 0.0).with_scale(2.0, 2.0),
+            (),
+        );
+        let child = scene
+            .insert_child(root, Transform2d::from_translation(1.0, 0.0), ())
+            .unwrap();
+        let grandchild = scene
+            .insert_child(child, Transform2d::from_translation(0.0, 1.0), ())
+            .unwrap();
+
+        assert_close(
+            scene.world_transform(grandchild).unwrap().translation(),
+            [3.0, 2.0],
+        );
+
+        scene.local_mut(root).unwrap().translation = [0.0, 0.0];
+        scene.local_mut(child).unwrap().scale = [1.0, 0.5];
+
+        assert_close(
+            scene.world_transform(child).unwrap().translation(),
+            [2.0, 0.0],
+        );
+        assert_close(
+            scene.world_transform(grandchild).unwrap().translation(),
+            [2.0, 1.0],
+        );
+    }
+
+    #[test]
+    fn should_keep_the_world_transform_when_reparenting() {
+        let mut scene = SceneGraph::new();
+        let old_parent = scene.insert(Transform2d::from_translation(5.0, 5.0), ());
+        let new_parent = scene.insert(
+            Transform2d::from_translation(-3.0, 2.0)
+                .with_rotation(1.0)
+                .with_scale(2.0, 2.0),
+            (),
+        );
+        let node = scene
+            .insert_child(old_parent, Transform2d::from_translation(1.0, 0.0), ())
+            .unwrap();
+        let world_before = scene.world_transform(node).unwrap();
+
+        scene.set_parent(node, Some(new_parent)).unwrap();
+
+        assert_eq!(scene.parent(node), Some(new_parent));
+        assert_eq!(scene.children(new_parent), &[node]);
+        assert!(scene.children(old_parent).is_empty());
+        let world_after = scene.world_transform(node).unwrap();
+        assert_close(world_after.translation(), world_before.translation());
+        assert_close(
+            world_after.transform_point([1.0, 1.0]),
+            world_before.transform_point([1.0, 1.0]),
+        );
+
+        scene.set_parent(node, None).unwrap();
+        assert_eq!(scene.roots(), &[old_parent, new_parent, node]);
+        assert_close(scene.local(node).unwrap().translation, [6.0, 5.0]);
+    }
+
+    #[test]
+    fn should_keep_a_zero_x_scale_when_reparenting() {
+        let mut scene = SceneGraph::new();
+        let old_parent = scene.insert(Transform2d::from_translation(5.0, 5.0), ());
+        let new_parent = scene.insert(
+            Transform2d::from_translation(-3.0, 2.0)
+                .with_rotation(1.0)
+                .with_scale(2.0, 2.0),
+            (),
+        );
+        let node = scene
+            .insert_child(
+                old_parent,
+                Transform2d::from_translation(1.0, 0.0).with_scale(0.0, 3.0),
+                (),
+            )
+            .unwrap();
+        let world_before = scene.world_transform(node).unwrap();
+
+        scene.set_parent(node, Some(new_parent)).unwrap();
+
+        let world_after = scene.world_transform(node).unwrap();
+        assert_close(scene.local(node).unwrap().scale, [0.0, 1.5]);
+        assert_close(
+            world_after.transform_point([1.0, 1.0]),
+            world_before.transform_point([1.0, 1.0]),
+        );
+        assert_close(
+            world_after.transform_point([0.0, 1.0]),
+            world_before.transform_point([0.0, 1.0]),
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_parents() {
+        let mut scene = SceneGraph::new();
+        let root = scene.insert(Transform2d::IDENTITY, ());
+        let child = scene.insert_child(root, Transform2d::IDENTITY, ()).unwrap();
+        let flat = scene.insert(Transform2d::IDENTITY.with_scale(0.0, 1.0), ());
+        let removed = scene.insert(Transform2d::IDENTITY, ());
+        scene.remove(removed);
+
+        assert_eq!(
+            scene.set_parent(root, Some(child)),
+            Err(SceneGraphError::Cycle {
+                node: root,
+                parent: child
+            })
+        );
+        assert_eq!(
+            scene.set_parent(root, Some(root)),
+            Err(SceneGraphError::Cycle {
+                node: root,
+                parent: root
+            })
+        );
+        assert_eq!(
+            scene.set_parent(child, Some(flat)),
+            Err(SceneGraphError::NonInvertibleParent(flat))
+        );
+        assert_eq!(
+            scene.insert_child(removed, Transform2d::IDENTITY, ()),
+            Err(SceneGraphError::NoSuchNode(removed))
+        );
+        assert_eq!(scene.parent(child), Some(root));
+    }
+
+    #[test]
+    fn should_reject_parents_which_would_shear_the_node() {
+        let mut scene = SceneGraph::new();
+        let stretched = scene.insert(Transform2d::IDENTITY.with_scale(2.0, 1.0), ());
+        let rotated = scene.insert(Transform2d::IDENTITY.with_rotation(0.5), ());
+        let sheared = scene
+            .insert_child(stretched, Transform2d::IDENTITY.with_rotation(0.5), ())
+            .unwrap();
+
+        assert_eq!(
+            scene.set_parent(rotated, Some(stretched)),
+            Err(SceneGraphError::Shear {
+                node: rotated,
+                parent: Some(stretched)
+            })
+        );
+        assert_eq!(
+            scene.set_parent(sheared, None),
+            Err(SceneGraphError::Shear {
+                node: sheared,
+                parent: None
+            })
+        );
+        assert_eq!(scene.parent(rotated), None);
+        assert_eq!(scene.parent(sheared), Some(stretched));
+        assert_eq!(scene.roots(), &[stretched, rotated]);
+    }
+
+    #[test]
+    fn should_remove_nodes_recursively() {
+        let mut scene = SceneGraph::new();
+        let root = scene.insert(Transform2d::IDENTITY, "root");
+        let child = scene
+            .insert_child(root, Transform2d::IDENTITY, "child")
+            .unwrap();
+        scene
+            .insert_child(child, Transform2d::IDENTITY, "grandchild")
+            .unwrap();
+        scene
+            .insert_child(root, Transform2d::IDENTITY, "sibling")
+            .unwrap();
+        let other = scene.insert(Transform2d::IDENTITY, "other");
+
+        assert_eq!(scene.remove(child), Some(vec!["child", "grandchild"]));
+        assert_eq!(scene.len(), 3);
+        assert_eq!(scene.remove(root), Some(vec!["root", "sibling"]));
+        assert_eq!(scene.remove(root), None);
+
+        let reused = scene.insert(Transform2d::IDENTITY, "reused");
+        let reused_again = scene.insert(Transform2d::IDENTITY, "reused again");
+        assert_eq!(reused_again.index(), root.index());
+        assert_eq!(scene.get(root), None);
+        assert_eq!(scene.get(reused_again), Some(&"reused again"));
+        assert_eq!(scene.roots(), &[other, reused, reused_again]);
+    }
+
+    #[test]
+    fn should_iterate_in_hierarchy_order() {
+        let mut scene = SceneGraph::new();
+        let background = scene.insert(Transform2d::IDENTITY, "background");
+        let player = scene.insert(Transform2d::from_translation(1.0, 0.0), "player");
+        scene
+            .insert_child(player, Transform2d::from_translation(1.0, 0.0), "sword")
+            .unwrap();
+        scene
+            .insert_child(background, Transform2d::IDENTITY, "tree")
+            .unwrap();
+
+        let nodes: Vec<(&str, [f32; 2])> = scene
+            .iter()
+            .map(|(_node, name, world)| (*name, world.translation()))
+            .collect();
+
+        assert_eq!(
+            nodes,
+            vec![
+                ("background", [0.0, 0.0]),
+                ("tree", [0.0, 0.0]),
+                ("player", [1.0, 0.0]),
+                ("sword", [2.0, 0.0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_despawn_entities_recursively() {
+        let mut world = World::new();
+        let mut scene = SceneGraph::new();
+        let parent = world.spawn(());
+        let child = world.spawn(());
+        let unrelated = world.spawn(());
+        let parent_node = scene.insert(Transform2d::IDENTITY, parent);
+        scene
+            .insert_child(parent_node, Transform2d::IDENTITY, child)
+            .unwrap();
+
+        assert!(scene.despawn_recursive(parent_node, &mut world));
+
+        assert!(!scene.despawn_recursive(parent_node, &mut world));
+        assert_eq!(world.entities().collect::<Vec<_>>(), vec![unrelated]);
+        assert!(scene.is_empty());
+    }
+}
@@ -0,0 +1,14 @@
+//! Provides a 2D scene graph, with a transform hierarchy.
+//!
+//! A [`SceneGraph`] stores a tree of nodes, each with a [`Transform2d`] relative to its parent.
+//! World transforms are propagated down the tree as [`Affine2d`] matrices, and are only
+//! recalculated for nodes which have changed.
+//!
+//! The scene graph doesn't depend on the [ECS](crate::ecs), so it can be used on its own, or
+//! alongside a [`World`](crate::ecs::World) by storing an [`Entity`](crate::ecs::Entity) in
+//! each node.
+
+mod graph;
+pub use graph::*;
+mod transform;
+pub use transform::*;
@@ -0,0 +1,281 @@
+use std::ops::Mul;
+
+/// A 2D transform, made of a translation, a rotation, and a scale.
+///
+/// Transforms are applied in the order: scale, rotate, then translate.  Rotations are in
+/// radians, counter-clockwise.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Transform2d {
+    /// The position, relative to the parent.
+    pub translation: [f32; 2],
+    /// The rotation, in radians.
+    pub rotation: f32,
+    /// The scale along each axis.
+    pub scale: [f32; 2],
+}
+
+impl Default for Transform2d {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform2d {
+    /// A transform which doesn't change anything.
+    pub const IDENTITY: Self = Self {
+        translation: [0.0, 0.0],
+        rotation: 0.0,
+        scale: [1.0, 1.0],
+    };
+
+    /// Creates a transform with the provided translation.
+    pub fn from_translation(x: f32, y: f32) -> Self {
+        Self {
+            translation: [x, y],
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Sets the rotation, in radians.
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Sets the scale along each axis.
+    pub fn with_scale(mut self, x: f32, y: f32) -> Self {
+        self.scale = [x, y];
+        self
+    }
+
+    /// Converts the transform to a matrix.
+    pub fn to_affine(&self) -> Affine2d {
+        let (sin, cos) = self.rotation.sin_cos();
+        Affine2d {
+            matrix: [
+                [cos * self.scale[0], sin * self.scale[0]],
+                [-sin * self.scale[1], cos * self.scale[1]],
+            ],
+            translation: self.translation,
+        }
+    }
+}
+
+/// A 2D affine transformation matrix.
+///
+/// Combining [`Transform2d`]s with rotations, and non-uniform scales, can produce shearing,
+/// which a [`Transform2d`] can't represent, so world transforms are stored as matrices.
+///
+/// `a * b` produces a matrix which applies `b`, then `a`.
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_framework::scene::*;
+///
+/// let parent = Transform2d::from_translation(10.0, 0.0).with_scale(2.0, 2.0);
+/// let child = Transform2d::from_translation(1.0, 1.0);
+///
+/// let world = parent.to_affine() * child.to_affine();
+///
+/// assert_eq!(world.transform_point([0.0, 0.0]), [12.0, 2.0]);
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Affine2d {
+    /// The columns of the linear part of the matrix.
+    matrix: [[f32; 2]; 2],
+    translation: [f32; 2],
+}
+
+impl Default for Affine2d {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Affine2d {
+    /// A matrix which doesn't change anything.
+    pub const IDENTITY: Self = Self {
+        matrix: [[1.0, 0.0], [0.0, 1.0]],
+        translation: [0.0, 0.0],
+    };
+
+    /// Returns the translation part of the matrix.
+    pub fn translation(&self) -> [f32; 2] {
+        self.translation
+    }
+
+    /// Applies the matrix to a point.
+    pub fn transform_point(&self, point: [f32; 2]) -> [f32; 2] {
+        let [x_axis, y_axis] = self.matrix;
+        [
+            x_axis[0] * point[0] + y_axis[0] * point[1] + self.translation[0],
+            x_axis[1] * point[0] + y_axis[1] * point[1] + self.translation[1],
+        ]
+    }
+
+    /// Returns the determinant of the linear part of the matrix.
+    pub fn determinant(&self) -> f32 {
+        let [x_axis, y_axis] = self.matrix;
+        x_axis[0] * y_axis[1] - y_axis[0] * x_axis[1]
+    }
+
+    /// Returns the inverse of the matrix, or `None` if it can't be inverted, such as when it
+    /// has a scale of `0`.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.determinant();
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        let [x_axis, y_axis] = self.matrix;
+        let matrix = [
+            [y_axis[1] / determinant, -x_axis[1] / determinant],
+            [-y_axis[0] / determinant, x_axis[0] / determinant],
+        ];
+        let inverse = Self {
+            matrix,
+            translation: [0.0, 0.0],
+        };
+        let translation = inverse.transform_point(self.translation);
+        Some(Self {
+            matrix,
+            translation: [-translation[0], -translation[1]],
+        })
+    }
+
+    /// Returns `true` if the matrix shears, so it can't be split into a [`Transform2d`] exactly.
+    pub fn has_shear(&self) -> bool {
+        let [x_axis, y_axis] = self.matrix;
+        let dot = x_axis[0] * y_axis[0] + x_axis[1] * y_axis[1];
+        let lengths = x_axis[0].hypot(x_axis[1]) * y_axis[0].hypot(y_axis[1]);
+        dot.abs() > lengths * 1e-5
+    }
+
+    /// Splits the matrix into a [`Transform2d`].
+    ///
+    /// Any shearing is lost, which can be checked using [`Affine2d::has_shear()`].  If the x axis
+    /// is scaled to zero, the rotation is taken from the y axis instead.
+    pub fn to_transform(&self) -> Transform2d {
+        let [x_axis, y_axis] = self.matrix;
+        let scale_x = x_axis[0].hypot(x_axis[1]);
+        let (rotation, scale_y) = if scale_x == 0.0 {
+            ((-y_axis[0]).atan2(y_axis[1]), y_axis[0].hypot(y_axis[1]))
+        } else {
+            (x_axis[1].atan2(x_axis[0]), self.determinant() / scale_x)
+        };
+        Transform2d {
+            translation: self.translation,
+            rotation,
+            scale: [scale_x, scale_y],
+        }
+    }
+}
+
+impl Mul for Affine2d {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let [x_axis, y_axis] = rhs.matrix;
+        let origin = Self {
+            translation: [0.0, 0.0],
+            ..self
+        };
+        Self {
+            matrix: [
+                origin.transform_point(x_axis),
+                origin.transform_point(y_axis),
+            ],
+            translation: self.transform_point(rhs.translation),
+        }
+    }
+}
+
+#[cfg(test)]
+mod transform_tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    fn assert_close(actual: [f32; 2], expected: [f32; 2]) {
+        assert!(
+            (actual[0] - expected[0]).abs() < 1e-5 && (actual[1] - expected[1]).abs() < 1e-5,
+            "{:?} is not close to {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn should_scale_then_rotate_then_translate() {
+        let transform = Transform2d::from_translation(1.0, 2.0)
+            .with_rotation(FRAC_PI_2)
+            .with_scale(2.0, 1.0);
+
+        assert_close(
+            transform.to_affine().transform_point([1.0, 0.0]),
+            [1.0, 4.0],
+        );
+    }
+
+    #[test]
+    fn should_invert_matrices() {
+        let matrix = Transform2d::from_translation(3.0, -1.0)
+            .with_rotation(0.5)
+            .with_scale(2.0, 0.5)
+            .to_affine();
+        let point = [4.0, 7.0];
+
+        let inverse = matrix.inverse().unwrap();
+
+        assert_close(
+            inverse.transform_point(matrix.transform_point(point)),
+            point,
+        );
+        assert_eq!(
+            Transform2d::IDENTITY
+                .with_scale(0.0, 1.0)
+                .to_affine()
+                .inverse(),
+            None
+        );
+    }
+
+    #[test]
+    fn should_split_matrices_into_transforms() {
+        let transform = Transform2d::from_translation(3.0, -1.0)
+            .with_rotation(0.5)
+            .with_scale(2.0, 0.5);
+
+        let split = transform.to_affine().to_transform();
+
+        assert_close(split.translation, transform.translation);
+        assert_close(split.scale, transform.scale);
+        assert!((split.rotation - transform.rotation).abs() < 1e-5);
+        assert!(!transform.to_affine().has_shear());
+    }
+
+    #[test]
+    fn should_split_matrices_with_a_zero_x_scale() {
+        let transform = Transform2d::from_translation(3.0, -1.0)
+            .with_rotation(0.5)
+            .with_scale(0.0, 2.0);
+
+        let split = transform.to_affine().to_transform();
+
+        assert_close(split.scale, transform.scale);
+        assert!((split.rotation - transform.rotation).abs() < 1e-5);
+        assert_close(
+            split.to_affine().transform_point([1.0, 1.0]),
+            transform.to_affine().transform_point([1.0, 1.0]),
+        );
+    }
+
+    #[test]
+    fn should_detect_shearing() {
+        let stretched = Transform2d::IDENTITY.with_scale(2.0, 1.0).to_affine();
+        let rotated = Transform2d::IDENTITY.with_rotation(0.5).to_affine();
+
+        assert!((stretched * rotated).has_shear());
+        assert!(!(rotated * stretched).has_shear());
+    }
+}
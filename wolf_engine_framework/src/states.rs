@@ -0,0 +1,525 @@
+use std::any::type_name;
+
+use wolf_engine_core::prelude::*;
+
+use crate::*;
+
+/// A change to the [`StateStack`], requested by a [`State`].
+pub enum Transition<D> {
+    /// Pauses the current state, and pushes a new state on top of it.
+    Push(Box<dyn State<D>>),
+    /// Removes the current state, and resumes the state below it.
+    Pop,
+    /// Replaces the current state with a new state.
+    Switch(Box<dyn State<D>>),
+    /// Removes every state, and quits the engine.
+    Quit,
+}
+
+/// A [`Transition`] which may, or may not, have been requested.
+pub type OptionalTransition<D> = Option<Transition<D>>;
+
+/// Represents a single state of the game, such as a menu, a level, or a pause screen.
+///
+/// States live on a [`StateStack`].  Only the state on top of the stack is active:  It receives
+/// events, and is updated, and rendered, every frame.  States below the top are paused, unless
+/// the top state allows them to keep updating, or rendering, using
+/// [`State::updates_below()`], and [`State::renders_below()`].
+///
+/// # Examples
+///
+/// ```
+/// use wolf_engine_framework::*;
+/// use wolf_engine_core::prelude::*;
+///
+/// struct Countdown(u32);
+///
+/// impl State<()> for Countdown {
+///     fn update(&mut self, _context: &mut Context<()>) -> OptionalTransition<()> {
+///         self.0 -= 1;
+///         if self.0 == 0 {
+///             Some(Transition::Quit)
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// let framework = FrameworkBuilder::new(())
+///     .with_plugin(StateStack::with_state(Countdown(3)))
+///     .build()
+///     .unwrap();
+///
+/// framework.run(|_event, _context, _control_flow| ());
+/// ```
+pub trait State<D> {
+    /// Returns the name of the state, which is stored in the [`ActiveState`] resource while
+    /// this is the top state.
+    ///
+    /// Defaults to the name of the type.
+    fn name(&self) -> &'static str {
+        type_name::<Self>()
+    }
+
+    /// Called when the state is added to the stack.
+    fn on_enter(&mut self, _context: &mut Context<D>) {}
+
+    /// Called when the state is removed from the stack.
+    fn on_exit(&mut self, _context: &mut Context<D>) {}
+
+    /// Called when another state is pushed on top of this one.
+    fn on_pause(&mut self, _context: &mut Context<D>) {}
+
+    /// Called when this state becomes the top state again.
+    fn on_resume(&mut self, _context: &mut Context<D>) {}
+
+    /// Called for every event, other than frame phases, while this is the top state.
+    fn on_event(&mut self, _event: Event, _context: &mut Context<D>) -> OptionalTransition<D> {
+        None
+    }
+
+    /// Called once per frame to update the state.
+    fn update(&mut self, _context: &mut Context<D>) -> OptionalTransition<D> {
+        None
+    }
+
+    /// Called once per frame to render the state.
+    fn render(&mut self, _context: &mut Context<D>) {}
+
+    /// Returns `true` if the state below this one should keep updating while this is the top
+    /// state.
+    fn updates_below(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if the state below this one should keep rendering while this is the top
+    /// state, such as for a pause menu drawn over the game.
+    fn renders_below(&self) -> bool {
+        false
+    }
+}
+
+/// Names the top [`State`] of a [`StateStack`].
+///
+/// The stack keeps this up to date as a [resource](Context::resource()), so other parts of the
+/// game, such as [`RunCriteria::in_state()`](crate::ecs::RunCriteria::in_state()), can check
+/// which state is active.  The resource is removed when the stack is empty.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ActiveState {
+    name: &'static str,
+}
+
+impl ActiveState {
+    /// Returns the [name](State::name()) of the top state.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Provides a stack of [`State`]s, which changes using the [`Transition`]s returned by the top
+/// state.
+///
+/// Events are routed using [`StateStack::handle_event()`]:
+///
+/// - [`Event::EventsCleared`] updates, then renders, the states.
+/// - [`FramePhase::Update`], and [`FramePhase::Render`], update, or render, the states
+///   respectively, for engines using [frame phases](FramePhase).
+/// - Any other event is passed to the top state's [`State::on_event()`].
+///
+/// Only transitions returned by the top state are applied.  Transitions returned by states
+/// updating below the top state are ignored.  Once the last state is removed, the engine quits.
+///
+/// The stack is also a [`Plugin`], which routes every event to the stack, so it can be added
+/// directly to a [`FrameworkBuilder`].
+pub struct StateStack<D> {
+    states: Vec<Box<dyn State<D>>>,
+    pending: Vec<Transition<D>>,
+}
+
+impl<D> Default for StateStack<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> StateStack<D> {
+    /// Creates an empty stack.
+    pub fn new() -> Self {
+        Self {
+            states: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Creates a stack, which pushes the provided state when it handles its first event.
+    pub fn with_state<S: State<D> + 'static>(state: S) -> Self {
+        let mut state_stack = Self::new();
+        state_stack.pending.push(Transition::Push(Box::new(state)));
+        state_stack
+    }
+
+    /// Returns the number of states on the stack.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Returns `true` if there are no states on the stack.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Pauses the top state, then pushes the new state on top of it.
+    pub fn push(&mut self, mut state: Box<dyn State<D>>, context: &mut Context<D>) {
+        if let Some(top_state) = self.states.last_mut() {
+            top_state.on_pause(context);
+        }
+        state.on_enter(context);
+        self.states.push(state);
+        self.update_active_state(context);
+    }
+
+    /// Removes the top state, then resumes the state below it.
+    pub fn pop(&mut self, context: &mut Context<D>) {
+        if let Some(mut state) = self.states.pop() {
+            state.on_exit(context);
+            if let Some(top_state) = self.states.last_mut() {
+                top_state.on_resume(context);
+            }
+            self.update_active_state(context);
+        }
+    }
+
+    /// Replaces the top state with the new state.
+    pub fn switch(&mut self, mut state: Box<dyn State<D>>, context: &mut Context<D>) {
+        if let Some(mut old_state) = self.states.pop() {
+            old_state.on_exit(context);
+        }
+        state.on_enter(context);
+        self.states.push(state);
+        self.update_active_state(context);
+    }
+
+    /// Removes every state, from the top down.
+    pub fn clear(&mut self, context: &mut Context<D>) {
+        while let Some(mut state) = self.states.pop() {
+            state.on_exit(context);
+        }
+        self.update_active_state(context);
+    }
+
+    /// Applies a transition to the stack.
+    ///
+    /// The engine is told to quit once the stack is empty.
+    pub fn apply(&mut self, transition: Transition<D>, context: &mut Context<D>) {
+        match transition {
+            Transition::Push(state) => self.push(state, context),
+            Transition::Pop => self.pop(context),
+            Transition::Switch(state) => self.switch(state, context),
+            Transition::Quit => self.clear(context),
+        }
+        if self.states.is_empty() {
+            context.quit();
+        }
+    }
+
+    /// Routes an event to the states.
+    pub fn handle_event(&mut self, event: Event, context: &mut Context<D>) {
+        for transition in std::mem::take(&mut self.pending) {
+            self.apply(transition, context);
+        }
+        match event {
+            Event::EventsCleared => {
+                self.update(context);
+                self.render(context);
+            }
+            Event::FramePhase(FramePhase::Update) => self.update(context),
+            Event::FramePhase(FramePhase::Render) => self.render(context),
+            Event::FramePhase(_) => (),
+            event => {
+                let transition = self
+                    .states
+                    .last_mut()
+                    .and_then(|state| state.on_event(event, context));
+                if let Some(transition) = transition {
+                    self.apply(transition, context);
+                }
+            }
+        }
+    }
+
+    /// Updates the top state, along with any states below it which are allowed to update.
+    ///
+    /// States are updated from the bottom up.
+    pub fn update(&mut self, context: &mut Context<D>) {
+        let first_active = self.first_active_state(|state| state.updates_below());
+        let mut transition = None;
+        let top_index = self.states.len().saturating_sub(1);
+        for (index, state) in self.states.iter_mut().enumerate().skip(first_active) {
+            let state_transition = state.update(context);
+            if index == top_index {
+                transition = state_transition;
+            }
+        }
+        if let Some(transition) = transition {
+            self.apply(transition, context);
+        }
+    }
+
+    /// Renders the top state, along with any states below it which are allowed to render.
+    ///
+    /// States are rendered from the bottom up, so the top state is drawn last.
+    pub fn render(&mut self, context: &mut Context<D>) {
+        let first_active = self.first_active_state(|state| state.renders_below());
+        for state in self.states.iter_mut().skip(first_active) {
+            state.render(context);
+        }
+    }
+
+    fn update_active_state(&self, context: &mut Context<D>) {
+        match self.states.last() {
+            Some(state) => {
+                context.insert_resource(ActiveState { name: state.name() });
+            }
+            None => {
+                context.remove_resource::<ActiveState>().ok();
+            }
+        }
+    }
+
+    fn first_active_state<F>(&self, is_transparent: F) -> usize
+    where
+        F: Fn(&dyn State<D>) -> bool,
+    {
+        let mut first_active = self.states.len().saturating_sub(1);
+        while first_active > 0 && is_transparent(self.states[first_active].as_ref()) {
+            first_active -= 1;
+        }
+        first_active
+    }
+}
+
+impl<D: 'static> Plugin<D> for StateStack<D> {
+    fn name(&self) -> &'static str {
+        "state_stack"
+    }
+
+    fn build(&mut self, builder: &mut PluginBuilder<D>) {
+        let mut state_stack = std::mem::take(self);
+        builder.add_event_handler(move |event, context, _control_flow| {
+            state_stack.handle_event(event, context);
+        });
+    }
+}
+
+#[cfg(test)]
+mod state_stack_tests {
+    use ntest::timeout;
+    use wolf_engine_core::testing::CallLog;
+
+    use super::*;
+
+    struct TestState {
+        name: &'static str,
+        log: CallLog,
+        transitions: Vec<Transition<()>>,
+        updates_below: bool,
+        renders_below: bool,
+    }
+
+    impl TestState {
+        fn new(name: &'static str, log: &CallLog) -> Self {
+            Self {
+                name,
+                log: log.clone(),
+                transitions: Vec::new(),
+                updates_below: false,
+                renders_below: false,
+            }
+        }
+
+        fn with_transition(mut self, transition: Transition<()>) -> Self {
+            self.transitions.insert(0, transition);
+            self
+        }
+
+        fn record(&self, hook: &str) {
+            self.log.push(format!("{} {}", self.name, hook));
+        }
+    }
+
+    impl State<()> for TestState {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn on_enter(&mut self, _context: &mut Context<()>) {
+            self.record("enter");
+        }
+
+        fn on_exit(&mut self, _context: &mut Context<()>) {
+            self.record("exit");
+        }
+
+        fn on_pause(&mut self, _context: &mut Context<()>) {
+            self.record("pause");
+        }
+
+        fn on_resume(&mut self, _context: &mut Context<()>) {
+            self.record("resume");
+        }
+
+        fn on_event(&mut self, event: Event, _context: &mut Context<()>) -> OptionalTransition<()> {
+            self.record(&format!("{:?}", event));
+            None
+        }
+
+        fn update(&mut self, _context: &mut Context<()>) -> OptionalTransition<()> {
+            self.record("update");
+            self.transitions.pop()
+        }
+
+        fn render(&mut self, _context: &mut Context<()>) {
+            self.record("render");
+        }
+
+        fn updates_below(&self) -> bool {
+            self.updates_below
+        }
+
+        fn renders_below(&self) -> bool {
+            self.renders_below
+        }
+    }
+
+    #[test]
+    fn should_push_pop_and_switch_states() {
+        let log = CallLog::new();
+        let (_event_loop, mut context) = wolf_engine_core::init(());
+        let mut state_stack = StateStack::new();
+
+        state_stack.push(Box::new(TestState::new("game", &log)), &mut context);
+        state_stack.push(Box::new(TestState::new("menu", &log)), &mut context);
+        state_stack.switch(Box::new(TestState::new("options", &log)), &mut context);
+        state_stack.pop(&mut context);
+
+        assert_eq!(
+            log.take(),
+            vec![
+                "game enter",
+                "game pause",
+                "menu enter",
+                "menu exit",
+                "options enter",
+                "options exit",
+                "game resume",
+            ]
+        );
+        assert_eq!(state_stack.len(), 1);
+    }
+
+    #[test]
+    fn should_keep_the_active_state_resource_up_to_date() {
+        let log = CallLog::new();
+        let (_event_loop, mut context) = wolf_engine_core::init(());
+        let mut state_stack = StateStack::new();
+
+        state_stack.push(Box::new(TestState::new("game", &log)), &mut context);
+        state_stack.push(Box::new(TestState::new("menu", &log)), &mut context);
+        assert_eq!(context.resource::<ActiveState>().unwrap().name(), "menu");
+
+        state_stack.pop(&mut context);
+        assert_eq!(context.resource::<ActiveState>().unwrap().name(), "game");
+
+        state_stack.clear(&mut context);
+        assert!(!context.has_resource::<ActiveState>());
+    }
+
+    #[test]
+    fn should_route_events_to_the_top_state() {
+        let log = CallLog::new();
+        let (_event_loop, mut context) = wolf_engine_core::init(());
+        let mut state_stack = StateStack::new();
+        state_stack.push(Box::new(TestState::new("game", &log)), &mut context);
+        state_stack.push(Box::new(TestState::new("menu", &log)), &mut context);
+        log.take();
+
+        state_stack.handle_event(Event::MainThreadTaskQueued, &mut context);
+        state_stack.handle_event(Event::EventsCleared, &mut context);
+
+        assert_eq!(
+            log.take(),
+            vec!["menu MainThreadTaskQueued", "menu update", "menu render"]
+        );
+    }
+
+    #[test]
+    fn should_let_states_below_update_and_render() {
+        let log = CallLog::new();
+        let (_event_loop, mut context) = wolf_engine_core::init(());
+        let mut state_stack = StateStack::new();
+        let mut pause_menu = TestState::new("pause", &log);
+        pause_menu.renders_below = true;
+        state_stack.push(Box::new(TestState::new("game", &log)), &mut context);
+        state_stack.push(Box::new(pause_menu), &mut context);
+        log.take();
+
+        state_stack.handle_event(Event::FramePhase(FramePhase::Update), &mut context);
+        state_stack.handle_event(Event::FramePhase(FramePhase::Render), &mut context);
+
+        assert_eq!(
+            log.take(),
+            vec!["pause update", "game render", "pause render"]
+        );
+    }
+
+    #[test]
+    fn should_only_apply_transitions_from_the_top_state() {
+        let log = CallLog::new();
+        let (_event_loop, mut context) = wolf_engine_core::init(());
+        let mut state_stack = StateStack::new();
+        let mut overlay = TestState::new("overlay", &log);
+        overlay.updates_below = true;
+        state_stack.push(
+            Box::new(TestState::new("game", &log).with_transition(Transition::Pop)),
+            &mut context,
+        );
+        state_stack.push(Box::new(overlay), &mut context);
+
+        state_stack.update(&mut context);
+
+        assert_eq!(state_stack.len(), 2);
+    }
+
+    #[test]
+    #[timeout(1000)]
+    fn should_quit_when_the_stack_is_empty() {
+        let log = CallLog::new();
+        let menu = TestState::new("menu", &log).with_transition(Transition::Pop);
+        let game = TestState::new("game", &log)
+            .with_transition(Transition::Push(Box::new(menu)))
+            .with_transition(Transition::Quit);
+        let framework = FrameworkBuilder::new(())
+            .with_plugin(StateStack::with_state(game))
+            .build()
+            .unwrap();
+
+        framework.run(|_event, _context, _control_flow| ());
+
+        assert_eq!(
+            log.take(),
+            vec![
+                "game enter",
+                "game update",
+                "game pause",
+                "menu enter",
+                "menu render",
+                "menu update",
+                "menu exit",
+                "game resume",
+                "game render",
+                "game update",
+                "game exit",
+            ]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use wolf_engine_core::prelude::*;
+use wolf_engine_framework::hot_reload::*;
+
+pub struct FixtureLogic;
+
+#[cfg(not(feature = "v2"))]
+impl GameLogic<Vec<String>> for FixtureLogic {
+    const STATE_VERSION: u32 = 1;
+
+    fn on_load(context: &mut Context<Vec<String>>) {
+        context.data.push("v1 load".to_string());
+    }
+
+    fn on_unload(context: &mut Context<Vec<String>>) {
+        context.data.push("v1 unload".to_string());
+    }
+
+    fn handle_event(event: Event, context: &mut Context<Vec<String>>) {
+        if event == Event::EventsCleared {
+            context.data.push("v1 event".to_string());
+        }
+    }
+}
+
+#[cfg(feature = "v2")]
+impl GameLogic<Vec<String>> for FixtureLogic {
+    const STATE_VERSION: u32 = 2;
+
+    fn on_load(context: &mut Context<Vec<String>>) {
+        context.data.push("v2 load".to_string());
+    }
+
+    fn handle_event(event: Event, context: &mut Context<Vec<String>>) {
+        if event == Event::EventsCleared {
+            context.data.push("v2 event".to_string());
+        }
+    }
+
+    fn migrate_state(data: &mut Vec<String>, from_version: u32) -> Result<(), String> {
+        data.push(format!("v2 migrate from {}", from_version));
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "no_export"))]
+wolf_engine_framework::export_game_logic!(Vec<String>, FixtureLogic);
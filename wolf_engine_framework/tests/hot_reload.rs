@@ -0,0 +1,149 @@
+#[cfg(test)]
+#[cfg(feature = "hot_reload")]
+mod hot_reload_tests {
+    use std::env::consts::{DLL_PREFIX, DLL_SUFFIX};
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use std::time::Duration;
+
+    use wolf_engine_core::prelude::*;
+    use wolf_engine_framework::assets::FileWatcher;
+    use wolf_engine_framework::hot_reload::*;
+
+    const FIXTURE: &str = "game_logic_fixture";
+
+    /// Builds the fixture library, with the host's features, and returns its path.
+    fn build_fixture(features: &[&str]) -> PathBuf {
+        let manifest_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("game_logic")
+            .join("Cargo.toml");
+        let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("game_logic_fixture");
+        let mut features = features.to_vec();
+        for (is_enabled, feature) in [
+            (cfg!(feature = "config"), "config"),
+            (cfg!(feature = "logging"), "logging"),
+            (cfg!(feature = "serde"), "serde"),
+        ] {
+            if is_enabled {
+                features.push(feature);
+            }
+        }
+        let mut command = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
+        command
+            .arg("build")
+            .arg("--quiet")
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .arg("--no-default-features")
+            .arg("--features")
+            .arg(features.join(","));
+        if !cfg!(debug_assertions) {
+            command.arg("--release");
+        }
+        let status = command.status().expect("Failed to run cargo");
+        assert!(status.success(), "Failed to build the fixture library");
+        let profile = if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        };
+        target_dir
+            .join(profile)
+            .join(format!("{}{}{}", DLL_PREFIX, FIXTURE, DLL_SUFFIX))
+    }
+
+    /// Returns the copies of the fixture library made by this process.
+    fn library_copies() -> Vec<PathBuf> {
+        let prefix = format!("wolf_engine_{}_", std::process::id());
+        let suffix = format!("{}{}{}", DLL_PREFIX, FIXTURE, DLL_SUFFIX);
+        std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let file_name = path.file_name().unwrap().to_string_lossy();
+                file_name.starts_with(&prefix) && file_name.ends_with(&suffix)
+            })
+            .collect()
+    }
+
+    /// Waits until the library's modification time is visibly different from its current one.
+    fn wait_for_a_new_modification_time(path: &Path) {
+        let modified = std::fs::metadata(path).unwrap().modified().unwrap();
+        while std::time::SystemTime::now() <= modified + Duration::from_millis(10) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn should_load_and_reload_a_game_library() {
+        let library_path = build_fixture(&[]);
+        let (_event_loop, mut context) = wolf_engine_core::init(Vec::<String>::new());
+
+        let mut game_library = unsafe { GameLibrary::<Vec<String>>::open(&library_path) }
+            .unwrap()
+            .with_file_watcher(
+                FileWatcher::new()
+                    .with_poll_interval(Duration::ZERO)
+                    .with_debounce(Duration::ZERO),
+            );
+        let first_copy = library_copies();
+        assert_eq!(
+            first_copy.len(),
+            1,
+            "The library should be loaded from a copy"
+        );
+        game_library.handle_event(Event::EventsCleared, &mut context);
+        game_library.reload_if_changed(&mut context);
+        assert_eq!(game_library.state_version(), 1);
+
+        wait_for_a_new_modification_time(&library_path);
+        build_fixture(&["v2"]);
+        Subsystem::on_frame(&mut game_library, &mut context);
+        game_library.handle_event(Event::EventsCleared, &mut context);
+
+        assert_eq!(game_library.state_version(), 2);
+        let second_copy = library_copies();
+        assert_eq!(second_copy.len(), 1, "The old copy should be removed");
+        assert_ne!(second_copy, first_copy);
+
+        wait_for_a_new_modification_time(&library_path);
+        build_fixture(&["v2", "no_export"]);
+        Subsystem::on_frame(&mut game_library, &mut context);
+        game_library.handle_event(Event::EventsCleared, &mut context);
+
+        assert_eq!(
+            game_library.state_version(),
+            2,
+            "A library without the entry point should be refused"
+        );
+        assert_eq!(library_copies(), second_copy);
+        let error = unsafe { GameLibrary::<Vec<String>>::open(&library_path) }
+            .err()
+            .unwrap();
+        assert!(matches!(error, HotReloadError::Load { .. }), "{}", error);
+        assert!(error.to_string().contains(ENTRY_POINT), "{}", error);
+
+        game_library.stop(&mut context);
+        drop(game_library);
+
+        assert!(library_copies().is_empty());
+        assert_eq!(
+            context.data,
+            vec![
+                "v1 load",
+                "v1 event",
+                "v1 unload",
+                "v2 migrate from 1",
+                "v2 load",
+                "v2 event",
+                "v2 event",
+            ],
+            "Data created by unloaded libraries should stay valid"
+        );
+    }
+}
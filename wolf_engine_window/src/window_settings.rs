@@ -14,8 +14,11 @@ pub enum FullscreenMode {
 }
 
 /// Provides the settings used to create a window.
+///
+/// When deserializing, missing settings are filled in using [`WindowSettings::default()`].
 #[derive(Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct WindowSettings {
     /// The desired title for the window.
     pub title: String,
@@ -180,6 +183,22 @@ mod window_settings_tests {
         assert_eq!(window_settings.title, "Hello, world");
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_use_defaults_for_missing_settings() {
+        let toml_str = r#"
+            width = 800
+            fullscreen_mode = "Borderless"
+        "#;
+        let window_settings: WindowSettings = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            window_settings,
+            WindowSettings::default()
+                .with_size((800, 720))
+                .with_borderless_fullscreen()
+        );
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn should_implement_serialize() {